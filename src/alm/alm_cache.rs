@@ -1,3 +1,5 @@
+use alloc::{vec, vec::Vec};
+
 use crate::panoc::PANOCCache;
 use crate::OptFloat;
 
@@ -46,7 +48,7 @@ where
     /// corresponds to an unspecified available time, i.e., there are
     /// no bounds on the maximum time). The maximum time is specified,
     /// if at all, in `AlmOptimizer`
-    pub(crate) available_time: Option<std::time::Duration>,
+    pub(crate) available_time: Option<core::time::Duration>,
 }
 
 impl<T> AlmCache<T>
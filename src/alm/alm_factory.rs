@@ -1,3 +1,5 @@
+use alloc::vec;
+
 use crate::OptFloat;
 /* ---------------------------------------------------------------------------- */
 /* ALM FACTORY                                                                  */
@@ -91,7 +93,7 @@ pub struct AlmFactory<
     jacobian_mapping_f2_trans: Option<JacobianMappingF2Trans>,
     set_c: Option<SetC>,
     n2: usize,
-    _t: std::marker::PhantomData<T>,
+    _t: core::marker::PhantomData<T>,
 }
 
 impl<
@@ -196,7 +198,7 @@ where
             jacobian_mapping_f2_trans,
             set_c,
             n2,
-            _t: std::marker::PhantomData,
+            _t: core::marker::PhantomData,
         }
     }
 
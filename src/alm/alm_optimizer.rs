@@ -1,6 +1,8 @@
+use alloc::vec::Vec;
+
 use crate::alm::*;
 use crate::core::panoc::PANOCOptimizer;
-use crate::core::{ExitStatus, Optimizer, Problem, SolverStatus};
+use crate::core::{clock::Stopwatch, Clock, ExitStatus, Optimizer, Problem, SolverStatus};
 use crate::{constraints, matrix_operations, FunctionCallResult, OptFloat, SolverError};
 
 const DEFAULT_MAX_OUTER_ITERATIONS: usize = 50;
@@ -150,7 +152,9 @@ pub struct AlmOptimizer<
     /// Maximum number of inner iterations
     max_inner_iterations: usize,
     /// Maximum duration
-    max_duration: Option<std::time::Duration>,
+    max_duration: Option<core::time::Duration>,
+    /// Clock used to measure the solution time (if not set, the system clock is used)
+    clock: Option<&'life dyn Clock>,
     /// epsilon for inner AKKT condition
     epsilon_tolerance: T,
     /// delta for outer AKKT condition
@@ -197,7 +201,7 @@ where
     ConstraintsType: constraints::Constraint<T>,
     AlmSetC: constraints::Constraint<T>,
     LagrangeSetY: constraints::Constraint<T>,
-    T: OptFloat + std::fmt::Debug,
+    T: OptFloat,
 {
     /* ---------------------------------------------------------------------------- */
     /*          CONSTRUCTOR                                                         */
@@ -277,6 +281,7 @@ where
             max_outer_iterations: DEFAULT_MAX_OUTER_ITERATIONS,
             max_inner_iterations: DEFAULT_MAX_INNER_ITERATIONS,
             max_duration: None,
+            clock: None,
             epsilon_tolerance: T::from(DEFAULT_EPSILON_TOLERANCE).unwrap(),
             delta_tolerance: T::from(DEFAULT_DELTA_TOLERANCE).unwrap(),
             penalty_update_factor: T::from(DEFAULT_PENALTY_UPDATE_FACTOR).unwrap(),
@@ -352,11 +357,32 @@ where
     ///
     /// Returns the current mutable and updated instance of the provided object
     ///
-    pub fn with_max_duration(mut self, max_duration: std::time::Duration) -> Self {
+    pub fn with_max_duration(mut self, max_duration: core::time::Duration) -> Self {
         self.max_duration = Some(max_duration);
         self
     }
 
+    /// Sets the clock which is used to measure the solution time
+    ///
+    /// By default, the system clock is used, which requires the feature
+    /// `std`; without it, and unless a clock is provided, the solution time is
+    /// reported as zero and the maximum duration has no effect (see
+    /// [Clock](../core/clock/trait.Clock.html)). The clock is also used by the
+    /// inner solver.
+    ///
+    /// # Arguments
+    ///
+    /// - `clock`: clock (e.g., a hardware timer)
+    ///
+    /// # Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_clock(mut self, clock: &'life dyn Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     /// Set the delta tolerance
     ///
     /// # Arguments
@@ -736,10 +762,13 @@ where
             .with_max_duration(
                 alm_cache
                     .available_time
-                    .unwrap_or_else(|| std::time::Duration::from_secs(std::u64::MAX)),
+                    .unwrap_or_else(|| core::time::Duration::from_secs(u64::MAX)),
             )
             // Set the maximum number of inner iterations
             .with_max_iter(self.max_inner_iterations);
+        if let Some(clock) = self.clock {
+            inner_solver = inner_solver.with_clock(clock);
+        }
         // this method returns the result of .solve:
         inner_solver.solve(u)
     }
@@ -893,8 +922,8 @@ where
         /* WORK IN PROGRESS */
         let alm_problem = &self.alm_problem; // Problem
         let alm_cache = &mut self.alm_cache; // ALM Cache
-        let mut empty_vec = std::vec::Vec::new(); // Empty vector
-        let xi: &mut std::vec::Vec<T> = alm_cache.xi.as_mut().unwrap_or(&mut empty_vec);
+        let mut empty_vec = Vec::new(); // Empty vector
+        let xi: &mut Vec<T> = alm_cache.xi.as_mut().unwrap_or(&mut empty_vec);
         let mut __c: T = T::zero();
         if !xi.is_empty() {
             __c = xi[0];
@@ -917,11 +946,10 @@ where
     ///
     pub fn solve(&mut self, u: &mut [T]) -> Result<AlmOptimizerStatus<T>, SolverError>
     where
-        T: OptFloat + std::fmt::Debug,
+        T: OptFloat,
     {
         let mut num_outer_iterations = 0;
-        // let tic = std::time::Instant::now();
-        let tic = instant::Instant::now();
+        let tic = Stopwatch::start(self.clock);
         let mut exit_status = ExitStatus::Converged;
         self.alm_cache.reset(); // first, reset the cache
         self.alm_cache.available_time = self.max_duration;
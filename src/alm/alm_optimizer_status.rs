@@ -1,3 +1,5 @@
+use alloc::{vec, vec::Vec};
+
 use crate::core::{ExitStatus, OptFloat};
 /// Solution statistics for `AlmOptimizer`
 ///
@@ -24,7 +26,7 @@ where
     ///
     lagrange_multipliers: Option<Vec<T>>,
     /// Total solve time
-    solve_time: std::time::Duration,
+    solve_time: core::time::Duration,
     /// Last value of penalty parameter
     penalty: T,
     /// A measure of infeasibility of constraints F1(u; p) in C
@@ -67,7 +69,7 @@ where
             num_inner_iterations: 0,
             last_problem_norm_fpr: T::from(-1.0).unwrap(),
             lagrange_multipliers: None,
-            solve_time: std::time::Duration::from_nanos(0),
+            solve_time: core::time::Duration::from_nanos(0),
             penalty: T::zero(),
             delta_y_norm: T::zero(),
             f2_norm: T::zero(),
@@ -85,7 +87,7 @@ where
     ///
     /// Does not panic
     ///
-    pub(crate) fn with_solve_time(mut self, duration: std::time::Duration) -> Self {
+    pub(crate) fn with_solve_time(mut self, duration: core::time::Duration) -> Self {
         self.solve_time = duration;
         self
     }
@@ -277,7 +279,7 @@ where
     ///
     /// Does not panic
     ///
-    pub fn solve_time(&self) -> std::time::Duration {
+    pub fn solve_time(&self) -> core::time::Duration {
         self.solve_time
     }
 
@@ -71,7 +71,7 @@ pub struct AlmProblem<
     /// number of PM-type parameters (range dim of F2)
     pub(crate) n2: usize,
 
-    _t: std::marker::PhantomData<T>,
+    _t: core::marker::PhantomData<T>,
 }
 
 impl<
@@ -171,7 +171,7 @@ where
             mapping_f2,
             n1,
             n2,
-            _t: std::marker::PhantomData,
+            _t: core::marker::PhantomData,
         }
     }
 }
@@ -1,5 +1,6 @@
-use super::{Constraint, Simplex};
+use super::Constraint;
 use crate::core::OptFloat;
+use crate::SolverError;
 
 #[derive(Copy, Clone)]
 /// A norm-1 ball, that is, a set given by $B_1^r = \\{x \in \mathbb{R}^n {}:{} \Vert{}x{}\Vert_1 \leq r\\}$
@@ -10,7 +11,6 @@ where
 {
     center: Option<&'a [T]>,
     radius: T,
-    simplex: Simplex<T>,
 }
 
 impl<'a, T> Ball1<'a, T>
@@ -21,27 +21,63 @@ where
     /// If no `center` is given, then it is assumed to be in the origin
     pub fn new(center: Option<&'a [T]>, radius: T) -> Self {
         assert!(radius > T::zero());
-        let simplex = Simplex::new(radius);
-        Ball1 {
-            center,
-            radius,
-            simplex,
+        Ball1 { center, radius }
+    }
+
+    /// Construct a new ball-1 like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `radius` is not
+    /// positive
+    pub fn try_new(center: Option<&'a [T]>, radius: T) -> Result<Self, SolverError> {
+        if radius > T::zero() {
+            Ok(Ball1::new(center, radius))
+        } else {
+            Err(SolverError::InvalidArgument)
         }
     }
 
+    /// Radius of the ball
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    /// Center of the ball, or `None` if it is centered at the origin
+    pub fn center(&self) -> Option<&'a [T]> {
+        self.center
+    }
+
+    /// Projects on the ball centered at the origin by soft-thresholding,
+    /// $x_i \mapsto \mathrm{sign}(x_i)\max\\{|x_i| - \tau, 0\\}$, where the
+    /// threshold $\tau$ is computed in place using Michelot's algorithm
     fn project_on_ball1_centered_at_origin(&self, x: &mut [T]) {
-        if crate::matrix_operations::norm1(x) > self.radius {
-            // u = |x| (copied)
-            let mut u = vec![T::zero(); x.len()];
-            u.iter_mut()
-                .zip(x.iter())
-                .for_each(|(ui, &xi)| *ui = xi.abs());
-            // u = P_simplex(u)
-            self.simplex.project(&mut u);
-            x.iter_mut()
-                .zip(u.iter())
-                .for_each(|(xi, &ui)| *xi = xi.signum() * ui);
+        let norm1_x = crate::matrix_operations::norm1(x);
+        if norm1_x <= self.radius {
+            return;
+        }
+
+        // tau = (sum of active |x_i| - radius) / (number of active entries),
+        // where the active entries are those with |x_i| > tau; starting with
+        // all entries active, tau increases monotonically and the number of
+        // active entries decreases until it no longer changes
+        let mut num_active = x.len();
+        let mut tau = (norm1_x - self.radius) / T::from(num_active).unwrap();
+        loop {
+            let (sum_active, count_active) =
+                x.iter().fold((T::zero(), 0_usize), |(sum, count), &xi| {
+                    if xi.abs() > tau {
+                        (sum + xi.abs(), count + 1)
+                    } else {
+                        (sum, count)
+                    }
+                });
+            if count_active == 0 || count_active == num_active {
+                break;
+            }
+            num_active = count_active;
+            tau = (sum_active - self.radius) / T::from(num_active).unwrap();
         }
+
+        x.iter_mut()
+            .for_each(|xi| *xi = xi.signum() * T::zero().max(xi.abs() - tau));
     }
 }
 
@@ -66,4 +102,8 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
 }
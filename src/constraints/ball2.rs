@@ -1,5 +1,6 @@
 use super::Constraint;
 use crate::core::OptFloat;
+use crate::SolverError;
 
 #[derive(Copy, Clone)]
 /// A Euclidean ball, that is, a set given by $B_2^r = \\{x \in \mathbb{R}^n {}:{} \Vert{}x{}\Vert \leq r\\}$
@@ -23,6 +24,40 @@ where
 
         Ball2 { center, radius }
     }
+
+    /// Construct a new Euclidean ball like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `radius` is not
+    /// positive
+    pub fn try_new(center: Option<&'a [T]>, radius: T) -> Result<Self, SolverError> {
+        if radius > T::zero() {
+            Ok(Ball2::new(center, radius))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
+    }
+
+    /// Radius of the ball
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    /// Center of the ball, or `None` if it is centered at the origin
+    pub fn center(&self) -> Option<&'a [T]> {
+        self.center
+    }
+
+    /// Construct a new Euclidean ball with the same center and radius
+    /// `factor` times the radius of this ball (e.g., to shrink or grow a
+    /// trust region)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `factor` is not positive
+    ///
+    pub fn scaled(&self, factor: T) -> Self {
+        assert!(factor > T::zero(), "factor must be positive");
+        Ball2::new(self.center, self.radius * factor)
+    }
 }
 
 impl<'a, T> Constraint<T> for Ball2<'a, T>
@@ -31,13 +66,7 @@ where
 {
     fn project(&self, x: &mut [T]) {
         if let Some(center) = &self.center {
-            let mut norm_difference = T::zero();
-            x.iter().zip(center.iter()).for_each(|(a, b)| {
-                let diff_ = *a - *b;
-                norm_difference += diff_ * diff_;
-            });
-
-            norm_difference = norm_difference.sqrt();
+            let norm_difference = crate::matrix_operations::norm2_diff(x, center);
 
             if norm_difference > self.radius {
                 x.iter_mut().zip(center.iter()).for_each(|(x, c)| {
@@ -56,4 +85,16 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
+
+    /// Support function of the ball, $\sigma_C(d) = \langle d, x_c \rangle + r\Vert{}d{}\Vert$
+    fn support(&self, d: &[T]) -> Option<T> {
+        let center_term = self.center.map_or(T::zero(), |center| {
+            crate::matrix_operations::inner_product(d, center)
+        });
+        Some(center_term + self.radius * crate::matrix_operations::norm2(d))
+    }
 }
@@ -0,0 +1,102 @@
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::SolverError;
+
+#[derive(Copy, Clone)]
+/// The complement of an open Euclidean ball, that is, a set given by
+/// $C_2^r = \\{x \in \mathbb{R}^n {}:{} \Vert{}x{}\Vert \geq r\\}$, or, for
+/// a ball centered at a point $x_c$,
+/// $C_2^{x_c, r} = \\{x \in \mathbb{R}^n {}:{} \Vert{}x-x_c{}\Vert \geq r\\}$
+///
+/// This set is useful to model obstacles which need to be avoided.
+pub struct BallComplement<'a, T>
+where
+    T: OptFloat,
+{
+    center: Option<&'a [T]>,
+    radius: T,
+}
+
+impl<'a, T> BallComplement<'a, T>
+where
+    T: OptFloat,
+{
+    /// Construct the complement of a Euclidean ball with given center and
+    /// radius; if no `center` is given, then it is assumed to be in the origin
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `radius` is not positive
+    ///
+    pub fn new(center: Option<&'a [T]>, radius: T) -> Self {
+        assert!(radius > T::zero());
+
+        BallComplement { center, radius }
+    }
+
+    /// Construct a new ball complement like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `radius` is not
+    /// positive
+    pub fn try_new(center: Option<&'a [T]>, radius: T) -> Result<Self, SolverError> {
+        if radius > T::zero() {
+            Ok(BallComplement::new(center, radius))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
+    }
+}
+
+impl<'a, T> Constraint<T> for BallComplement<'a, T>
+where
+    T: OptFloat,
+{
+    /// Projection onto the complement of the ball with radius $r$ and center
+    /// $c$. Points outside the ball are not modified, whereas points
+    /// $x \neq c$ inside the ball are pushed radially to the boundary,
+    ///
+    /// $$
+    /// P(x) = c + r\frac{x-c}{\Vert{}x-c\Vert_2}.
+    /// $$
+    ///
+    /// For $x=c$, the projection is multi-valued and $c + r e_1$ is returned,
+    /// where $e_1$ is the first unit vector.
+    ///
+    /// ## Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the projection on the set
+    ///
+    fn project(&self, x: &mut [T]) {
+        let epsilon = T::from(1e-12).unwrap();
+        if let Some(center) = &self.center {
+            let norm_difference = crate::matrix_operations::norm2_diff(x, center);
+            if norm_difference >= self.radius {
+                return;
+            }
+            if norm_difference <= epsilon {
+                x.copy_from_slice(center);
+                x[0] += self.radius;
+                return;
+            }
+            x.iter_mut().zip(center.iter()).for_each(|(x, c)| {
+                *x = *c + self.radius * (*x - *c) / norm_difference;
+            });
+        } else {
+            let norm_x = crate::matrix_operations::norm2(x);
+            if norm_x >= self.radius {
+                return;
+            }
+            if norm_x <= epsilon {
+                x[0] += self.radius;
+                return;
+            }
+            let radius_over_norm = self.radius / norm_x;
+            x.iter_mut().for_each(|x_| *x_ *= radius_over_norm);
+        }
+    }
+
+    /// Returns false (the complement of a ball is not a convex set)
+    ///
+    fn is_convex(&self) -> bool {
+        false
+    }
+}
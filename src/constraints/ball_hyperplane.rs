@@ -0,0 +1,163 @@
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+#[derive(Copy, Clone)]
+/// The intersection of a Euclidean ball and a hyperplane, that is, a set of
+/// the form
+/// $C = \\{x \in \mathbb{R}^n {}:{} \Vert{}x-x_c{}\Vert \leq r, \langle c, x\rangle = b\\}$
+///
+/// The hyperplane $H = \\{x {}:{} \langle c, x\rangle = b\\}$ slices the ball
+/// along a lower-dimensional ball of $H$, which is centered at the projection
+/// of $x_c$ on $H$, $x_c' = x_c - t_c c$ with
+/// $t_c = (\langle c, x_c\rangle - b)/\Vert{}c{}\Vert^2$, and has radius
+/// $r' = \sqrt{r^2 - t_c^2 \Vert{}c{}\Vert^2}$. Since $H$ is an affine
+/// subspace, the projection on $C$ is obtained in closed form by projecting
+/// on $H$ and then on this lower-dimensional ball.
+///
+pub struct BallHyperplane<'a, T>
+where
+    T: OptFloat,
+{
+    center: Option<&'a [T]>,
+    normal_vector: &'a [T],
+    offset: T,
+    /// squared Euclidean norm of the normal vector
+    normal_vector_squared_norm: T,
+    /// $t_c$, so that the center of the slice is $x_c - t_c c$
+    center_shift: T,
+    /// radius of the slice of the ball, $r'$
+    slice_radius: T,
+}
+
+impl<'a, T> BallHyperplane<'a, T>
+where
+    T: OptFloat,
+{
+    /// Construct the intersection of a Euclidean ball and a hyperplane
+    ///
+    /// # Arguments
+    ///
+    /// - `center`: center of the ball, $x_c$; if `None`, the ball is centered
+    ///   at the origin
+    /// - `radius`: radius of the ball, $r$
+    /// - `normal_vector`: normal vector of the hyperplane, $c$
+    /// - `offset`: offset of the hyperplane, $b$
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `radius` is not positive, if `normal_vector` is
+    /// zero, if `center` and `normal_vector` have different dimensions, or if
+    /// the hyperplane does not intersect the ball
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{BallHyperplane, Constraint};
+    ///
+    /// let normal_vector = [0.0, 0.0, 1.0];
+    /// let set = BallHyperplane::new(None, 2.0, &normal_vector, 1.0);
+    /// let mut x = [3.0, 0.0, 5.0];
+    /// set.project(&mut x);
+    /// assert!((x[0] - 3.0_f64.sqrt()).abs() < 1e-12 && x[2] == 1.0);
+    /// ```
+    ///
+    pub fn new(center: Option<&'a [T]>, radius: T, normal_vector: &'a [T], offset: T) -> Self {
+        assert!(radius > T::zero(), "radius must be positive");
+        let normal_vector_squared_norm = matrix_operations::norm2_squared(normal_vector);
+        assert!(
+            normal_vector_squared_norm > T::zero(),
+            "normal_vector must be nonzero"
+        );
+        let center_inner_product = center.map_or(T::zero(), |center| {
+            assert!(
+                center.len() == normal_vector.len(),
+                "center and normal_vector have incompatible dimensions"
+            );
+            matrix_operations::inner_product(center, normal_vector)
+        });
+        let center_shift = (center_inner_product - offset) / normal_vector_squared_norm;
+        let slice_radius_squared =
+            radius * radius - center_shift * center_shift * normal_vector_squared_norm;
+        assert!(
+            slice_radius_squared >= T::zero(),
+            "the hyperplane does not intersect the ball"
+        );
+        BallHyperplane {
+            center,
+            normal_vector,
+            offset,
+            normal_vector_squared_norm,
+            center_shift,
+            slice_radius: slice_radius_squared.sqrt(),
+        }
+    }
+
+    /// Radius of the intersection of the ball with the hyperplane, $r'$
+    pub fn slice_radius(&self) -> T {
+        self.slice_radius
+    }
+
+    /// Coordinate `i` of the center of the slice, $x_c - t_c c$
+    fn slice_center(&self, i: usize) -> T {
+        let center_i = self.center.map_or(T::zero(), |center| center[i]);
+        center_i - self.center_shift * self.normal_vector[i]
+    }
+}
+
+impl<'a, T> Constraint<T> for BallHyperplane<'a, T>
+where
+    T: OptFloat,
+{
+    /// Projects on the intersection of the ball and the hyperplane
+    ///
+    /// The vector $x$ is first projected on the hyperplane,
+    /// $x \gets x - (\langle c, x\rangle - b)c/\Vert{}c{}\Vert^2$, and then on the
+    /// ball of the hyperplane with center $x_c'$ and radius $r'$
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the projection on the set
+    ///
+    /// # Panics
+    ///
+    /// The method panics if the dimension of `x` is not equal to that of the
+    /// normal vector
+    ///
+    fn project(&self, x: &mut [T]) {
+        assert!(
+            x.len() == self.normal_vector.len(),
+            "x and normal_vector have incompatible dimensions"
+        );
+
+        // project on the hyperplane
+        let factor = (matrix_operations::inner_product(x, self.normal_vector) - self.offset)
+            / self.normal_vector_squared_norm;
+        matrix_operations::sub_scaled_in_place(x, factor, self.normal_vector);
+
+        // project on the slice of the ball, within the hyperplane
+        let distance = x
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |sum, (i, &xi)| {
+                let di = xi - self.slice_center(i);
+                sum + di * di
+            })
+            .sqrt();
+        if distance > self.slice_radius {
+            let scaling = self.slice_radius / distance;
+            x.iter_mut().enumerate().for_each(|(i, xi)| {
+                let slice_center_i = self.slice_center(i);
+                *xi = slice_center_i + scaling * (*xi - slice_center_i);
+            });
+        }
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
+}
@@ -1,5 +1,6 @@
 use super::Constraint;
 use crate::core::OptFloat;
+use crate::SolverError;
 
 #[derive(Copy, Clone)]
 /// An infinity ball defined as $B_\infty^r = \\{x\in\mathbb{R}^n {}:{} \Vert{}x{}\Vert_{\infty} \leq r\\}$,
@@ -25,6 +26,40 @@ where
         assert!(radius > T::zero());
         BallInf { center, radius }
     }
+
+    /// Construct a new infinity-norm ball like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `radius` is not
+    /// positive
+    pub fn try_new(center: Option<&'a [T]>, radius: T) -> Result<Self, SolverError> {
+        if radius > T::zero() {
+            Ok(BallInf::new(center, radius))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
+    }
+
+    /// Radius of the ball
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    /// Center of the ball, or `None` if it is centered at the origin
+    pub fn center(&self) -> Option<&'a [T]> {
+        self.center
+    }
+
+    /// Construct a new infinity-norm ball with the same center and radius
+    /// `factor` times the radius of this ball (e.g., to shrink or grow a
+    /// trust region)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `factor` is not positive
+    ///
+    pub fn scaled(&self, factor: T) -> Self {
+        assert!(factor > T::zero(), "factor must be positive");
+        BallInf::new(self.center, self.radius * factor)
+    }
 }
 
 impl<'a, T> Constraint<T> for BallInf<'a, T>
@@ -68,4 +103,20 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
+
+    fn is_separable(&self) -> bool {
+        true
+    }
+
+    /// Support function of the ball, $\sigma_C(d) = \langle d, x_c \rangle + r\Vert{}d{}\Vert_1$
+    fn support(&self, d: &[T]) -> Option<T> {
+        let center_term = self.center.map_or(T::zero(), |center| {
+            crate::matrix_operations::inner_product(d, center)
+        });
+        Some(center_term + self.radius * crate::matrix_operations::norm1(d))
+    }
 }
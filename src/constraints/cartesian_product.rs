@@ -1,3 +1,5 @@
+use alloc::{boxed::Box, vec::Vec};
+
 use super::Constraint;
 use crate::core::OptFloat;
 /// Cartesian product of constraints
@@ -21,6 +23,10 @@ use crate::core::OptFloat;
 /// The constraint $x \in C$ is interpreted as $x_i \in C_i$
 /// for all $i=0,\ldots, n-1$.
 ///
+/// Note that, since the sets $C_i$ are stored as trait objects, a
+/// `CartesianProduct` is neither `Send` nor `Sync`, so it cannot be shared
+/// across threads.
+///
 #[derive(Default)]
 pub struct CartesianProduct<'a, T>
 where
@@ -163,4 +169,9 @@ where
             flag
         })
     }
+
+    /// The Cartesian product is separable if all its factors are separable
+    fn is_separable(&self) -> bool {
+        self.constraints.iter().all(|cnstr| cnstr.is_separable())
+    }
 }
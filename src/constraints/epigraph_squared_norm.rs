@@ -9,7 +9,7 @@ pub struct EpigraphSquaredNorm<T>
 where
     T: OptFloat,
 {
-    _phantom: std::marker::PhantomData<T>,
+    _phantom: core::marker::PhantomData<T>,
 }
 
 impl<T> EpigraphSquaredNorm<T>
@@ -21,7 +21,7 @@ where
     /// Note that you do not need to specify the dimension.
     pub fn new() -> Self {
         EpigraphSquaredNorm {
-            _phantom: std::marker::PhantomData,
+            _phantom: core::marker::PhantomData,
         }
     }
 }
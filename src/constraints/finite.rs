@@ -60,7 +60,7 @@ where
 
 impl<'a, T> Constraint<T> for FiniteSet<'a, T>
 where
-    T: OptFloat + std::ops::AddAssign,
+    T: OptFloat + core::ops::AddAssign,
 {
     ///
     /// Projection on the current finite set
@@ -109,4 +109,8 @@ where
     fn is_convex(&self) -> bool {
         self.data.len() == 1 && !self.data[0].is_empty()
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
 }
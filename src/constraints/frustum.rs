@@ -0,0 +1,129 @@
+use super::{Constraint, SecondOrderCone};
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+#[derive(Clone, Copy)]
+///
+/// A truncated second-order cone (frustum)
+///
+/// A set of the form
+///
+/// $$
+/// F = \\{x=(y, t) \in \mathbb{R}^{n+1}: \Vert{}y\Vert \leq \alpha{}t,
+/// t_{\min} \leq t \leq t_{\max}\\},
+/// $$
+///
+/// that is, the intersection of the second-order cone $C_\alpha$ (see
+/// [SecondOrderCone](struct.SecondOrderCone.html)) with the slab
+/// $t_{\min} \leq t \leq t_{\max}$. Such sets describe, for example, thrust
+/// vectors with a bounded tilt angle and a bounded vertical component.
+///
+/// The projection is computed by first projecting on $C_\alpha$. If the
+/// result violates one of the bounds on $t$, then the projection on $F$ lies
+/// on the corresponding face of the frustum, that is, on the disc
+/// $\\{(y, t_b): \Vert{}y\Vert \leq \alpha{}t_b\\}$, where $t_b$ is the
+/// violated bound, so it is obtained by fixing $t = t_b$ and projecting $y$
+/// on the ball of radius $\alpha{}t_b$.
+///
+pub struct Frustum<T>
+where
+    T: OptFloat,
+{
+    cone: SecondOrderCone<T>,
+    alpha: T,
+    t_min: T,
+    t_max: T,
+}
+
+impl<T> Frustum<T>
+where
+    T: OptFloat,
+{
+    /// Construct a new frustum with parameters `alpha`, `t_min` and `t_max`
+    ///
+    /// # Arguments
+    ///
+    /// - `alpha`: parameter $\alpha$ of the second-order cone
+    /// - `t_min`: lower bound on $t$
+    /// - `t_max`: upper bound on $t$
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `alpha` is not positive, if `t_max` is negative
+    /// (in which case the frustum is empty) or if `t_min > t_max`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, Frustum};
+    ///
+    /// let frustum = Frustum::new(1.0, 1.0, 2.0);
+    /// let mut x = [0.5, 0.5, 3.0];
+    /// frustum.project(&mut x);
+    /// assert_eq!([0.5, 0.5, 2.0], x);
+    /// ```
+    ///
+    pub fn new(alpha: T, t_min: T, t_max: T) -> Self {
+        assert!(t_max >= T::zero(), "t_max must be nonnegative");
+        assert!(t_min <= t_max, "t_min must not exceed t_max");
+        Frustum {
+            cone: SecondOrderCone::new(alpha),
+            alpha,
+            t_min,
+            t_max,
+        }
+    }
+}
+
+impl<T> Constraint<T> for Frustum<T>
+where
+    T: OptFloat,
+{
+    /// Project on the frustum (updates the given vector/slice)
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: (in) vector to be projected on the frustum, (out) projection on
+    ///   the frustum
+    ///
+    /// # Panics
+    ///
+    /// The methods panics is the length of `x` is less than 2.
+    ///
+    fn project(&self, x: &mut [T]) {
+        let n = x.len();
+        assert!(n >= 2, "x must be of dimension at least 2");
+        let norm_y = matrix_operations::norm2(&x[..n - 1]);
+        let t = x[n - 1];
+
+        // last coordinate of the projection on the cone (without computing it)
+        let t_cone = if self.alpha * norm_y <= -t {
+            T::zero()
+        } else if norm_y <= self.alpha * t {
+            t
+        } else {
+            (self.alpha * norm_y + t) / (self.alpha.powi(2) + T::one())
+        };
+        let t_bound = if t_cone > self.t_max {
+            self.t_max
+        } else if t_cone < self.t_min {
+            self.t_min
+        } else {
+            self.cone.project(x);
+            return;
+        };
+
+        // project on the face {(y, t_bound): ||y|| <= alpha * t_bound}
+        let radius = self.alpha * t_bound;
+        if norm_y > radius {
+            x[..n - 1]
+                .iter_mut()
+                .for_each(|yi| *yi = *yi * radius / norm_y);
+        }
+        x[n - 1] = t_bound;
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
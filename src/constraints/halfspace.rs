@@ -64,7 +64,7 @@ where
 
 impl<'a, T> Constraint<T> for Halfspace<'a, T>
 where
-    T: OptFloat + std::ops::SubAssign,
+    T: OptFloat + core::ops::SubAssign,
 {
     /// Projects on halfspace using the following formula:
     ///
@@ -1,15 +1,20 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
 use super::Constraint;
 use crate::core::OptFloat;
 use crate::matrix_operations;
 
 #[derive(Clone)]
 /// A hyperplane is a set given by $H = \\{x \in \mathbb{R}^n {}:{} \langle c, x\rangle = b\\}$.
+///
+/// The normal vector is either borrowed (see `new`) or owned (see `owned`).
 pub struct Hyperplane<'a, T>
 where
     T: OptFloat,
 {
     /// normal vector
-    normal_vector: &'a [T],
+    normal_vector: Cow<'a, [T]>,
     /// offset
     offset: T,
     /// squared Euclidean norm of the normal vector (computed once upon construction)
@@ -55,7 +60,41 @@ where
     pub fn new(normal_vector: &'a [T], offset: T) -> Self {
         let normal_vector_squared_norm = matrix_operations::norm2_squared(normal_vector);
         Hyperplane {
-            normal_vector,
+            normal_vector: Cow::Borrowed(normal_vector),
+            offset,
+            normal_vector_squared_norm,
+        }
+    }
+
+    /// Constructs a new instance of `Hyperplane` which owns its normal vector
+    ///
+    /// This is the same as `new`, but the hyperplane takes ownership of the
+    /// normal vector, so it does not borrow any data (which is convenient
+    /// when the normal vector is computed on the fly)
+    ///
+    /// # Arguments
+    ///
+    /// - `normal_vector`: the normal vector, $c$
+    /// - `offset`: the offset parameter, $b$
+    ///
+    /// # Returns
+    ///
+    /// New instance of `Hyperplane`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, Hyperplane};
+    ///
+    /// let hyperplane = Hyperplane::owned(vec![1., 2.], 1.0);
+    /// let mut x = [-1., 3.];
+    /// hyperplane.project(&mut x);
+    /// ```
+    ///
+    pub fn owned(normal_vector: Vec<T>, offset: T) -> Hyperplane<'static, T> {
+        let normal_vector_squared_norm = matrix_operations::norm2_squared(&normal_vector);
+        Hyperplane {
+            normal_vector: Cow::Owned(normal_vector),
             offset,
             normal_vector_squared_norm,
         }
@@ -64,7 +103,7 @@ where
 
 impl<'a, T> Constraint<T> for Hyperplane<'a, T>
 where
-    T: OptFloat + std::ops::SubAssign,
+    T: OptFloat + core::ops::SubAssign,
 {
     /// Projects on the hyperplane using the formula:
     ///
@@ -87,7 +126,7 @@ where
     /// of the hyperplane.
     ///
     fn project(&self, x: &mut [T]) {
-        let inner_product = matrix_operations::inner_product(x, self.normal_vector);
+        let inner_product = matrix_operations::inner_product(x, &self.normal_vector);
         let factor = (inner_product - self.offset) / self.normal_vector_squared_norm;
         x.iter_mut()
             .zip(self.normal_vector.iter())
@@ -102,4 +141,25 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    /// Support function of the hyperplane, which is equal to $\lambda b$ if
+    /// $d = \lambda c$ for some $\lambda \in \mathbb{R}$, and infinite otherwise
+    ///
+    /// Whether $d$ is parallel to $c$ is decided up to a relative tolerance
+    /// equal to the square root of the machine epsilon.
+    fn support(&self, d: &[T]) -> Option<T> {
+        let lambda = matrix_operations::inner_product(d, &self.normal_vector)
+            / self.normal_vector_squared_norm;
+        let residual = d
+            .iter()
+            .zip(self.normal_vector.iter())
+            .fold(T::zero(), |current_max, (&di, &ci)| {
+                (di - lambda * ci).abs().max(current_max)
+            });
+        if residual <= T::epsilon().sqrt() * matrix_operations::norm_inf(d) {
+            Some(lambda * self.offset)
+        } else {
+            Some(T::infinity())
+        }
+    }
 }
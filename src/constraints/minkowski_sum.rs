@@ -0,0 +1,189 @@
+use super::workspace::Workspace;
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+const DEFAULT_MAX_ITER: usize = 500;
+const DEFAULT_TOLERANCE: f64 = 1e-10;
+
+/// The Minkowski sum of two sets, $A \oplus B = \\{a + b {}:{} a\in A, b \in B\\}$
+///
+/// The projection on $A \oplus B$ is not a composition of the projections on
+/// $A$ and $B$; instead, it is computed by solving the problem
+///
+/// $$
+/// \mathrm{Minimize}_{a\in A, b\in B}\ \tfrac{1}{2}\Vert{}a + b - x{}\Vert^2,
+/// $$
+///
+/// and then $\Pi_{A \oplus B}(x) = a^\star + b^\star$. This problem is solved
+/// iteratively by alternating minimization over $a$ and $b$, that is,
+/// $b \gets \Pi_B(x - a)$ and $a \gets \Pi_A(x - b)$, until the changes in $a$
+/// and $b$ (in the infinity norm) drop below a given tolerance, or a maximum
+/// number of iterations is reached. Each iteration costs one projection on each
+/// set.
+///
+/// Only the projections on $A$ and $B$ are used, so the support functions of
+/// the two sets are not needed (and no duality gap is available to certify
+/// the result). The stopping criterion is on the size of the steps, so if the
+/// two sets meet at a shallow angle, the method may converge slowly, and the
+/// returned point is not an accurate projection if the maximum number of
+/// iterations is reached; in debug builds, this triggers an assertion.
+///
+/// The Minkowski sum of two convex sets is convex; if either set is not convex,
+/// the above procedure is only a heuristic.
+///
+/// The vectors $a$ and $b$ are stored in a workspace, which is allocated at
+/// the first projection and reused afterwards.
+///
+pub struct MinkowskiSum<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    set_a: &'a A,
+    set_b: &'a B,
+    max_iter: usize,
+    tolerance: T,
+    workspace: Workspace<T>,
+}
+
+impl<'a, A, B, T> MinkowskiSum<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    /// Construct the Minkowski sum of two sets, $A \oplus B$
+    ///
+    /// # Arguments
+    ///
+    /// - `set_a`: set $A$
+    /// - `set_b`: set $B$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Ball2, BallInf, Constraint, MinkowskiSum};
+    ///
+    /// let box_set = BallInf::new(None, 1.0);
+    /// let ball = Ball2::new(None, 0.5);
+    /// let rounded_box = MinkowskiSum::new(&box_set, &ball);
+    /// let mut x = [3.0, 2.0];
+    /// rounded_box.project(&mut x);
+    /// ```
+    ///
+    pub fn new(set_a: &'a A, set_b: &'a B) -> Self {
+        MinkowskiSum {
+            set_a,
+            set_b,
+            max_iter: DEFAULT_MAX_ITER,
+            tolerance: T::from(DEFAULT_TOLERANCE).unwrap(),
+            workspace: Workspace::new(0, T::zero()),
+        }
+    }
+
+    /// Sets the maximum number of iterations of the projection (the default
+    /// value is `500`)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `max_iter` is zero
+    ///
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        assert!(max_iter > 0, "max_iter must be positive");
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Sets the tolerance of the projection (the default value is `1e-10`)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `tolerance` is not positive
+    ///
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        assert!(tolerance > T::zero(), "tolerance must be positive");
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl<'a, A, B, T> Constraint<T> for MinkowskiSum<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    /// Projects on $A \oplus B$ by alternating minimization
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the projection on the set
+    ///
+    /// # Memory allocation
+    ///
+    /// At the first call (or if `x` is longer than at any previous call),
+    /// this method allocates a workspace of three vectors of the same length
+    /// as `x`
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, the method panics if the tolerance is not reached
+    /// within the maximum number of iterations
+    ///
+    fn project(&self, x: &mut [T]) {
+        let n = x.len();
+        self.workspace.with(3 * n, |work| {
+            let (a, work) = work.split_at_mut(n);
+            let (previous, b) = work.split_at_mut(n);
+            a.copy_from_slice(x);
+            self.set_a.project(a);
+            b.iter_mut()
+                .zip(x.iter())
+                .zip(a.iter())
+                .for_each(|((bi, &xi), &ai)| *bi = xi - ai);
+            let mut converged = false;
+
+            for _ in 0..self.max_iter {
+                // b <-- Proj_B(x - a)
+                previous.copy_from_slice(b);
+                b.iter_mut()
+                    .zip(x.iter())
+                    .zip(a.iter())
+                    .for_each(|((bi, &xi), &ai)| *bi = xi - ai);
+                self.set_b.project(b);
+                let step_b = matrix_operations::norm_inf_diff(b, previous);
+
+                // a <-- Proj_A(x - b)
+                previous.copy_from_slice(a);
+                a.iter_mut()
+                    .zip(x.iter())
+                    .zip(b.iter())
+                    .for_each(|((ai, &xi), &bi)| *ai = xi - bi);
+                self.set_a.project(a);
+
+                let step_a = matrix_operations::norm_inf_diff(a, previous);
+
+                if step_a <= self.tolerance && step_b <= self.tolerance {
+                    converged = true;
+                    break;
+                }
+            }
+            debug_assert!(
+                converged,
+                "the projection on the Minkowski sum did not converge within max_iter iterations"
+            );
+
+            x.iter_mut()
+                .zip(a.iter())
+                .zip(b.iter())
+                .for_each(|((xi, &ai), &bi)| *xi = ai + bi);
+        });
+    }
+
+    /// The Minkowski sum of two convex sets is convex
+    fn is_convex(&self) -> bool {
+        self.set_a.is_convex() && self.set_b.is_convex()
+    }
+}
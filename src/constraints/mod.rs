@@ -5,42 +5,94 @@
 //! projection method, and a collection of simple sets, such as norm-balls,
 //! finite sets, second-order cones and their Cartesian products.
 //!
+//! ## Thread safety
+//!
+//! Projections take `&self`; the constraints of this module which keep work
+//! memory across projections (to avoid allocating memory at every projection)
+//! guard it so that it can be used from several threads, so a constraint can
+//! be shared across threads (for example, to solve several problems in
+//! parallel) whenever it is `Send + Sync`:
+//!
+//! - all constraints which own their data or borrow slices (such as
+//!   [`Ball2`], [`Rectangle`] or [`AffineSpace`]) are `Send + Sync` for
+//!   `f32` and `f64`, and generally whenever `T: Send + Sync`
+//! - wrappers and combinators, such as [`MinkowskiSum`], [`SocIntersection`],
+//!   [`Rotated`], [`ScaledConstraint`] and [`NonexpansiveChecked`], are `Send + Sync` whenever the sets they
+//!   wrap are
+//! - [`CartesianProduct`] stores its sets as trait objects and is neither
+//!   `Send` nor `Sync`
+//!
 //!
 //! [`Constraint`]: trait.Constraint.html
+//! [`Ball2`]: struct.Ball2.html
+//! [`Rectangle`]: struct.Rectangle.html
+//! [`AffineSpace`]: struct.AffineSpace.html
+//! [`MinkowskiSum`]: struct.MinkowskiSum.html
+//! [`SocIntersection`]: struct.SocIntersection.html
+//! [`Rotated`]: struct.Rotated.html
+//! [`ScaledConstraint`]: struct.ScaledConstraint.html
+//! [`NonexpansiveChecked`]: struct.NonexpansiveChecked.html
+//! [`CartesianProduct`]: struct.CartesianProduct.html
 
+#[cfg(feature = "std")]
 mod affine_space;
 mod ball1;
 mod ball2;
+mod ball_complement;
+mod ball_hyperplane;
 mod ballinf;
 mod cartesian_product;
+#[cfg(feature = "std")]
 mod epigraph_squared_norm;
 mod finite;
+mod frustum;
 mod halfspace;
 mod hyperplane;
+mod minkowski_sum;
 mod no_constraints;
+mod nonexpansive_checked;
+mod order_cone;
 mod rectangle;
+mod regularized_box;
+mod rotated;
+mod scaled;
 mod simplex;
 mod soc;
+mod soc_intersection;
 mod sphere2;
+mod workspace;
 mod zero;
 
+#[cfg(feature = "std")]
 pub use affine_space::AffineSpace;
 pub use ball1::Ball1;
 pub use ball2::Ball2;
+pub use ball_complement::BallComplement;
+pub use ball_hyperplane::BallHyperplane;
 pub use ballinf::BallInf;
 pub use cartesian_product::CartesianProduct;
+#[cfg(feature = "std")]
 pub use epigraph_squared_norm::EpigraphSquaredNorm;
 pub use finite::FiniteSet;
+pub use frustum::Frustum;
 pub use halfspace::Halfspace;
 pub use hyperplane::Hyperplane;
+pub use minkowski_sum::MinkowskiSum;
 pub use no_constraints::NoConstraints;
+pub use nonexpansive_checked::NonexpansiveChecked;
+pub use order_cone::OrderCone;
 pub use rectangle::Rectangle;
+pub use regularized_box::RegularizedBox;
+pub use rotated::Rotated;
+pub use scaled::ScaledConstraint;
 pub use simplex::Simplex;
 pub use soc::SecondOrderCone;
+pub use soc_intersection::SocIntersection;
 pub use sphere2::Sphere2;
 pub use zero::Zero;
 
 use crate::core::OptFloat;
+use alloc::boxed::Box;
 
 /// A set which can be used as a constraint
 ///
@@ -64,6 +116,139 @@ where
 
     /// Returns true if and only if the set is convex
     fn is_convex(&self) -> bool;
+
+    /// Returns true if the set is known to be compact (closed and bounded)
+    ///
+    /// This is useful, e.g., to decide whether the iterates of an algorithm
+    /// are bounded a priori. The default implementation returns `false`,
+    /// which only means that compactness is not guaranteed.
+    ///
+    fn is_compact(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the set is known to be separable, that is, a Cartesian
+    /// product of one-dimensional sets, $U = U_1 \times \cdots \times U_n$
+    ///
+    /// The projection on a separable set in the norm
+    /// $\Vert{}x{}\Vert_D = \Vert{}Dx{}\Vert$, where $D$ is a positive
+    /// diagonal matrix, coincides with the Euclidean projection; this is
+    /// required by the diagonal preconditioner of PANOC (see
+    /// `PANOCCache::with_preconditioner`). The default implementation returns
+    /// `false`, which only means that separability is not guaranteed.
+    ///
+    fn is_separable(&self) -> bool {
+        false
+    }
+
+    /// Whether a given point belongs to the set, up to a tolerance
+    ///
+    /// The default implementation projects a copy of `x` on the set and
+    /// checks whether its distance (in the infinity norm) from `x` is at
+    /// most `tolerance`. Implementations may override this method with a
+    /// cheaper membership test.
+    ///
+    /// ## Arguments
+    ///
+    /// - `x`: the given point
+    /// - `tolerance`: nonnegative tolerance
+    ///
+    /// ## Memory allocation
+    ///
+    /// The default implementation allocates memory for `x.len()` floats
+    ///
+    fn contains(&self, x: &[T], tolerance: T) -> bool {
+        let mut x_projected = x.to_vec();
+        self.project(&mut x_projected);
+        crate::matrix_operations::norm_inf_diff(x, &x_projected) <= tolerance
+    }
+
+    /// Support function of the set, that is,
+    ///
+    /// $$
+    /// \sigma_C(d) = \sup_{x \in C} \langle d, x \rangle
+    /// $$
+    ///
+    /// which may be equal to infinity if the set is unbounded
+    ///
+    /// ## Arguments
+    ///
+    /// - `d`: the given vector $d$
+    ///
+    /// ## Returns
+    ///
+    /// The value of the support function at `d`, or `None` if it is not
+    /// available in closed form (this is what the default implementation
+    /// returns)
+    ///
+    fn support(&self, d: &[T]) -> Option<T> {
+        let _ = d;
+        None
+    }
+}
+
+/// Boxed constraints (e.g., `Box<dyn Constraint<T>>`) are constraints;
+/// all methods are forwarded to the boxed set
+impl<T, C> Constraint<T> for Box<C>
+where
+    T: OptFloat,
+    C: Constraint<T> + ?Sized,
+{
+    fn project(&self, x: &mut [T]) {
+        (**self).project(x)
+    }
+
+    fn is_convex(&self) -> bool {
+        (**self).is_convex()
+    }
+
+    fn is_compact(&self) -> bool {
+        (**self).is_compact()
+    }
+
+    fn is_separable(&self) -> bool {
+        (**self).is_separable()
+    }
+
+    fn contains(&self, x: &[T], tolerance: T) -> bool {
+        (**self).contains(x, tolerance)
+    }
+
+    fn support(&self, d: &[T]) -> Option<T> {
+        (**self).support(d)
+    }
+}
+
+/// References to constraints (e.g., `&dyn Constraint<T>`) are constraints;
+/// all methods are forwarded to the referenced set
+impl<T, C> Constraint<T> for &C
+where
+    T: OptFloat,
+    C: Constraint<T> + ?Sized,
+{
+    fn project(&self, x: &mut [T]) {
+        (**self).project(x)
+    }
+
+    fn is_convex(&self) -> bool {
+        (**self).is_convex()
+    }
+
+    fn is_compact(&self) -> bool {
+        (**self).is_compact()
+    }
+
+    fn is_separable(&self) -> bool {
+        (**self).is_separable()
+    }
+
+    fn contains(&self, x: &[T], tolerance: T) -> bool {
+        (**self).contains(x, tolerance)
+    }
+
+    fn support(&self, d: &[T]) -> Option<T> {
+        (**self).support(d)
+    }
 }
 
 /* ---------------------------------------------------------------------------- */
@@ -21,4 +21,8 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_separable(&self) -> bool {
+        true
+    }
 }
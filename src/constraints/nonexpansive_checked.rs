@@ -0,0 +1,130 @@
+use super::workspace::Workspace;
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+const DEFAULT_PERTURBATION: f64 = 1e-4;
+
+/// A wrapper around a constraint that checks, at every projection, that the
+/// projection is non-expansive
+///
+/// Projections on closed convex sets are (firmly) non-expansive, that is,
+///
+/// $$
+/// \Vert{}\Pi_C(x) - \Pi_C(y){}\Vert \leq \Vert{}x - y{}\Vert,
+/// $$
+///
+/// for all $x, y$. Every time `project` is called with some $x$, this wrapper
+/// also projects a slightly perturbed copy of it, $y$, and panics if the above
+/// inequality is violated, which indicates that the projection on the wrapped
+/// (convex) set is wrong.
+///
+/// This is a testing aid for custom implementations of `Constraint`: it doubles
+/// the cost of every projection. The perturbed copy is stored in a workspace,
+/// which is allocated at the first projection. The check is skipped for
+/// nonconvex sets, and it is only performed in builds with debug assertions;
+/// in release builds, the wrapper simply projects on the wrapped set.
+///
+pub struct NonexpansiveChecked<C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    constraint: C,
+    perturbation: T,
+    workspace: Workspace<T>,
+}
+
+impl<C, T> NonexpansiveChecked<C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    /// Wraps a given constraint
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Ball2, Constraint, NonexpansiveChecked};
+    ///
+    /// let ball = NonexpansiveChecked::new(Ball2::new(None, 1.0));
+    /// let mut x = [3.0, 4.0];
+    /// ball.project(&mut x);
+    /// ```
+    ///
+    pub fn new(constraint: C) -> Self {
+        NonexpansiveChecked {
+            constraint,
+            perturbation: T::from(DEFAULT_PERTURBATION).unwrap(),
+            workspace: Workspace::new(0, T::zero()),
+        }
+    }
+
+    /// Sets the magnitude of the perturbation (the default value is `1e-4`)
+    ///
+    /// Element $y_i$ of the perturbed copy of $x$ differs from $x_i$ by
+    /// `perturbation * (1 + |x_i|)`, in alternating directions
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `perturbation` is not positive
+    ///
+    pub fn with_perturbation(mut self, perturbation: T) -> Self {
+        assert!(perturbation > T::zero(), "perturbation must be positive");
+        self.perturbation = perturbation;
+        self
+    }
+}
+
+impl<C, T> Constraint<T> for NonexpansiveChecked<C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    /// Projects on the wrapped set and, in builds with debug assertions,
+    /// checks that the projection is non-expansive
+    ///
+    /// # Panics
+    ///
+    /// With debug assertions, the method panics if the wrapped set is convex
+    /// and the inequality
+    /// $\Vert{}\Pi_C(x) - \Pi_C(y){}\Vert \leq \Vert{}x - y{}\Vert$ is violated
+    /// (up to a small tolerance) for the perturbed copy $y$ of $x$
+    ///
+    fn project(&self, x: &mut [T]) {
+        if !cfg!(debug_assertions) || !self.constraint.is_convex() {
+            self.constraint.project(x);
+            return;
+        }
+        self.workspace.with(x.len(), |y| {
+            y.iter_mut()
+                .zip(x.iter())
+                .enumerate()
+                .for_each(|(i, (yi, &xi))| {
+                    let delta = self.perturbation * (T::one() + xi.abs());
+                    *yi = if i % 2 == 0 { xi + delta } else { xi - delta };
+                });
+            let distance_before = matrix_operations::norm2_diff(x, y);
+
+            self.constraint.project(x);
+            self.constraint.project(y);
+            let distance_after = matrix_operations::norm2_diff(x, y);
+
+            let slack = T::epsilon().sqrt() * (T::one() + distance_before);
+            assert!(
+                distance_after <= distance_before + slack,
+                "projection is expansive: |Px - Py| = {:?} > |x - y| = {:?}",
+                distance_after,
+                distance_before
+            );
+        });
+    }
+
+    fn is_convex(&self) -> bool {
+        self.constraint.is_convex()
+    }
+
+    fn is_separable(&self) -> bool {
+        self.constraint.is_separable()
+    }
+}
@@ -0,0 +1,182 @@
+use super::workspace::Workspace;
+use super::Constraint;
+use crate::core::OptFloat;
+
+/// A block of PAVA: (sum of w_i * z_i, sum of w_i^2, number of entries)
+type Block<T> = (T, T, usize);
+
+#[derive(Clone)]
+/// A (weighted) order cone, that is, a set of the form
+/// $K_w = \\{x \in \mathbb{R}^n {}:{} x_1/w_1 \leq x_2/w_2 \leq \ldots \leq x_n/w_n\\}$,
+/// where $w_i > 0$ are given weights (by default, $w_i = 1$), or the set of
+/// vectors whose weighted entries are in nonincreasing order
+///
+/// The projection on $K_w$ is computed by the pool adjacent violators
+/// algorithm (PAVA). Projecting on the (unweighted) order cone amounts to
+/// solving an isotonic regression problem.
+///
+/// The stack of blocks of PAVA is kept in the struct: it is allocated by
+/// `with_weights` (whose dimension is that of the vectors to be projected),
+/// or, if no weights are given, at the first projection, and it is reused
+/// afterwards.
+pub struct OrderCone<'a, T>
+where
+    T: OptFloat,
+{
+    weights: Option<&'a [T]>,
+    reverse_order: bool,
+    blocks: Workspace<Block<T>>,
+}
+
+impl<'a, T> OrderCone<'a, T>
+where
+    T: OptFloat,
+{
+    /// Construct the (unweighted) cone of vectors with nondecreasing entries,
+    /// $\\{x \in \mathbb{R}^n {}:{} x_1 \leq x_2 \leq \ldots \leq x_n\\}$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, OrderCone};
+    ///
+    /// let order_cone = OrderCone::new();
+    /// let mut x = [1.0, 3.0, 2.0];
+    /// order_cone.project(&mut x);
+    /// assert_eq!([1.0, 2.5, 2.5], x);
+    /// ```
+    ///
+    pub fn new() -> Self {
+        OrderCone {
+            weights: None,
+            reverse_order: false,
+            blocks: Workspace::new(0, (T::zero(), T::zero(), 0)),
+        }
+    }
+
+    /// Sets the weights $w$ of the cone
+    ///
+    /// # Arguments
+    ///
+    /// - `weights`: positive weights; the vectors to be projected must have
+    ///   the same dimension as `weights`
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `weights` is empty or if any of the weights is
+    /// not positive
+    ///
+    /// # Memory allocation
+    ///
+    /// This method allocates the stack of blocks of the projection, which
+    /// holds up to `weights.len()` blocks
+    ///
+    pub fn with_weights(mut self, weights: &'a [T]) -> Self {
+        assert!(!weights.is_empty(), "weights must be nonempty");
+        assert!(
+            weights.iter().all(|w| *w > T::zero()),
+            "weights must be positive"
+        );
+        self.weights = Some(weights);
+        self.blocks = Workspace::new(weights.len(), (T::zero(), T::zero(), 0));
+        self
+    }
+
+    /// Reverses the order of the cone, so that it becomes the set
+    /// $\\{x \in \mathbb{R}^n {}:{} x_1/w_1 \geq x_2/w_2 \geq \ldots \geq x_n/w_n\\}$
+    pub fn with_reverse_order(mut self) -> Self {
+        self.reverse_order = true;
+        self
+    }
+
+    fn weight(&self, i: usize) -> T {
+        self.weights.map_or(T::one(), |weights| weights[i])
+    }
+}
+
+impl<'a, T> Default for OrderCone<'a, T>
+where
+    T: OptFloat,
+{
+    fn default() -> Self {
+        OrderCone::new()
+    }
+}
+
+impl<'a, T> Constraint<T> for OrderCone<'a, T>
+where
+    T: OptFloat,
+{
+    /// Projection on the weighted order cone
+    ///
+    /// With the change of variables $y_i = x_i/w_i$, the projection of $z$
+    /// amounts to minimizing $\sum_i w_i^2(y_i - z_i/w_i)^2$ over all
+    /// nondecreasing $y$. PAVA scans the entries and merges adjacent blocks
+    /// whose weighted means violate the ordering; the mean of a block $B$ is
+    /// $\sum_{i\in B} w_i z_i / \sum_{i \in B} w_i^2$.
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the projection on the set
+    ///
+    /// # Panics
+    ///
+    /// The method panics if weights have been provided and their dimension
+    /// is not equal to that of `x`
+    ///
+    /// # Memory allocation
+    ///
+    /// Without weights, the stack of blocks, which holds up to `x.len()`
+    /// blocks, is allocated at the first call (or if `x` is longer than at
+    /// any previous call)
+    ///
+    fn project(&self, x: &mut [T]) {
+        if let Some(weights) = self.weights {
+            assert!(
+                weights.len() == x.len(),
+                "x and weights have incompatible dimensions"
+            );
+        }
+
+        self.blocks.with(x.len(), |blocks| {
+            // the stack consists of the first `num_blocks` blocks
+            let mut num_blocks = 0;
+            x.iter().enumerate().for_each(|(i, &xi)| {
+                let wi = self.weight(i);
+                let mut block = (wi * xi, wi * wi, 1);
+                while num_blocks > 0 {
+                    let (sum_last, weight_last, len_last) = blocks[num_blocks - 1];
+                    let mean_last = sum_last / weight_last;
+                    let mean = block.0 / block.1;
+                    let is_violated = if self.reverse_order {
+                        mean_last < mean
+                    } else {
+                        mean_last > mean
+                    };
+                    if !is_violated {
+                        break;
+                    }
+                    block = (
+                        block.0 + sum_last,
+                        block.1 + weight_last,
+                        block.2 + len_last,
+                    );
+                    num_blocks -= 1;
+                }
+                blocks[num_blocks] = block;
+                num_blocks += 1;
+            });
+
+            let mut idx = 0;
+            blocks[..num_blocks].iter().for_each(|&(sum, weight, len)| {
+                let mean = sum / weight;
+                (idx..idx + len).for_each(|i| x[i] = self.weight(i) * mean);
+                idx += len;
+            });
+        });
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
@@ -1,5 +1,6 @@
 use super::Constraint;
 use crate::core::OptFloat;
+use crate::SolverError;
 #[derive(Clone, Copy)]
 ///
 /// A rectangle, $R = \\{x \in \mathbb{R}^n {}:{} x_{\min} {}\leq{} x {}\leq{} x_{\max}\\}$
@@ -11,8 +12,53 @@ pub struct Rectangle<'a, T>
 where
     T: OptFloat,
 {
-    xmin: Option<&'a [T]>,
-    xmax: Option<&'a [T]>,
+    xmin: Option<Bound<'a, T>>,
+    xmax: Option<Bound<'a, T>>,
+}
+
+/// A lower or upper bound of a rectangle: either a slice, or a scalar which is
+/// the same for the first `n` elements
+#[derive(Clone, Copy)]
+enum Bound<'a, T> {
+    Slice(&'a [T]),
+    Scalar(T, usize),
+}
+
+impl<'a, T> Bound<'a, T>
+where
+    T: OptFloat,
+{
+    /// The bound as a slice, or `None` if it is a scalar
+    fn as_slice(&self) -> Option<&'a [T]> {
+        match *self {
+            Bound::Slice(bound) => Some(bound),
+            Bound::Scalar(..) => None,
+        }
+    }
+
+    /// Whether all elements of the bound are finite
+    fn is_finite(&self) -> bool {
+        match *self {
+            Bound::Slice(bound) => bound.iter().all(|b| b.is_finite()),
+            Bound::Scalar(bound, _) => bound.is_finite(),
+        }
+    }
+
+    /// Number of elements of the bound
+    fn len(&self) -> usize {
+        match *self {
+            Bound::Slice(bound) => bound.len(),
+            Bound::Scalar(_, n) => n,
+        }
+    }
+
+    /// The $i$-th element of the bound
+    fn value(&self, i: usize) -> T {
+        match *self {
+            Bound::Slice(bound) => bound[i],
+            Bound::Scalar(bound, _) => bound,
+        }
+    }
 }
 
 impl<'a, T> Rectangle<'a, T>
@@ -46,7 +92,115 @@ where
             xmin.is_none() || xmax.is_none() || xmin.unwrap().len() == xmax.unwrap().len(),
             "incompatible dimensions of xmin and xmax"
         );
-        Rectangle { xmin, xmax }
+        Rectangle {
+            xmin: xmin.map(Bound::Slice),
+            xmax: xmax.map(Bound::Slice),
+        }
+    }
+
+    /// Construct a new rectangle like `new`, but without panicking
+    ///
+    /// # Errors
+    ///
+    /// Returns `SolverError::InvalidArgument` if both `xmin` and `xmax` are
+    /// `None`, or if they have incompatible dimensions
+    ///
+    pub fn try_new(xmin: Option<&'a [T]>, xmax: Option<&'a [T]>) -> Result<Self, SolverError> {
+        match (xmin, xmax) {
+            (None, None) => Err(SolverError::InvalidArgument),
+            (Some(xmin), Some(xmax)) if xmin.len() != xmax.len() => {
+                Err(SolverError::InvalidArgument)
+            }
+            _ => Ok(Rectangle::new(xmin, xmax)),
+        }
+    }
+
+    /// Construct a new rectangle in $\mathbb{R}^n$ whose bounds are the same
+    /// for all elements, that is, $x_{\min} = (l, \ldots, l)$ and
+    /// $x_{\max} = (u, \ldots, u)$
+    ///
+    /// Unlike `new`, this does not require slices which hold the bounds
+    /// (either of the bounds may be infinite)
+    ///
+    /// # Arguments
+    ///
+    /// - `n`: dimension of the rectangle
+    /// - `xmin`: minimum value, $l$, of all elements of `x`
+    /// - `xmax`: maximum value, $u$, of all elements of `x`
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `n` is zero, or if `xmin > xmax` (or either of
+    /// them is NaN)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, Rectangle};
+    ///
+    /// let rectangle = Rectangle::from_scalars(3, -1.0, 1.0);
+    /// let mut x = [2.0, 0.5, -3.0];
+    /// rectangle.project(&mut x);
+    /// assert_eq!([1.0, 0.5, -1.0], x);
+    /// ```
+    ///
+    pub fn from_scalars(n: usize, xmin: T, xmax: T) -> Self {
+        assert!(n > 0, "n must be positive");
+        assert!(xmin <= xmax, "xmin must not be larger than xmax");
+        Rectangle {
+            xmin: Some(Bound::Scalar(xmin, n)),
+            xmax: Some(Bound::Scalar(xmax, n)),
+        }
+    }
+
+    /// Lower and upper bound of the $i$-th element of `x`, where a bound
+    /// which has not been provided is equal to $-\infty$ or $+\infty$
+    /// respectively
+    ///
+    /// # Arguments
+    ///
+    /// - `i`: index of the element
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `i` is not smaller than the dimension of the
+    /// rectangle
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::Rectangle;
+    ///
+    /// let xmin = [0.0, -1.0];
+    /// let rectangle = Rectangle::new(Some(&xmin), None);
+    /// assert_eq!((-1.0, f64::INFINITY), rectangle.bounds(1));
+    /// ```
+    ///
+    pub fn bounds(&self, i: usize) -> (T, T) {
+        let value = |bound: &Option<Bound<'a, T>>, absent: T| {
+            bound.map_or(absent, |bound| {
+                assert!(i < bound.len(), "index out of bounds");
+                bound.value(i)
+            })
+        };
+        (
+            value(&self.xmin, T::neg_infinity()),
+            value(&self.xmax, T::infinity()),
+        )
+    }
+
+    /// Width of the $i$-th side of the rectangle, that is,
+    /// $x_{\max, i} - x_{\min, i}$, which is infinite if either of the
+    /// bounds is absent or infinite
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `i` is not smaller than the dimension of the
+    /// rectangle
+    ///
+    pub fn width(&self, i: usize) -> T {
+        let (lower, upper) = self.bounds(i);
+        upper - lower
     }
 }
 
@@ -55,24 +209,56 @@ where
     T: OptFloat,
 {
     fn project(&self, x: &mut [T]) {
-        if let Some(xmin) = &self.xmin {
-            x.iter_mut().zip(xmin.iter()).for_each(|(x_, xmin_)| {
-                if *x_ < *xmin_ {
-                    *x_ = *xmin_
+        // scalar bounds are only constructed in pairs (see `from_scalars`)
+        if let (Some(Bound::Scalar(xmin, n)), Some(Bound::Scalar(xmax, _))) = (self.xmin, self.xmax)
+        {
+            x.iter_mut().take(n).for_each(|x_| {
+                if *x_ < xmin {
+                    *x_ = xmin
                 };
-            });
-        }
-
-        if let Some(xmax) = &self.xmax {
-            x.iter_mut().zip(xmax.iter()).for_each(|(x_, xmax_)| {
-                if *x_ > *xmax_ {
-                    *x_ = *xmax_
+                if *x_ > xmax {
+                    *x_ = xmax
                 };
             });
+            return;
         }
+        crate::matrix_operations::clamp_into(
+            x,
+            self.xmin.and_then(|xmin| xmin.as_slice()),
+            self.xmax.and_then(|xmax| xmax.as_slice()),
+        );
     }
 
     fn is_convex(&self) -> bool {
         true
     }
+
+    /// The rectangle is compact if both $x_{\min}$ and $x_{\max}$ are given
+    /// and all their elements are finite
+    fn is_compact(&self) -> bool {
+        self.xmin.is_some_and(|xmin| xmin.is_finite())
+            && self.xmax.is_some_and(|xmax| xmax.is_finite())
+    }
+
+    fn is_separable(&self) -> bool {
+        true
+    }
+
+    /// Support function of the rectangle, $\sigma_R(d) = \sum_{d_i > 0} d_i x_{\max, i}
+    /// + \sum_{d_i < 0} d_i x_{\min, i}$, which is infinite if a bound is missing
+    fn support(&self, d: &[T]) -> Option<T> {
+        let bound_term = |bound: &Option<Bound<'a, T>>, i: usize, di: T| {
+            bound.map_or(T::infinity(), |bound| di * bound.value(i))
+        };
+        let support = d.iter().enumerate().fold(T::zero(), |sum, (i, &di)| {
+            if di > T::zero() {
+                sum + bound_term(&self.xmax, i, di)
+            } else if di < T::zero() {
+                sum + bound_term(&self.xmin, i, di)
+            } else {
+                sum
+            }
+        });
+        Some(support)
+    }
 }
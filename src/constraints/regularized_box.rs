@@ -0,0 +1,138 @@
+use super::Constraint;
+use crate::core::OptFloat;
+
+#[derive(Clone, Copy)]
+///
+/// A box with an $\ell_2$ regularization pull toward a prior
+///
+/// Given a box $R = \\{z \in \mathbb{R}^n {}:{} z_{\min} {}\leq{} z {}\leq{} z_{\max}\\}$,
+/// a prior $\bar{z}$ and a regularization weight $\lambda \geq 0$, this
+/// computes
+///
+/// $$
+/// \mathrm{argmin}_{z\in R}\ \tfrac{1}{2}\Vert{}z-x{}\Vert^2 +
+/// \tfrac{\lambda}{2}\Vert{}z-\bar{z}{}\Vert^2.
+/// $$
+///
+/// This problem is separable and its solution is
+///
+/// $$
+/// z_i = \min\left\\{z_{\max, i}, \max\left\\{z_{\min, i},
+/// \tfrac{x_i + \lambda \bar{z}_i}{1 + \lambda}\right\\}\right\\},
+/// $$
+///
+/// that is, a weighted average of $x$ and the prior, clamped to the box.
+///
+/// Note that this is the proximal operator of the function
+/// $\tfrac{\lambda}{2}\Vert{}\cdot{}-\bar{z}\Vert^2 + \delta_R$, not a
+/// projection on a set (unless $\lambda = 0$). It is nonetheless firmly
+/// nonexpansive, as the proximal operator of a convex function, so it can be
+/// used where a `Constraint` is expected.
+pub struct RegularizedBox<'a, T>
+where
+    T: OptFloat,
+{
+    xmin: Option<&'a [T]>,
+    xmax: Option<&'a [T]>,
+    prior: &'a [T],
+    lambda: T,
+}
+
+impl<'a, T> RegularizedBox<'a, T>
+where
+    T: OptFloat,
+{
+    /// Construct a new regularized box
+    ///
+    /// # Arguments
+    ///
+    /// - `xmin`: minimum value of `z`
+    /// - `xmax`: maximum value of `z`
+    /// - `prior`: the prior, $\bar{z}$
+    /// - `lambda`: the regularization weight, $\lambda$
+    ///
+    /// # Note
+    ///
+    /// `RegularizedBox` does not copy `xmin`, `xmax` and `prior` internally;
+    /// it only keeps references. You may set either of `xmin` and `xmax` (or
+    /// both) to `None`.
+    ///
+    /// # Panics
+    ///
+    /// The method panics if:
+    ///
+    /// - `xmin` or `xmax` have been provided, but their dimensions are not
+    ///   equal to that of `prior`
+    /// - `lambda` is negative
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, RegularizedBox};
+    ///
+    /// let xmin = [-1.0, -1.0];
+    /// let xmax = [1.0, 1.0];
+    /// let prior = [0.5, 0.0];
+    /// let regularized_box = RegularizedBox::new(Some(&xmin), Some(&xmax), &prior, 1.0);
+    /// let mut x = [0.0, 3.0];
+    /// regularized_box.project(&mut x);
+    /// assert_eq!([0.25, 1.0], x);
+    /// ```
+    ///
+    pub fn new(xmin: Option<&'a [T]>, xmax: Option<&'a [T]>, prior: &'a [T], lambda: T) -> Self {
+        assert!(
+            xmin.is_none() || xmin.unwrap().len() == prior.len(),
+            "incompatible dimensions of xmin and prior"
+        );
+        assert!(
+            xmax.is_none() || xmax.unwrap().len() == prior.len(),
+            "incompatible dimensions of xmax and prior"
+        );
+        assert!(lambda >= T::zero(), "lambda must be nonnegative");
+        RegularizedBox {
+            xmin,
+            xmax,
+            prior,
+            lambda,
+        }
+    }
+}
+
+impl<'a, T> Constraint<T> for RegularizedBox<'a, T>
+where
+    T: OptFloat,
+{
+    /// Computes the proximal operator (see the documentation of
+    /// `RegularizedBox`)
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the result
+    ///
+    fn project(&self, x: &mut [T]) {
+        let lambda = self.lambda;
+        x.iter_mut()
+            .zip(self.prior.iter())
+            .for_each(|(x_, prior_)| *x_ = (*x_ + lambda * *prior_) / (T::one() + lambda));
+
+        if let Some(xmin) = &self.xmin {
+            x.iter_mut().zip(xmin.iter()).for_each(|(x_, xmin_)| {
+                if *x_ < *xmin_ {
+                    *x_ = *xmin_
+                };
+            });
+        }
+
+        if let Some(xmax) = &self.xmax {
+            x.iter_mut().zip(xmax.iter()).for_each(|(x_, xmax_)| {
+                if *x_ > *xmax_ {
+                    *x_ = *xmax_
+                };
+            });
+        }
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,137 @@
+use super::workspace::Workspace;
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+/// A set expressed in a rotated frame, $R C = \\{Rz {}:{} z \in C\\}$, where
+/// $R\in\mathbb{R}^{n\times n}$ is an orthogonal matrix
+///
+/// This is useful for sets which are simple (e.g., axis-aligned) in a rotated
+/// frame, such as a box which is aligned with the heading of a vehicle. Since
+/// $R$ is an isometry, the projection on $RC$ is
+///
+/// $$
+/// \Pi_{RC}(x) = R\Pi_C(R^\intercal x).
+/// $$
+///
+/// The set $RC$ is convex if and only if $C$ is convex.
+///
+pub struct Rotated<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    set: &'a C,
+    rotation: &'a [T],
+    n: usize,
+    /// Work memory for $R^\intercal x$, which is kept across projections
+    workspace: Workspace<T>,
+}
+
+impl<'a, C, T> Rotated<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    /// Construct the set $RC$
+    ///
+    /// # Arguments
+    ///
+    /// - `set`: set $C$
+    /// - `rotation`: orthogonal matrix $R$, row-wise data
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `rotation` is not a square matrix, or if it is not
+    /// orthogonal, that is, if an element of $RR^\intercal - I$ exceeds
+    /// $\sqrt{\epsilon}$ in absolute value, where $\epsilon$ is the machine
+    /// epsilon
+    ///
+    /// # Memory allocation
+    ///
+    /// This method allocates a workspace of `n` floats, which is used by the
+    /// projections
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{BallInf, Constraint, Rotated};
+    ///
+    /// // a square rotated by 90 degrees
+    /// let square = BallInf::new(None, 1.0);
+    /// let rotation = [0.0, -1.0, 1.0, 0.0];
+    /// let rotated_square = Rotated::new(&square, &rotation);
+    /// let mut x = [3.0, 0.5];
+    /// rotated_square.project(&mut x);
+    /// assert_eq!([1.0, 0.5], x);
+    /// ```
+    ///
+    pub fn new(set: &'a C, rotation: &'a [T]) -> Self {
+        let n = num::integer::Roots::sqrt(&rotation.len());
+        assert!(n * n == rotation.len(), "rotation must be a square matrix");
+        let tolerance = T::epsilon().sqrt();
+        for i in 0..n {
+            for j in 0..n {
+                let row_i = &rotation[i * n..(i + 1) * n];
+                let row_j = &rotation[j * n..(j + 1) * n];
+                let dot = matrix_operations::inner_product(row_i, row_j);
+                let expected = if i == j { T::one() } else { T::zero() };
+                assert!(
+                    (dot - expected).abs() <= tolerance,
+                    "rotation must be an orthogonal matrix"
+                );
+            }
+        }
+        Rotated {
+            set,
+            rotation,
+            n,
+            workspace: Workspace::new(n, T::zero()),
+        }
+    }
+}
+
+impl<'a, C, T> Constraint<T> for Rotated<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    /// Projects on $RC$
+    ///
+    /// # Panics
+    ///
+    /// The method panics if the length of `x` does not match the dimension
+    /// of the rotation matrix
+    ///
+    /// # Memory allocation
+    ///
+    /// This method uses the workspace allocated by `new`, so it does not
+    /// allocate memory (unless the same set is projected on concurrently from
+    /// several threads)
+    ///
+    fn project(&self, x: &mut [T]) {
+        let n = self.n;
+        assert_eq!(n, x.len(), "x has incompatible dimensions");
+        let row = |i: usize| &self.rotation[i * n..(i + 1) * n];
+
+        self.workspace.with(n, |z| {
+            // z = R' x
+            z.iter_mut().for_each(|zj| *zj = T::zero());
+            x.iter().enumerate().for_each(|(i, &xi)| {
+                z.iter_mut()
+                    .zip(row(i).iter())
+                    .for_each(|(zj, &rij)| *zj += rij * xi);
+            });
+            self.set.project(z);
+
+            // x = R z
+            x.iter_mut().enumerate().for_each(|(i, xi)| {
+                *xi = matrix_operations::inner_product(row(i), z);
+            });
+        });
+    }
+
+    fn is_convex(&self) -> bool {
+        self.set.is_convex()
+    }
+}
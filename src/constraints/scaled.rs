@@ -0,0 +1,85 @@
+use super::Constraint;
+use crate::core::OptFloat;
+
+/// A set of the form $\\{x {}:{} ax \in C\\}$, where $a \neq 0$ is a scalar
+///
+/// This is useful when the set $C$ is described in different units than the
+/// decision variables. Since $x \mapsto ax$ is a scaled isometry, the
+/// projection on this set is
+///
+/// $$
+/// \Pi(x) = \tfrac{1}{a}\Pi_C(ax).
+/// $$
+///
+/// The set is convex if and only if $C$ is convex.
+///
+pub struct ScaledConstraint<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    set: &'a C,
+    scaling: T,
+}
+
+impl<'a, C, T> ScaledConstraint<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    /// Construct the set $\\{x {}:{} ax \in C\\}$
+    ///
+    /// # Arguments
+    ///
+    /// - `set`: set $C$
+    /// - `scaling`: scalar $a$
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `scaling` is zero or not finite
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Ball2, Constraint, ScaledConstraint};
+    ///
+    /// // x in millimetres, the ball in metres
+    /// let ball = Ball2::new(None, 1.0);
+    /// let ball_in_mm = ScaledConstraint::new(&ball, 1e-3);
+    /// let mut x: [f64; 2] = [3000.0, 4000.0];
+    /// ball_in_mm.project(&mut x);
+    /// assert!((x[0] - 600.0).abs() < 1e-9 && (x[1] - 800.0).abs() < 1e-9);
+    /// ```
+    ///
+    pub fn new(set: &'a C, scaling: T) -> Self {
+        assert!(
+            scaling != T::zero() && scaling.is_finite(),
+            "scaling must be nonzero and finite"
+        );
+        ScaledConstraint { set, scaling }
+    }
+}
+
+impl<'a, C, T> Constraint<T> for ScaledConstraint<'a, C, T>
+where
+    C: Constraint<T>,
+    T: OptFloat,
+{
+    fn project(&self, x: &mut [T]) {
+        x.iter_mut().for_each(|xi| *xi *= self.scaling);
+        self.set.project(x);
+        x.iter_mut().for_each(|xi| *xi /= self.scaling);
+    }
+
+    fn is_convex(&self) -> bool {
+        self.set.is_convex()
+    }
+
+    fn is_compact(&self) -> bool {
+        self.set.is_compact()
+    }
+
+    fn is_separable(&self) -> bool {
+        self.set.is_separable()
+    }
+}
@@ -1,15 +1,27 @@
+use super::workspace::Workspace;
 use super::Constraint;
 use crate::core::OptFloat;
-#[derive(Copy, Clone)]
+use crate::SolverError;
+
+/// Relative tolerance below which a nonnegative vector whose entries sum
+/// to (approximately) $\alpha$ is considered to lie on the simplex
+const FEASIBILITY_TOLERANCE: f64 = 1e-12;
+
+#[derive(Clone)]
 /// A simplex with level $\alpha$ is a set of the form
 /// $\Delta_\alpha^n = \\{x \in \mathbb{R}^n {}:{} x \geq 0, \sum_i x_i = \alpha\\}$,
 /// where $\alpha$ is a positive constant.
+///
+/// The projection uses a workspace of $2n$ floats, which is allocated at the
+/// first projection and reused afterwards.
 pub struct Simplex<T>
 where
     T: OptFloat,
 {
     /// Simplex level
     alpha: T,
+    /// Work memory of the projection (the lists `v` and `v_tilde`)
+    workspace: Workspace<T>,
 }
 
 impl<T> Simplex<T>
@@ -20,7 +32,21 @@ where
     /// to specify the dimension of the simplex.
     pub fn new(alpha: T) -> Self {
         assert!(alpha > T::zero(), "alpha is nonpositive");
-        Simplex { alpha }
+        Simplex {
+            alpha,
+            workspace: Workspace::new(0, T::zero()),
+        }
+    }
+
+    /// Construct a new simplex like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `alpha` is not
+    /// positive
+    pub fn try_new(alpha: T) -> Result<Self, SolverError> {
+        if alpha > T::zero() {
+            Ok(Simplex::new(alpha))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
     }
 }
 
@@ -33,62 +59,84 @@ where
     /// See: Laurent Condat. Fast Projection onto the Simplex and the $\ell_1$ Ball.
     /// <em>Mathematical Programming, Series A,</em> Springer, 2016, 158 (1), pp.575-585.
     /// ⟨<a href="https://dx.doi.org/10.1007/s10107-015-0946-6">10.1007/s10107-015-0946-6</a>⟩.
+    ///
+    /// If $x$ already lies on the simplex, that is, $x \geq 0$ and
+    /// $|\sum_i x_i - \alpha| \leq 10^{-12}\alpha$, then $x$ is returned
+    /// unchanged without running the algorithm (this is useful when
+    /// projecting warm-start points which are already feasible).
     fn project(&self, x: &mut [T]) {
         let a = &self.alpha;
 
-        // ---- step 1
-        let mut v = Vec::<T>::with_capacity(x.len()); // vector containing x[0]
-        v.push(x[0]);
-        let mut v_size_old: i64 = -1; // 64 bit signed int
-        let mut v_tilde: Vec<T> = Vec::new(); // empty vector of T
-        let mut rho: T = x[0] - *a; // T float
+        // ---- step 0: early exit if x is already on the simplex
+        if x.iter().all(|x_n| *x_n >= T::zero()) {
+            let tolerance = T::from(FEASIBILITY_TOLERANCE).unwrap() * *a;
+            if (crate::matrix_operations::sum(x) - *a).abs() <= tolerance {
+                return;
+            }
+        }
+
+        // The lists v and v_tilde hold distinct elements of x, so they both
+        // fit in slices of length n of the workspace
+        let n = x.len();
+        let rho = self.workspace.with(2 * n, |work| {
+            let (v, v_tilde) = work.split_at_mut(n);
+
+            // ---- step 1
+            v[0] = x[0]; // v contains x[0]
+            let mut v_len = 1;
+            let mut v_tilde_len = 0;
+            let mut rho: T = x[0] - *a; // T float
 
-        // ---- step 2
-        x.iter().skip(1).for_each(|x_n| {
-            if *x_n > rho {
-                let len_plus_one = T::from(v.len() + 1).unwrap();
-                rho = rho + (*x_n - rho) / len_plus_one;
-                if rho > *x_n - *a {
-                    v.push(*x_n);
-                } else {
-                    v_tilde.extend(&v);
-                    v = vec![*x_n];
-                    rho = *x_n - *a;
+            // ---- step 2
+            x.iter().skip(1).for_each(|x_n| {
+                if *x_n > rho {
+                    let len_plus_one = T::from(v_len + 1).unwrap();
+                    rho = rho + (*x_n - rho) / len_plus_one;
+                    if rho > *x_n - *a {
+                        v[v_len] = *x_n;
+                        v_len += 1;
+                    } else {
+                        v_tilde[v_tilde_len..v_tilde_len + v_len].copy_from_slice(&v[..v_len]);
+                        v_tilde_len += v_len;
+                        v[0] = *x_n;
+                        v_len = 1;
+                        rho = *x_n - *a;
+                    }
                 }
-            }
-        });
+            });
 
-        // ---- step 3
-        if !v_tilde.is_empty() {
-            v_tilde.iter().for_each(|v_t_n| {
+            // ---- step 3
+            v_tilde[..v_tilde_len].iter().for_each(|v_t_n| {
                 if *v_t_n > rho {
-                    v.push(*v_t_n);
-                    let len_t = T::from(v.len()).unwrap();
+                    v[v_len] = *v_t_n;
+                    v_len += 1;
+                    let len_t = T::from(v_len).unwrap();
                     rho = rho + (*v_t_n - rho) / len_t;
                 }
             });
-        }
 
-        // ---- step 4
-        let mut keep_running = true;
-        while keep_running {
-            let mut hit_list: Vec<usize> = Vec::with_capacity(x.len());
-            let mut current_len_v = v.len() as i64;
-            v.iter().enumerate().for_each(|(n, v_n)| {
-                if *v_n <= rho {
-                    hit_list.push(n);
-                    current_len_v -= 1;
-                    let current_len_t = T::from(current_len_v).unwrap();
-                    rho = rho + (rho - *v_n) / current_len_t;
+            // ---- step 4
+            // (the elements of v which are not removed are moved to the
+            // front of v, in order)
+            loop {
+                let mut current_len_v = v_len;
+                for k in 0..v_len {
+                    let v_k = v[k];
+                    if v_k <= rho {
+                        current_len_v -= 1;
+                        let current_len_t = T::from(current_len_v).unwrap();
+                        rho = rho + (rho - v_k) / current_len_t;
+                    } else {
+                        v[k + current_len_v - v_len] = v_k;
+                    }
                 }
-            });
-            hit_list.iter().rev().for_each(|target| {
-                // remove in reverse to keep indexing correct
-                v.remove(*target);
-            });
-            keep_running = current_len_v != v_size_old;
-            v_size_old = current_len_v;
-        }
+                if current_len_v == v_len {
+                    break;
+                }
+                v_len = current_len_v;
+            }
+            rho
+        });
 
         // ---- step 6
         let zero: T = T::zero();
@@ -98,4 +146,8 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,183 @@
+use super::workspace::Workspace;
+use super::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+const DEFAULT_MAX_ITER: usize = 500;
+const DEFAULT_TOLERANCE: f64 = 1e-10;
+
+/// The intersection of two second-order cones, $C_1 \cap C_2$
+///
+/// The projection on the intersection of two second-order cones (e.g., with
+/// different parameters $\alpha$, or where one of them is rotated using
+/// [Rotated](struct.Rotated.html)) is not available in closed form. It is
+/// computed with Dykstra's alternating projections algorithm,
+///
+/// $$
+/// \begin{aligned}
+/// y_k &= \Pi_{C_1}(x_k + p_k), & p_{k+1} &= x_k + p_k - y_k,
+/// \\\\
+/// x_{k+1} &= \Pi_{C_2}(y_k + q_k), & q_{k+1} &= y_k + q_k - x_{k+1},
+/// \end{aligned}
+/// $$
+///
+/// with $x_0 = x$ and $p_0 = q_0 = 0$, which converges to the projection of
+/// $x$ on $C_1 \cap C_2$. The iterations stop when the changes in $x_k$, $p_k$
+/// and $q_k$ (in the infinity norm) all drop below a given tolerance, or when
+/// a maximum number of iterations is reached. Each iteration costs one
+/// projection on each cone.
+///
+/// The result, $x_{k+1}$, is a projection on $C_2$, so it belongs to $C_2$, but
+/// it only belongs to $C_1$ approximately: since $y_k \in C_1$ and
+/// $x_{k+1} - y_k = q_k - q_{k+1}$, its distance to $C_1$ is at most
+/// $\Vert q_{k+1} - q_k \Vert$, that is, at most $\sqrt{n}$ times the tolerance
+/// if the iterations stop before the maximum number of iterations.
+///
+/// The vectors $y_k$, $p_k$, $q_k$ and $x_k$ are stored in a workspace, which
+/// is allocated at the first projection and reused afterwards.
+///
+/// Although this is meant for second-order cones, any two convex sets can
+/// be used.
+///
+pub struct SocIntersection<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    cone_a: &'a A,
+    cone_b: &'a B,
+    max_iter: usize,
+    tolerance: T,
+    workspace: Workspace<T>,
+}
+
+impl<'a, A, B, T> SocIntersection<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    /// Construct the intersection of two cones, $C_1 \cap C_2$
+    ///
+    /// # Arguments
+    ///
+    /// - `cone_a`: cone $C_1$
+    /// - `cone_b`: cone $C_2$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use optimization_engine::constraints::{Constraint, SecondOrderCone, SocIntersection};
+    ///
+    /// let wide_cone = SecondOrderCone::new(1.0);
+    /// let narrow_cone = SecondOrderCone::new(0.5);
+    /// let intersection = SocIntersection::new(&wide_cone, &narrow_cone);
+    /// let mut x: [f64; 3] = [1.0, 0.0, 1.0];
+    /// intersection.project(&mut x);
+    /// assert!((x[0] - 0.6).abs() < 1e-8 && (x[2] - 1.2).abs() < 1e-8);
+    /// ```
+    ///
+    pub fn new(cone_a: &'a A, cone_b: &'a B) -> Self {
+        SocIntersection {
+            cone_a,
+            cone_b,
+            max_iter: DEFAULT_MAX_ITER,
+            tolerance: T::from(DEFAULT_TOLERANCE).unwrap(),
+            workspace: Workspace::new(0, T::zero()),
+        }
+    }
+
+    /// Sets the maximum number of iterations of the projection (the default
+    /// value is `500`)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `max_iter` is zero
+    ///
+    pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        assert!(max_iter > 0, "max_iter must be positive");
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Sets the tolerance of the projection (the default value is `1e-10`)
+    ///
+    /// # Panics
+    ///
+    /// The method panics if `tolerance` is not positive
+    ///
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        assert!(tolerance > T::zero(), "tolerance must be positive");
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl<'a, A, B, T> Constraint<T> for SocIntersection<'a, A, B, T>
+where
+    A: Constraint<T>,
+    B: Constraint<T>,
+    T: OptFloat,
+{
+    /// Projects on $C_1 \cap C_2$ using Dykstra's algorithm
+    ///
+    /// # Arguments
+    ///
+    /// - `x`: The given vector $x$ is updated with the (approximate) projection
+    ///   on the set; it belongs to $C_2$, but only approximately to $C_1$
+    ///
+    /// # Memory allocation
+    ///
+    /// At the first call (or if `x` is longer than at any previous call),
+    /// this method allocates a workspace of four vectors of the same length
+    /// as `x`
+    ///
+    fn project(&self, x: &mut [T]) {
+        let n = x.len();
+        self.workspace.with(4 * n, |work| {
+            let (y, work) = work.split_at_mut(n);
+            let (p, work) = work.split_at_mut(n);
+            let (q, x_previous) = work.split_at_mut(n);
+            p.fill(T::zero());
+            q.fill(T::zero());
+
+            for _ in 0..self.max_iter {
+                // y <-- Proj_A(x + p), p <-- x + p - y
+                y.iter_mut()
+                    .zip(x.iter())
+                    .zip(p.iter())
+                    .for_each(|((yi, &xi), &pi)| *yi = xi + pi);
+                self.cone_a.project(y);
+                let change_p = matrix_operations::norm_inf_diff(x, y);
+                p.iter_mut()
+                    .zip(x.iter())
+                    .zip(y.iter())
+                    .for_each(|((pi, &xi), &yi)| *pi += xi - yi);
+
+                // x <-- Proj_B(y + q), q <-- y + q - x
+                x_previous.copy_from_slice(x);
+                x.iter_mut()
+                    .zip(y.iter())
+                    .zip(q.iter())
+                    .for_each(|((xi, &yi), &qi)| *xi = yi + qi);
+                self.cone_b.project(x);
+                let change_q = matrix_operations::norm_inf_diff(y, x);
+                q.iter_mut()
+                    .zip(y.iter())
+                    .zip(x.iter())
+                    .for_each(|((qi, &yi), &xi)| *qi += yi - xi);
+
+                let change_x = matrix_operations::norm_inf_diff(x, x_previous);
+                if change_x.max(change_p).max(change_q) <= self.tolerance {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// The intersection of two convex sets is convex
+    fn is_convex(&self) -> bool {
+        self.cone_a.is_convex() && self.cone_b.is_convex()
+    }
+}
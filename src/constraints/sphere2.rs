@@ -1,5 +1,6 @@
 use super::Constraint;
 use crate::core::OptFloat;
+use crate::{matrix_operations, SolverError};
 #[derive(Copy, Clone)]
 /// A Euclidean sphere, that is, a set given by $S_2^r = \\{x \in \mathbb{R}^n {}:{} \Vert{}x{}\Vert = r\\}$
 /// or a Euclidean sphere centered at a point $x_c$, that is, $S_2^{x_c, r} = \\{x \in \mathbb{R}^n {}:{} \Vert{}x-x_c{}\Vert = r\\}$
@@ -9,6 +10,16 @@ where
 {
     center: Option<&'a [T]>,
     radius: T,
+    fallback: Fallback<'a, T>,
+}
+
+/// Direction along which the center of the sphere is projected
+#[derive(Copy, Clone)]
+enum Fallback<'a, T> {
+    /// along the $i$-th coordinate axis
+    Axis(usize),
+    /// along a given unit vector
+    Direction(&'a [T]),
 }
 
 impl<'a, T> Sphere2<'a, T>
@@ -19,7 +30,99 @@ where
     /// If no `center` is given, then it is assumed to be in the origin
     pub fn new(center: Option<&'a [T]>, radius: T) -> Self {
         assert!(radius > T::zero());
-        Sphere2 { center, radius }
+        Sphere2 {
+            center,
+            radius,
+            fallback: Fallback::Axis(0),
+        }
+    }
+
+    /// Construct a new Euclidean sphere like `new`, but returns
+    /// `SolverError::InvalidArgument` instead of panicking if `radius` is not
+    /// positive
+    pub fn try_new(center: Option<&'a [T]>, radius: T) -> Result<Self, SolverError> {
+        if radius > T::zero() {
+            Ok(Sphere2::new(center, radius))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
+    }
+
+    /// Radius of the sphere
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    /// Center of the sphere, or `None` if it is centered at the origin
+    pub fn center(&self) -> Option<&'a [T]> {
+        self.center
+    }
+
+    /// Sets the coordinate axis along which the center of the sphere is
+    /// projected (the default is `0`)
+    ///
+    /// The projection of the center, $c$, on the sphere is multi-valued; with
+    /// this option, it is $c + r e_i$, where $e_i$ is the $i$-th unit vector.
+    ///
+    /// ## Arguments
+    ///
+    /// - `axis`: the index of the coordinate axis, $i$
+    ///
+    /// ## Panics
+    ///
+    /// The projection of the center panics if `axis` is not smaller than the
+    /// dimension of the vector being projected
+    ///
+    pub fn with_fallback_axis(mut self, axis: usize) -> Self {
+        self.fallback = Fallback::Axis(axis);
+        self
+    }
+
+    /// Sets the direction along which the center of the sphere is projected
+    ///
+    /// The projection of the center, $c$, on the sphere is multi-valued; with
+    /// this option, it is $c + r d$, where $d$ is the given unit vector.
+    ///
+    /// ## Arguments
+    ///
+    /// - `direction`: a unit vector, $d$, of the same dimension as the sphere
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the Euclidean norm of `direction` is not equal to
+    /// one (up to a tolerance of `1e-8`), or if the dimensions of `direction`
+    /// and `center` differ
+    ///
+    pub fn with_fallback_direction(mut self, direction: &'a [T]) -> Self {
+        let norm_direction = crate::matrix_operations::norm2(direction);
+        assert!(
+            (norm_direction - T::one()).abs() <= T::from(1e-8).unwrap(),
+            "direction must be a unit vector"
+        );
+        assert!(
+            self.center
+                .is_none_or(|center| center.len() == direction.len()),
+            "incompatible dimensions of center and direction"
+        );
+        self.fallback = Fallback::Direction(direction);
+        self
+    }
+
+    /// Moves `x` by a distance equal to the radius along the fallback direction
+    fn move_along_fallback(&self, x: &mut [T]) {
+        match self.fallback {
+            Fallback::Axis(axis) => x[axis] += self.radius,
+            Fallback::Direction(direction) => {
+                assert_eq!(
+                    x.len(),
+                    direction.len(),
+                    "x and direction have different lengths"
+                );
+                x.iter_mut()
+                    .zip(direction.iter())
+                    .for_each(|(x_, &d)| *x_ += self.radius * d);
+            }
+        }
     }
 }
 
@@ -36,7 +139,8 @@ where
     ///
     /// but for $x=c$, the projection is multi-valued. In particular, let
     /// $y = P_{S_{r, c}}(c)$. Then $y_1 = c_1 + r$ and $y_i = c_i$ for
-    /// $i=2,\ldots, n$.
+    /// $i=2,\ldots, n$, unless a different direction is chosen with
+    /// `with_fallback_axis` or `with_fallback_direction`.
     ///
     /// ## Arguments
     ///
@@ -45,23 +149,22 @@ where
     fn project(&self, x: &mut [T]) {
         let epsilon = T::from(1e-12).unwrap();
         if let Some(center) = &self.center {
-            let norm_difference = crate::matrix_operations::norm2_squared_diff(x, center).sqrt();
-            if norm_difference <= epsilon {
+            // x ← (x - c) / ||x - c||, then x ← c + r * x
+            x.iter_mut().zip(center.iter()).for_each(|(x, &c)| *x -= c);
+            if matrix_operations::normalize_in_place(x, epsilon) == T::zero() {
                 x.copy_from_slice(center);
-                x[0] += self.radius;
+                self.move_along_fallback(x);
                 return;
             }
-            x.iter_mut().zip(center.iter()).for_each(|(x, c)| {
-                *x = *c + self.radius * (*x - *c) / norm_difference;
-            });
+            x.iter_mut()
+                .zip(center.iter())
+                .for_each(|(x, &c)| *x = c + self.radius * *x);
         } else {
-            let norm_x = crate::matrix_operations::norm2(x);
-            if norm_x <= epsilon {
-                x[0] += self.radius;
+            if matrix_operations::normalize_in_place(x, epsilon) == T::zero() {
+                self.move_along_fallback(x);
                 return;
             }
-            let norm_over_radius = self.radius / norm_x;
-            x.iter_mut().for_each(|x_| *x_ = *x_ * norm_over_radius);
+            matrix_operations::scale_in_place(x, self.radius);
         }
     }
 
@@ -70,4 +173,8 @@ where
     fn is_convex(&self) -> bool {
         false
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
 }
@@ -226,6 +226,85 @@ fn t_rectangle_only_xmax() {
     );
 }
 
+#[test]
+fn t_rectangle_from_scalars() {
+    let xmin = [-1.5; 5];
+    let xmax = [2.0; 5];
+    let rectangle_slices = Rectangle::new(Some(&xmin[..]), Some(&xmax[..]));
+    let rectangle_scalars = Rectangle::from_scalars(5, -1.5, 2.0);
+    let mut x = [-10.0, -1.0, 0.0, 2.5, 30.0];
+    let mut x_scalars = x;
+
+    rectangle_slices.project(&mut x);
+    rectangle_scalars.project(&mut x_scalars);
+
+    unit_test_utils::assert_nearly_equal_array(&x, &x_scalars, 1e-12, 1e-14, "projection");
+    assert!(rectangle_scalars.is_convex());
+}
+
+#[test]
+fn t_rectangle_from_scalars_infinite() {
+    let rectangle = Rectangle::from_scalars(3, f64::NEG_INFINITY, 1.0);
+    let mut x = [-1e10, 0.5, 3.0];
+
+    rectangle.project(&mut x);
+
+    unit_test_utils::assert_nearly_equal_array(&[-1e10, 0.5, 1.0], &x, 1e-12, 1e-14, "projection");
+}
+
+#[test]
+fn t_rectangle_bounds_lower_only() {
+    let xmin = [-1.0, 2.0];
+    let rectangle = Rectangle::new(Some(&xmin), None);
+    assert_eq!((-1.0, f64::INFINITY), rectangle.bounds(0));
+    assert_eq!((2.0, f64::INFINITY), rectangle.bounds(1));
+    assert_eq!(f64::INFINITY, rectangle.width(1));
+}
+
+#[test]
+fn t_rectangle_bounds_upper_only() {
+    let xmax = [3.0, 0.5];
+    let rectangle = Rectangle::new(None, Some(&xmax));
+    assert_eq!((f64::NEG_INFINITY, 3.0), rectangle.bounds(0));
+    assert_eq!((f64::NEG_INFINITY, 0.5), rectangle.bounds(1));
+    assert_eq!(f64::INFINITY, rectangle.width(0));
+}
+
+#[test]
+fn t_rectangle_bounds_both() {
+    let xmin = [-1.0, f64::NEG_INFINITY, 0.0];
+    let xmax = [2.0, 1.0, 0.0];
+    let rectangle = Rectangle::new(Some(&xmin), Some(&xmax));
+    assert_eq!((-1.0, 2.0), rectangle.bounds(0));
+    assert_eq!((f64::NEG_INFINITY, 1.0), rectangle.bounds(1));
+    assert_eq!(3.0, rectangle.width(0));
+    assert_eq!(f64::INFINITY, rectangle.width(1));
+    assert_eq!(0.0, rectangle.width(2));
+
+    let rectangle_scalars = Rectangle::from_scalars(4, -0.5, 1.5);
+    assert_eq!((-0.5, 1.5), rectangle_scalars.bounds(3));
+    assert_eq!(2.0, rectangle_scalars.width(2));
+}
+
+#[test]
+#[should_panic]
+fn t_rectangle_bounds_out_of_range() {
+    let rectangle = Rectangle::from_scalars(3, -1.0, 1.0);
+    let _ = rectangle.bounds(3);
+}
+
+#[test]
+#[should_panic]
+fn t_rectangle_from_scalars_xmin_larger_than_xmax() {
+    let _rectangle = Rectangle::from_scalars(3, 1.0, -1.0);
+}
+
+#[test]
+#[should_panic]
+fn t_rectangle_from_scalars_zero_dimension() {
+    let _rectangle = Rectangle::from_scalars(0, -1.0, 1.0);
+}
+
 #[test]
 fn t_ball2_at_origin() {
     let radius = 1.0;
@@ -272,7 +351,7 @@ fn t_ball2_at_center_different_radius_outside() {
     let center = [-0.8, -1.1];
     let ball = Ball2::new(Some(&center), radius);
     ball.project(&mut x);
-    let norm_x_minus_c = crate::matrix_operations::norm2_squared_diff(&x, &center).sqrt();
+    let norm_x_minus_c = crate::matrix_operations::norm2_diff(&x, &center);
     unit_test_utils::assert_nearly_equal(radius, norm_x_minus_c, 1e-10, 1e-12, "wrong norm");
 }
 
@@ -463,6 +542,189 @@ fn t_second_order_cone_short_vector() {
     soc.project(&mut _x);
 }
 
+#[test]
+fn t_frustum_inside() {
+    let frustum = Frustum::new(0.5, 1.0, 3.0);
+    let mut x = [0.3, -0.4, 2.0];
+    let x_copy = x;
+    frustum.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&x_copy, &x, 1e-12, 1e-14, "x has been modified");
+}
+
+#[test]
+fn t_frustum_above_top() {
+    let frustum = Frustum::new(0.5, 1.0, 3.0);
+    // inside the cone, above the top face
+    let mut x = [0.3, -0.4, 5.0];
+    frustum.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.3, -0.4, 3.0], &x, 1e-12, 1e-14, "x");
+    // outside the cone, above the top face
+    let mut x = [6.0, 8.0, 5.0];
+    frustum.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.9, 1.2, 3.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_frustum_below_bottom() {
+    let frustum = Frustum::new(0.5, 1.0, 3.0);
+    // inside the cone, below the bottom face
+    let mut x = [0.3, 0.0, 0.8];
+    frustum.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.3, 0.0, 1.0], &x, 1e-12, 1e-14, "x");
+    // projection on the cone is the origin
+    let mut x = [3.0, 4.0, -10.0];
+    frustum.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.3, 0.4, 1.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_frustum_outside_cone_wall() {
+    let alpha = 0.5;
+    let frustum = Frustum::new(alpha, 1.0, 3.0);
+    let soc = SecondOrderCone::new(alpha);
+    let mut x = [3.0, 0.0, 1.5];
+    let mut x_soc = x;
+    frustum.project(&mut x);
+    soc.project(&mut x_soc);
+    // the projection on the cone lies within the height band
+    assert!(x_soc[2] >= 1.0 && x_soc[2] <= 3.0);
+    unit_test_utils::assert_nearly_equal_array(&x_soc, &x, 1e-12, 1e-14, "x");
+    unit_test_utils::assert_nearly_equal(alpha * x[2], x[0], 1e-12, 1e-14, "not on the wall");
+    assert!(frustum.is_convex());
+}
+
+#[test]
+fn t_frustum_projection_is_optimal() {
+    // compare against points on a fine grid of the (2D) frustum
+    let frustum = Frustum::new(0.5, 1.0, 3.0);
+    for x in [
+        [4.0, 0.5],
+        [-4.0, 2.5],
+        [0.1, -1.0],
+        [2.0, 3.5],
+        [-0.6, 1.1],
+    ]
+    .iter()
+    {
+        let mut x_proj = *x;
+        frustum.project(&mut x_proj);
+        let dist = crate::matrix_operations::norm2_squared_diff(x, &x_proj);
+        for i in 0..=200 {
+            let t = 1.0 + 2.0 * (i as f64) / 200.0;
+            for j in 0..=100 {
+                let y = 0.5 * t * (2.0 * (j as f64) / 100.0 - 1.0);
+                assert!(dist <= crate::matrix_operations::norm2_squared_diff(x, &[y, t]) + 1e-12);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn t_frustum_wrong_bounds() {
+    let _ = Frustum::new(1.0, 2.0, 1.0);
+}
+
+#[test]
+fn t_soc_intersection_tighter_cone_dominates() {
+    let wide_cone = SecondOrderCone::new(1.0);
+    let narrow_cone = SecondOrderCone::new(0.5);
+    let intersection = SocIntersection::new(&wide_cone, &narrow_cone);
+    let intersection_reversed = SocIntersection::new(&narrow_cone, &wide_cone);
+    for x in [
+        [3.0, -1.0, 0.5],
+        [0.1, 0.2, 2.0],
+        [1.0, 1.0, -4.0],
+        [-2.0, 0.5, 1.0],
+    ]
+    .iter()
+    {
+        let mut x_narrow = *x;
+        narrow_cone.project(&mut x_narrow);
+        let mut x_intersection = *x;
+        intersection.project(&mut x_intersection);
+        unit_test_utils::assert_nearly_equal_array(
+            &x_narrow,
+            &x_intersection,
+            1e-8,
+            1e-10,
+            "wrong projection on the intersection",
+        );
+        let mut x_intersection = *x;
+        intersection_reversed.project(&mut x_intersection);
+        unit_test_utils::assert_nearly_equal_array(
+            &x_narrow,
+            &x_intersection,
+            1e-8,
+            1e-10,
+            "wrong projection on the intersection (reversed)",
+        );
+    }
+    assert!(intersection.is_convex());
+}
+
+#[test]
+fn t_soc_intersection_brute_force() {
+    // the second cone is rotated by 30 degrees about the first axis
+    let cone = SecondOrderCone::new(1.0);
+    let (c, s) = (30f64.to_radians().cos(), 30f64.to_radians().sin());
+    let rotation = [1.0, 0.0, 0.0, 0.0, c, -s, 0.0, s, c];
+    let rotated_cone = Rotated::new(&cone, &rotation);
+    let intersection = SocIntersection::new(&cone, &rotated_cone);
+    let is_in_intersection = |z: &[f64]| {
+        let in_cone = |z: &[f64]| crate::matrix_operations::norm2(&z[..2]) <= z[2];
+        let z_rotated = [z[0], c * z[1] + s * z[2], -s * z[1] + c * z[2]];
+        in_cone(z) && in_cone(&z_rotated)
+    };
+
+    let x = [1.0, 1.5, 0.5];
+    let mut x_proj = x;
+    intersection.project(&mut x_proj);
+    let slack = 1e-7;
+    assert!(cone.contains(&x_proj, slack) && rotated_cone.contains(&x_proj, slack));
+    let dist = crate::matrix_operations::norm2_diff(&x, &x_proj);
+
+    // no point of the intersection on a grid is closer to x than x_proj
+    let h = 0.025;
+    for i in -80..=80 {
+        for j in -80..=80 {
+            for k in 0..=80 {
+                let z = [i as f64 * h, j as f64 * h, k as f64 * h];
+                if is_in_intersection(&z) {
+                    let dist_z = crate::matrix_operations::norm2_diff(&x, &z);
+                    assert!(dist <= dist_z + 1e-8);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn t_soc_intersection_approximately_feasible() {
+    let cone = SecondOrderCone::new(1.0);
+    let (c, s) = (30f64.to_radians().cos(), 30f64.to_radians().sin());
+    let rotation = [1.0, 0.0, 0.0, 0.0, c, -s, 0.0, s, c];
+    let rotated_cone = Rotated::new(&cone, &rotation);
+    let tolerance = 1e-4;
+    let intersection = SocIntersection::new(&cone, &rotated_cone).with_tolerance(tolerance);
+
+    let x = [1.0, 1.5, 0.5];
+    let mut x_proj = x;
+    intersection.project(&mut x_proj);
+
+    // the result belongs to the second set and is close to the first one
+    assert!(rotated_cone.contains(&x_proj, 1e-12));
+    let mut x_proj_a = x_proj;
+    cone.project(&mut x_proj_a);
+    let distance_to_a = crate::matrix_operations::norm2_diff(&x_proj, &x_proj_a);
+    assert!(distance_to_a <= 3f64.sqrt() * tolerance);
+
+    // the workspace does not carry over any state between projections
+    let mut x_proj_again = x;
+    intersection.project(&mut x_proj_again);
+    assert_eq!(x_proj, x_proj_again);
+}
+
 #[test]
 fn t_cartesian_product_dimension() {
     let data: &[&[f64]] = &[&[0.0, 0.0], &[1.0, 1.0]];
@@ -641,6 +903,20 @@ fn t_is_convex_cartesian_product() {
     assert!(!cartesian_product.is_convex());
 }
 
+#[test]
+fn t_hyperplane_owned() {
+    let normal_vector = [1.0, 2.0, 3.0];
+    let offset = 1.0;
+    let hyperplane = Hyperplane::new(&normal_vector, offset);
+    let hyperplane_owned = Hyperplane::owned(normal_vector.to_vec(), offset);
+    let mut x = [-1., 3., 5.];
+    let mut x_owned = x;
+    hyperplane.project(&mut x);
+    hyperplane_owned.project(&mut x_owned);
+    unit_test_utils::assert_nearly_equal_array(&x, &x_owned, 1e-14, 1e-14, "projection");
+    assert!(hyperplane_owned.is_convex());
+}
+
 #[test]
 fn t_hyperplane_is_convex() {
     let normal_vector = [1.0, 2.0, 3.0];
@@ -716,6 +992,29 @@ fn t_simplex_projection_random_optimality() {
     }
 }
 
+#[test]
+fn t_simplex_reused_for_different_dimensions() {
+    // the workspace of a simplex which has been used for larger (and smaller)
+    // vectors gives the same projection as a new simplex
+    let alpha = 2.5;
+    let simplex = Simplex::new(alpha);
+    for &n in [20, 5, 20, 1, 12].iter() {
+        let x: Vec<f64> = (0..n).map(|i| ((i * 7 % 11) as f64 - 4.0) * 0.7).collect();
+        let mut x_reused = x.clone();
+        simplex.project(&mut x_reused);
+        let mut x_new = x;
+        Simplex::new(alpha).project(&mut x_new);
+        assert_eq!(x_new, x_reused);
+        unit_test_utils::assert_nearly_equal(
+            alpha,
+            crate::matrix_operations::sum(&x_reused),
+            1e-10,
+            1e-12,
+            "sum",
+        );
+    }
+}
+
 #[test]
 #[should_panic]
 fn t_simplex_alpha_zero() {
@@ -868,6 +1167,206 @@ fn t_sphere2_center_projection_of_center() {
     unit_test_utils::assert_nearly_equal(radius, norm_out, 1e-10, 1e-12, "norm_out is not 1.0");
 }
 
+#[test]
+fn t_sphere2_fallback_axis() {
+    let radius = 1.3;
+    let center = [-3.0, 5.0, 1.0];
+    let mut x = center;
+    let sphere = Sphere2::new(Some(&center), radius).with_fallback_axis(2);
+    sphere.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[-3.0, 5.0, 2.3], &x, 1e-12, 1e-14, "x");
+
+    let mut x_zero = [0.0; 3];
+    let sphere_at_origin = Sphere2::new(None, radius).with_fallback_axis(1);
+    sphere_at_origin.project(&mut x_zero);
+    unit_test_utils::assert_nearly_equal_array(&[0.0, 1.3, 0.0], &x_zero, 1e-12, 1e-14, "x0");
+}
+
+#[test]
+fn t_sphere2_fallback_direction() {
+    let radius = 2.0;
+    let center = [1.0, -1.0];
+    let direction = [0.6, -0.8];
+    let mut x = center;
+    let sphere = Sphere2::new(Some(&center), radius).with_fallback_direction(&direction);
+    sphere.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[2.2, -2.6], &x, 1e-12, 1e-14, "x");
+    let distance = crate::matrix_operations::norm2_diff(&x, &center);
+    unit_test_utils::assert_nearly_equal(radius, distance, 1e-12, 1e-14, "distance");
+}
+
+#[test]
+#[should_panic]
+fn t_sphere2_fallback_direction_not_unit() {
+    let direction = [1.0, 1.0];
+    let _sphere = Sphere2::new(None, 1.0).with_fallback_direction(&direction);
+}
+
+#[test]
+fn t_ball_complement_interior() {
+    let center = [1.0, 1.0];
+    let radius = 2.0;
+    let ball_complement = BallComplement::new(Some(&center), radius);
+    let mut x = [1.5, 0.5];
+    ball_complement.project(&mut x);
+    let s = 2.0_f64.sqrt();
+    unit_test_utils::assert_nearly_equal_array(
+        &[1.0 + s, 1.0 - s],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong projection of interior point",
+    );
+    assert!(!ball_complement.is_convex());
+}
+
+#[test]
+fn t_ball_complement_boundary_and_exterior() {
+    let radius = 2.0;
+    let ball_complement = BallComplement::new(None, radius);
+    let mut x_boundary = [0.0, -2.0, 0.0];
+    ball_complement.project(&mut x_boundary);
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.0, -2.0, 0.0],
+        &x_boundary,
+        1e-12,
+        1e-14,
+        "boundary point was modified",
+    );
+    let mut x_exterior = [3.0, -4.0, 1.0];
+    ball_complement.project(&mut x_exterior);
+    unit_test_utils::assert_nearly_equal_array(
+        &[3.0, -4.0, 1.0],
+        &x_exterior,
+        1e-12,
+        1e-14,
+        "exterior point was modified",
+    );
+    let mut x_interior = [0.0, 0.0, 0.5];
+    ball_complement.project(&mut x_interior);
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.0, 0.0, 2.0],
+        &x_interior,
+        1e-12,
+        1e-14,
+        "wrong projection of interior point",
+    );
+}
+
+#[test]
+fn t_ball_complement_center() {
+    let center = [1.0, -1.0, 3.0];
+    let radius = 0.5;
+    let ball_complement = BallComplement::new(Some(&center), radius);
+    let mut x = center;
+    ball_complement.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(
+        &[1.5, -1.0, 3.0],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong projection of center",
+    );
+
+    let ball_complement_at_origin = BallComplement::new(None, radius);
+    let mut x_zero = [0.0; 2];
+    ball_complement_at_origin.project(&mut x_zero);
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.5, 0.0],
+        &x_zero,
+        1e-12,
+        1e-14,
+        "wrong projection of origin",
+    );
+}
+
+#[test]
+#[should_panic]
+fn t_ball_complement_radius_not_positive() {
+    let _ = BallComplement::<f64>::new(None, 0.0);
+}
+
+#[test]
+fn t_ball_accessors() {
+    let center = [1.0, -2.0, 0.5];
+    let ball1 = Ball1::new(Some(&center), 1.5);
+    assert_eq!(1.5, ball1.radius());
+    assert_eq!(Some(&center[..]), ball1.center());
+    let ball2 = Ball2::new(None, 2.5);
+    assert_eq!(2.5, ball2.radius());
+    assert!(ball2.center().is_none());
+    let ball_inf = BallInf::new(Some(&center), 0.5);
+    assert_eq!(0.5, ball_inf.radius());
+    assert_eq!(Some(&center[..]), ball_inf.center());
+    let sphere = Sphere2::new(Some(&center), 3.0);
+    assert_eq!(3.0, sphere.radius());
+    assert_eq!(Some(&center[..]), sphere.center());
+    assert!(Sphere2::<f64>::new(None, 1.0).center().is_none());
+}
+
+#[test]
+fn t_is_compact_bounded_sets() {
+    let center = [1.0, 2.0];
+    assert!(Constraint::<f64>::is_compact(&Ball1::new(
+        Some(&center),
+        1.0
+    )));
+    assert!(Constraint::<f64>::is_compact(&Ball2::new(None, 1.0)));
+    assert!(Constraint::<f64>::is_compact(&BallInf::new(None, 1.0)));
+    assert!(Constraint::<f64>::is_compact(&Sphere2::new(
+        Some(&center),
+        1.0
+    )));
+    assert!(Constraint::<f64>::is_compact(&Simplex::new(1.0)));
+    assert!(Constraint::<f64>::is_compact(&Zero::new()));
+    let data: &[&[f64]] = &[&[0.0, 0.0], &[1.0, 1.0]];
+    assert!(FiniteSet::new(data).is_compact());
+}
+
+#[test]
+fn t_is_compact_unbounded_sets() {
+    assert!(!Constraint::<f64>::is_compact(&NoConstraints::new()));
+    assert!(!Constraint::<f64>::is_compact(&BallComplement::new(
+        None, 1.0
+    )));
+    assert!(!SecondOrderCone::new(1.0).is_compact());
+    let normal_vector = [1.0, 2.0];
+    assert!(!Halfspace::new(&normal_vector, 1.0).is_compact());
+}
+
+#[test]
+fn t_is_separable() {
+    let xmin = [-1.0, -2.0];
+    assert!(Rectangle::new(Some(&xmin), None).is_separable());
+    assert!(Constraint::<f64>::is_separable(&NoConstraints::new()));
+    assert!(Constraint::<f64>::is_separable(&Zero::new()));
+    assert!(Constraint::<f64>::is_separable(&BallInf::new(None, 1.0)));
+    assert!(!Constraint::<f64>::is_separable(&Ball2::new(None, 1.0)));
+    assert!(!Constraint::<f64>::is_separable(&Simplex::new(1.0)));
+
+    let separable_product = CartesianProduct::new()
+        .add_constraint(2, BallInf::new(None, 1.0))
+        .add_constraint(4, Rectangle::new(Some(&xmin), None));
+    assert!(separable_product.is_separable());
+    let product = CartesianProduct::new()
+        .add_constraint(2, BallInf::new(None, 1.0))
+        .add_constraint(4, Ball2::new(None, 1.0));
+    assert!(!product.is_separable());
+}
+
+#[test]
+fn t_is_compact_rectangle() {
+    let xmin = [-1.0, -2.0];
+    let xmax = [1.0, 2.0];
+    assert!(Rectangle::new(Some(&xmin), Some(&xmax)).is_compact());
+    assert!(Rectangle::<f64>::from_scalars(3, -1.0, 1.0).is_compact());
+    assert!(!Rectangle::new(Some(&xmin), None).is_compact());
+    assert!(!Rectangle::new(None, Some(&xmax)).is_compact());
+    let xmax_infinite = [1.0, f64::INFINITY];
+    assert!(!Rectangle::new(Some(&xmin), Some(&xmax_infinite)).is_compact());
+    assert!(!Rectangle::<f64>::from_scalars(3, -1.0, f64::INFINITY).is_compact());
+}
+
 #[test]
 #[should_panic]
 fn t_ball1_alpha_negative() {
@@ -921,6 +1420,54 @@ fn t_epigraph_squared_norm_correctness() {
     );
 }
 
+#[test]
+fn t_epigraph_squared_norm_brute_force() {
+    // the projection of (z, t) with ||z||^2 > t is of the form (s z / ||z||, s^2)
+    // for some s in [0, ||z||], so it is found by a grid search over s
+    let epi = EpigraphSquaredNorm::new();
+    let points = [
+        [1.0, -1.0, 0.5],
+        [0.3, 0.2, -2.0],
+        [-2.0, 0.5, 1.0],
+        [0.1, 0.0, 0.0],
+    ];
+    for x0 in points.iter() {
+        let mut x = *x0;
+        epi.project(&mut x);
+        let norm_z0 = crate::matrix_operations::norm2(&x0[..2]);
+        let num_grid_points = 100_000;
+        let (mut s_best, mut dist_sq_best) = (0.0, f64::INFINITY);
+        for i in 0..=num_grid_points {
+            let s = norm_z0 * i as f64 / num_grid_points as f64;
+            let dist_sq = (s - norm_z0).powi(2) + (s * s - x0[2]).powi(2);
+            if dist_sq < dist_sq_best {
+                s_best = s;
+                dist_sq_best = dist_sq;
+            }
+        }
+        let x_brute_force = [
+            s_best * x0[0] / norm_z0,
+            s_best * x0[1] / norm_z0,
+            s_best * s_best,
+        ];
+        unit_test_utils::assert_nearly_equal_array(
+            &x_brute_force,
+            &x,
+            1e-4,
+            1e-4,
+            "wrong projection on epigraph of squared norm",
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn t_epigraph_squared_norm_too_short() {
+    let epi = EpigraphSquaredNorm::new();
+    let mut x = [1.0];
+    epi.project(&mut x);
+}
+
 #[test]
 fn t_affine_space() {
     let a = vec![
@@ -988,3 +1535,869 @@ fn t_affine_space_wrong_dimensions() {
     let b = vec![1., 2., -0.5];
     let _: AffineSpace<f64> = AffineSpace::new(a, b);
 }
+
+#[test]
+fn t_minkowski_sum_box_ball() {
+    let box_set = BallInf::new(None, 1.0);
+    let ball = Ball2::new(None, 0.5);
+    let rounded_box = MinkowskiSum::new(&box_set, &ball);
+
+    // the Minkowski sum is the set of points whose distance from the box is
+    // at most 0.5, so the projection of x is p + min(1, 0.5/|x - p|) (x - p),
+    // where p is the projection of x on the box
+    let exact_projection = |x: &[f64; 2]| -> [f64; 2] {
+        let mut p = *x;
+        box_set.project(&mut p);
+        let distance = crate::matrix_operations::norm2_diff(x, &p);
+        let t = if distance > 0.5 { 0.5 / distance } else { 1.0 };
+        [p[0] + t * (x[0] - p[0]), p[1] + t * (x[1] - p[1])]
+    };
+
+    for x_init in [[3.0, 2.0], [1.2, 0.3], [-0.4, -2.5], [0.1, 0.2]].iter() {
+        let mut x = *x_init;
+        rounded_box.project(&mut x);
+        unit_test_utils::assert_nearly_equal_array(
+            &exact_projection(x_init),
+            &x,
+            1e-8,
+            1e-8,
+            "wrong projection on Minkowski sum",
+        );
+    }
+    assert!(rounded_box.is_convex());
+}
+
+#[test]
+fn t_minkowski_sum_two_balls() {
+    // the sum of two balls is a ball whose radius is the sum of their radii
+    let center_a = [1.0, -1.0, 0.5];
+    let center_b = [0.5, 2.0, -1.0];
+    let ball_a = Ball2::new(Some(&center_a), 0.7);
+    let ball_b = Ball2::new(Some(&center_b), 1.3);
+    let center_sum = [1.5, 1.0, -0.5];
+    let ball_sum = Ball2::new(Some(&center_sum), 2.0);
+    let minkowski_sum = MinkowskiSum::new(&ball_a, &ball_b);
+
+    // the workspace is reused across projections
+    for x_init in [[6.0, 2.0, -3.0], [1.0, 1.0, 0.0], [-2.0, -4.0, 5.0]].iter() {
+        let mut x = *x_init;
+        minkowski_sum.project(&mut x);
+        let mut x_ball = *x_init;
+        ball_sum.project(&mut x_ball);
+        unit_test_utils::assert_nearly_equal_array(
+            &x_ball,
+            &x,
+            1e-8,
+            1e-8,
+            "wrong projection on the sum of two balls",
+        );
+    }
+}
+
+#[test]
+fn t_minkowski_sum_nonconvex() {
+    let sphere = Sphere2::new(None, 1.0);
+    let ball = Ball2::new(None, 0.5);
+    let minkowski_sum = MinkowskiSum::new(&sphere, &ball);
+    assert!(!minkowski_sum.is_convex());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn t_minkowski_sum_max_iter_reached() {
+    let box_set = BallInf::new(None, 1.0);
+    let ball = Ball2::new(None, 0.5);
+    let rounded_box = MinkowskiSum::new(&box_set, &ball).with_max_iter(1);
+    let mut x = [3.0, 2.0];
+    rounded_box.project(&mut x);
+}
+
+/// A deliberately broken "projection" which doubles its input
+struct ExpansiveConstraint {}
+
+impl Constraint<f64> for ExpansiveConstraint {
+    fn project(&self, x: &mut [f64]) {
+        x.iter_mut().for_each(|xi| *xi *= 2.0);
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn t_nonexpansive_checked_ball2() {
+    let ball = NonexpansiveChecked::new(Ball2::new(None, 1.0));
+    let mut x = [3.0, 4.0, -1.0];
+    ball.project(&mut x);
+    unit_test_utils::assert_nearly_equal(
+        1.0,
+        crate::matrix_operations::norm2(&x),
+        1e-10,
+        1e-12,
+        "wrong projection on ball",
+    );
+    let mut x = [0.1, -0.2, 0.3];
+    ball.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.1, -0.2, 0.3],
+        &x,
+        1e-12,
+        1e-12,
+        "wrong projection on ball",
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn t_nonexpansive_checked_expansive() {
+    let broken = NonexpansiveChecked::new(ExpansiveConstraint {}).with_perturbation(1e-3);
+    let mut x = [1.0, 2.0];
+    broken.project(&mut x);
+}
+
+#[test]
+fn t_workspace_reuses_buffer() {
+    let workspace = super::workspace::Workspace::new(0, 0.0);
+    let first = workspace.with(3, |w| {
+        w[0] = 1.0;
+        w.as_ptr()
+    });
+    let second = workspace.with(2, |w| {
+        assert_eq!(1.0, w[0]);
+        w.as_ptr()
+    });
+    assert_eq!(first, second);
+
+    // while the buffer is in use, a temporary buffer is provided
+    workspace.with(3, |w| {
+        let outer = w.as_ptr();
+        workspace.with(3, |v| assert_ne!(outer, v.as_ptr()));
+    });
+    assert_eq!(first, workspace.with(3, |w| w.as_ptr()));
+}
+
+#[test]
+fn t_regularized_box() {
+    let xmin = [-1.0, -1.0, -1.0, -1.0];
+    let xmax = [1.0, 1.0, 1.0, 1.0];
+    let prior = [0.5, -0.5, 2.0, 0.0];
+    let lambda: f64 = 3.0;
+    let regularized_box = RegularizedBox::new(Some(&xmin), Some(&xmax), &prior, lambda);
+    let x_original = [0.1, 0.3, 1.0, -5.0];
+    let mut x = x_original;
+    regularized_box.project(&mut x);
+    let expected: Vec<f64> = x_original
+        .iter()
+        .zip(prior.iter())
+        .map(|(&xi, &pi)| ((xi + lambda * pi) / (1.0 + lambda)).clamp(-1.0, 1.0))
+        .collect();
+    unit_test_utils::assert_nearly_equal_array(
+        &expected,
+        &x,
+        1e-12,
+        1e-14,
+        "wrong result (regularized box)",
+    );
+    // the third and fourth coordinates are clamped
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.4, -0.3, 1.0, -1.0],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong result (regularized box)",
+    );
+    assert!(regularized_box.is_convex());
+}
+
+#[test]
+fn t_regularized_box_zero_lambda() {
+    let xmin = [2.0; 3];
+    let xmax = [4.5; 3];
+    let prior = [0.0; 3];
+    let regularized_box = RegularizedBox::new(Some(&xmin), Some(&xmax), &prior, 0.0);
+    let rectangle = Rectangle::new(Some(&xmin), Some(&xmax));
+    let mut x = [1.0, 3.0, 5.0];
+    let mut y = x;
+    regularized_box.project(&mut x);
+    rectangle.project(&mut y);
+    unit_test_utils::assert_nearly_equal_array(&y, &x, 1e-12, 1e-14, "not a projection");
+}
+
+#[test]
+#[should_panic]
+fn t_regularized_box_incompatible_dims() {
+    let xmin = [-1.0; 3];
+    let prior = [0.0; 2];
+    let _ = RegularizedBox::new(Some(&xmin), None, &prior, 1.0);
+}
+
+#[test]
+fn t_rotated_rectangle() {
+    // box [-1, 1] x [-0.5, 0.5], rotated by 45 degrees
+    let xmin = [-1.0, -0.5];
+    let xmax = [1.0, 0.5];
+    let rectangle = Rectangle::new(Some(&xmin), Some(&xmax));
+    let (c, s) = (
+        std::f64::consts::FRAC_1_SQRT_2,
+        std::f64::consts::FRAC_1_SQRT_2,
+    );
+    let rotation = [c, -s, s, c];
+    let rotated_box = Rotated::new(&rectangle, &rotation);
+
+    // in the box frame, (2, 0) becomes (sqrt(2), -sqrt(2)), which is projected
+    // on the corner (1, -0.5), i.e., (1.5, 0.5) / sqrt(2) in the original frame
+    let mut x = [2.0, 0.0];
+    rotated_box.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(
+        &[1.5 * c, 0.5 * c],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong projection (corner)",
+    );
+
+    // in the box frame, (0, 2) becomes (sqrt(2), sqrt(2)), which is projected
+    // on (1, 0.5), i.e., (0.5, 1.5) / sqrt(2) in the original frame
+    let mut x = [0.0, 2.0];
+    rotated_box.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(
+        &[0.5 * c, 1.5 * c],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong projection (corner)",
+    );
+
+    // the point (0.5, 0.5) is (1/sqrt(2), 0) in the box frame, so it is in the set
+    let mut x = [0.5, 0.5];
+    rotated_box.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.5, 0.5], &x, 1e-12, 1e-14, "x modified");
+
+    // the point (-0.5, 0.5) is (0, 1/sqrt(2)) in the box frame, so it is
+    // projected on (0, 0.5), i.e., (-0.25, 0.25) * sqrt(2)
+    let mut x = [-0.5, 0.5];
+    rotated_box.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(
+        &[-0.5 * c, 0.5 * c],
+        &x,
+        1e-12,
+        1e-14,
+        "wrong projection (edge)",
+    );
+    assert!(rotated_box.is_convex());
+}
+
+#[test]
+#[should_panic]
+fn t_rotated_not_orthogonal() {
+    let ball = Ball2::new(None, 1.0);
+    let matrix = [1.0, 1.0, 0.0, 1.0];
+    let _ = Rotated::new(&ball, &matrix);
+}
+
+#[test]
+#[should_panic]
+fn t_rotated_not_square() {
+    let ball = Ball2::new(None, 1.0);
+    let matrix = [1.0, 0.0, 0.0];
+    let _ = Rotated::new(&ball, &matrix);
+}
+
+#[test]
+fn t_constraint_contains() {
+    let ball = Ball2::new(None, 1.0);
+    assert!(ball.contains(&[0.6, 0.8], 1e-12));
+    assert!(ball.contains(&[0.1, -0.2], 0.0));
+    assert!(!ball.contains(&[0.6, 0.81], 1e-6));
+    assert!(ball.contains(&[0.6, 0.81], 1e-2));
+    let rectangle = Rectangle::new(Some(&[0.0, 0.0]), Some(&[1.0, 1.0]));
+    assert!(rectangle.contains(&[1.0, 0.0], 0.0));
+    assert!(!rectangle.contains(&[1.0, -1e-3], 1e-4));
+}
+
+fn assert_send_sync<C: Send + Sync>() {}
+
+#[test]
+fn t_constraints_are_send_sync() {
+    assert_send_sync::<AffineSpace<f64>>();
+    assert_send_sync::<Ball1<'_, f64>>();
+    assert_send_sync::<Ball2<'_, f64>>();
+    assert_send_sync::<BallHyperplane<'_, f64>>();
+    assert_send_sync::<BallInf<'_, f64>>();
+    assert_send_sync::<EpigraphSquaredNorm<f64>>();
+    assert_send_sync::<FiniteSet<'_, f64>>();
+    assert_send_sync::<Frustum<f64>>();
+    assert_send_sync::<Halfspace<'_, f64>>();
+    assert_send_sync::<Hyperplane<'_, f64>>();
+    assert_send_sync::<NoConstraints>();
+    assert_send_sync::<OrderCone<'_, f64>>();
+    assert_send_sync::<Rectangle<'_, f64>>();
+    assert_send_sync::<RegularizedBox<'_, f64>>();
+    assert_send_sync::<Simplex<f64>>();
+    assert_send_sync::<SecondOrderCone<f64>>();
+    assert_send_sync::<Sphere2<'_, f64>>();
+    assert_send_sync::<Zero>();
+    assert_send_sync::<Ball2<'_, f32>>();
+    assert_send_sync::<Rectangle<'_, f32>>();
+}
+
+#[test]
+fn t_constraint_combinators_are_send_sync() {
+    assert_send_sync::<MinkowskiSum<'_, BallInf<'_, f64>, Ball2<'_, f64>, f64>>();
+    assert_send_sync::<NonexpansiveChecked<Ball2<'_, f64>, f64>>();
+    assert_send_sync::<Rotated<'_, Rectangle<'_, f64>, f64>>();
+    assert_send_sync::<SocIntersection<'_, SecondOrderCone<f64>, Zero, f64>>();
+}
+
+// maximizes <d, x> over the points of a grid on [lower, upper]^2 which belong
+// to the set
+fn brute_force_support<C: Constraint<f64>>(set: &C, d: &[f64; 2], lower: f64, upper: f64) -> f64 {
+    let num_points = 601;
+    let step = (upper - lower) / (num_points - 1) as f64;
+    let mut support = f64::NEG_INFINITY;
+    for i in 0..num_points {
+        for j in 0..num_points {
+            let x = [lower + i as f64 * step, lower + j as f64 * step];
+            if set.contains(&x, 1e-12) {
+                support = support.max(d[0] * x[0] + d[1] * x[1]);
+            }
+        }
+    }
+    support
+}
+
+#[test]
+fn t_support_ball2() {
+    let center = [0.5, -0.3];
+    let ball = Ball2::new(Some(&center), 1.2);
+    for d in [[1.0, 0.0], [0.3, -2.0], [-1.5, -0.5]].iter() {
+        let support = ball.support(d).unwrap();
+        let brute_force = brute_force_support(&ball, d, -2.0, 2.0);
+        assert!(support >= brute_force - 1e-12);
+        unit_test_utils::assert_nearly_equal(support, brute_force, 1e-3, 1e-3, "support");
+    }
+}
+
+#[test]
+fn t_support_ballinf() {
+    let center = [0.5, -0.3];
+    let ball = BallInf::new(Some(&center), 1.2);
+    for d in [[1.0, 0.0], [0.3, -2.0], [-1.5, -0.5]].iter() {
+        let support = ball.support(d).unwrap();
+        let brute_force = brute_force_support(&ball, d, -2.0, 2.0);
+        assert!(support >= brute_force - 1e-12);
+        unit_test_utils::assert_nearly_equal(support, brute_force, 1e-3, 1e-3, "support");
+    }
+}
+
+#[test]
+fn t_support_rectangle() {
+    let xmin = [-1.0, 0.5];
+    let xmax = [1.5, 2.0];
+    let rectangle = Rectangle::new(Some(&xmin), Some(&xmax));
+    for d in [[1.0, 0.0], [0.3, -2.0], [-1.5, -0.5], [0.0, 0.0]].iter() {
+        let support = rectangle.support(d).unwrap();
+        let brute_force = brute_force_support(&rectangle, d, -3.0, 3.0);
+        unit_test_utils::assert_nearly_equal(support, brute_force, 1e-10, 1e-12, "support");
+    }
+
+    let halfspace = Rectangle::new(Some(&xmin), None);
+    assert_eq!(1.0, halfspace.support(&[-1.0, 0.0]).unwrap());
+    assert_eq!(f64::INFINITY, halfspace.support(&[-1.0, 1.0]).unwrap());
+    let box_from_scalars = Rectangle::from_scalars(2, f64::NEG_INFINITY, 1.0);
+    assert_eq!(1.0, box_from_scalars.support(&[1.0, 0.0]).unwrap());
+}
+
+#[test]
+fn t_support_hyperplane() {
+    let normal_vector = [1.0, 2.0];
+    let hyperplane = Hyperplane::new(&normal_vector, 3.0);
+    // d = -2c: <d, x> = -2 <c, x> = -6 for all x in the hyperplane
+    let support = hyperplane.support(&[-2.0, -4.0]).unwrap();
+    unit_test_utils::assert_nearly_equal(-6.0, support, 1e-12, 1e-14, "support");
+    let brute_force = brute_force_support(&hyperplane, &[-2.0, -4.0], -5.0, 5.0);
+    unit_test_utils::assert_nearly_equal(support, brute_force, 1e-10, 1e-12, "brute force");
+    assert_eq!(f64::INFINITY, hyperplane.support(&[1.0, 0.0]).unwrap());
+}
+
+#[test]
+fn t_support_zero_and_unknown() {
+    assert_eq!(Some(0.0), Zero::new().support(&[1.0, -2.0]));
+    assert_eq!(None, Simplex::new(1.0).support(&[1.0, -2.0]));
+}
+
+#[test]
+fn t_scaled_constraint() {
+    let ball = Ball2::new(None, 1.0);
+    let scaled_ball = ScaledConstraint::new(&ball, 2.0);
+    let mut x = [3.0, -4.0];
+    scaled_ball.project(&mut x);
+    let two_x = [2.0 * x[0], 2.0 * x[1]];
+    assert!(crate::matrix_operations::norm2(&two_x) <= 1.0 + 1e-12);
+    unit_test_utils::assert_nearly_equal_array(&[0.3, -0.4], &x, 1e-12, 1e-14, "x");
+    assert!(scaled_ball.is_convex());
+    assert!(scaled_ball.is_compact());
+
+    // points in the scaled set are not modified
+    let mut x_inside = [0.1, 0.2];
+    scaled_ball.project(&mut x_inside);
+    unit_test_utils::assert_nearly_equal_array(&[0.1, 0.2], &x_inside, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_scaled_constraint_negative_scaling() {
+    let xmin = [0.0, 1.0];
+    let xmax = [2.0, 3.0];
+    let rectangle = Rectangle::new(Some(&xmin), Some(&xmax));
+    let scaled_rectangle = ScaledConstraint::new(&rectangle, -0.5);
+    let mut x = [1.0, -10.0];
+    scaled_rectangle.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.0, -6.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+#[should_panic]
+fn t_scaled_constraint_zero_scaling() {
+    let ball = Ball2::new(None, 1.0);
+    let _ = ScaledConstraint::new(&ball, 0.0);
+}
+
+#[test]
+fn t_boxed_constraints() {
+    let xmin = [-1.0, 0.5];
+    let xmax = [1.0, 2.0];
+    let constraints: Vec<Box<dyn Constraint<f64>>> = vec![
+        Box::new(Ball2::new(None, 1.0)),
+        Box::new(Rectangle::new(Some(&xmin), Some(&xmax))),
+    ];
+    let expected = [[0.6, 0.8], [1.0, 2.0]];
+    for (set, x_expected) in constraints.iter().zip(expected.iter()) {
+        let mut x = [3.0, 4.0];
+        set.project(&mut x);
+        unit_test_utils::assert_nearly_equal_array(x_expected, &x, 1e-12, 1e-14, "x");
+        assert!(set.is_convex());
+        assert!(set.is_compact());
+        assert!(set.contains(&x, 1e-12));
+    }
+
+    // a boxed constraint can be wrapped by combinators which expect a `Constraint`
+    let scaled = ScaledConstraint::new(&constraints[0], 2.0);
+    let mut x = [3.0, 4.0];
+    scaled.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.3, 0.4], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_borrowed_constraint_object() {
+    let ball = BallInf::new(None, 0.5);
+    let set: &dyn Constraint<f64> = &ball;
+    let mut x = [2.0, -0.1, -3.0];
+    set.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[0.5, -0.1, -0.5], &x, 1e-12, 1e-14, "x");
+
+    let scaled = ScaledConstraint::new(&set, 1.0);
+    assert!(scaled.is_convex());
+    assert!(scaled.is_compact());
+}
+
+#[test]
+fn t_simplex_projection_feasible_early_exit() {
+    let simplex = Simplex::new(2.0);
+    let x_feasible = [0.5, 0.0, 1.25, 0.25];
+    let mut x = x_feasible;
+    simplex.project(&mut x);
+    assert_eq!(x_feasible, x);
+
+    // a vector with negative entries is projected even if its sum is alpha
+    let mut x = [3.0, -1.0];
+    simplex.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[2.0, 0.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_simplex_projection_repeated() {
+    let simplex = Simplex::new(1.5);
+    let x_init = [0.7, -2.0, 1.3, 0.9, -0.1, 2.2, 0.0, 1.0];
+    let mut x_first = x_init;
+    simplex.project(&mut x_first);
+    for _ in 0..5 {
+        let mut x = x_init;
+        simplex.project(&mut x);
+        assert_eq!(x_first, x);
+    }
+
+    // projecting the projection again does not modify it
+    let mut x_again = x_first;
+    simplex.project(&mut x_again);
+    unit_test_utils::assert_nearly_equal_array(&x_first, &x_again, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_ball1_projection_matches_simplex() {
+    // reference: project |x - xc| on the simplex and restore the signs
+    let project_reference = |x: &mut [f64], xc: &[f64], radius: f64| {
+        let mut u: Vec<f64> = x
+            .iter()
+            .zip(xc.iter())
+            .map(|(a, b)| (a - b).abs())
+            .collect();
+        if u.iter().sum::<f64>() > radius {
+            Simplex::new(radius).project(&mut u);
+            x.iter_mut()
+                .zip(xc.iter())
+                .zip(u.iter())
+                .for_each(|((xi, ci), ui)| *xi = ci + (*xi - ci).signum() * ui);
+        }
+    };
+    for n in [1, 2, 5, 20, 100] {
+        for _ in 0..200 {
+            let x_init: Vec<f64> = (0..n)
+                .map(|_| 20. * (2. * rand::random::<f64>() - 1.))
+                .collect();
+            let xc: Vec<f64> = (0..n)
+                .map(|_| 5. * (2. * rand::random::<f64>() - 1.))
+                .collect();
+            let zeros = vec![0.0; n];
+            let radius = 10. * rand::random::<f64>() + 0.01;
+
+            let mut x = x_init.clone();
+            let mut x_reference = x_init.clone();
+            Ball1::new(None, radius).project(&mut x);
+            project_reference(&mut x_reference, &zeros, radius);
+            unit_test_utils::assert_nearly_equal_array(&x_reference, &x, 1e-10, 1e-12, "x");
+
+            let mut x = x_init.clone();
+            let mut x_reference = x_init.clone();
+            Ball1::new(Some(&xc), radius).project(&mut x);
+            project_reference(&mut x_reference, &xc, radius);
+            unit_test_utils::assert_nearly_equal_array(&x_reference, &x, 1e-10, 1e-12, "x");
+        }
+    }
+}
+
+#[test]
+fn t_ball1_projection_ties() {
+    let ball1 = Ball1::new(None, 1.0);
+    let mut x = [2.0, -2.0, 2.0, 0.0];
+    ball1.project(&mut x);
+    let third = 1.0 / 3.0;
+    unit_test_utils::assert_nearly_equal_array(&[third, -third, third, 0.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_order_cone_pava() {
+    let order_cone = OrderCone::new();
+    let mut x = [4.0, 5.0, 1.0, 6.0, 3.0];
+    order_cone.project(&mut x);
+    let ten_thirds = 10.0 / 3.0;
+    unit_test_utils::assert_nearly_equal_array(
+        &[ten_thirds, ten_thirds, ten_thirds, 4.5, 4.5],
+        &x,
+        1e-12,
+        1e-14,
+        "x",
+    );
+    assert!(order_cone.is_convex());
+    assert!(!order_cone.is_compact());
+
+    // points in the cone are not modified
+    let mut x_sorted = [-1.0, 0.0, 0.0, 2.5];
+    order_cone.project(&mut x_sorted);
+    assert_eq!([-1.0, 0.0, 0.0, 2.5], x_sorted);
+
+    // the stack of blocks is reused, also for longer vectors
+    let mut x_longer = [4.0, 5.0, 1.0, 6.0, 3.0, 3.0];
+    order_cone.project(&mut x_longer);
+    unit_test_utils::assert_nearly_equal_array(
+        &[ten_thirds, ten_thirds, ten_thirds, 4.0, 4.0, 4.0],
+        &x_longer,
+        1e-12,
+        1e-14,
+        "x_longer",
+    );
+}
+
+#[test]
+fn t_order_cone_reverse() {
+    let order_cone = OrderCone::new().with_reverse_order();
+    let mut x = [1.0, 3.0, 2.0, -1.0, 0.0];
+    order_cone.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[2.0, 2.0, 2.0, -0.5, -0.5], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_order_cone_weighted() {
+    // minimize (y - 2)^2 + (2y - 2)^2, so y = 1.2 and x = (1.2, 2.4)
+    let weights = [1.0, 2.0];
+    let order_cone = OrderCone::new().with_weights(&weights);
+    let mut x = [2.0, 2.0];
+    order_cone.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[1.2, 2.4], &x, 1e-12, 1e-14, "x");
+
+    // weighted PAVA with weights w_i^2 = (1, 4, 1) on z_i/w_i = (3, 1, 2):
+    // the first two entries are pooled into (3 + 4) / 5 = 1.4, which does
+    // not exceed the third one, so y = (1.4, 1.4, 2)
+    let weights = [1.0, 2.0, 1.0];
+    let order_cone = OrderCone::new().with_weights(&weights);
+    let mut x = [3.0, 2.0, 2.0];
+    order_cone.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[1.4, 2.8, 2.0], &x, 1e-12, 1e-14, "x");
+}
+
+#[test]
+fn t_order_cone_unit_weights() {
+    let n = 30;
+    let weights = vec![1.0; n];
+    let order_cone = OrderCone::new();
+    let order_cone_weighted = OrderCone::new().with_weights(&weights);
+    for _ in 0..100 {
+        let mut x: Vec<f64> = (0..n)
+            .map(|_| 10. * (2. * rand::random::<f64>() - 1.))
+            .collect();
+        let mut x_weighted = x.clone();
+        order_cone.project(&mut x);
+        order_cone_weighted.project(&mut x_weighted);
+        assert_eq!(x, x_weighted);
+        assert!(x.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
+
+#[test]
+#[should_panic]
+fn t_order_cone_nonpositive_weights() {
+    let weights = [1.0, 0.0];
+    let _ = OrderCone::new().with_weights(&weights);
+}
+
+#[test]
+#[should_panic]
+fn t_order_cone_incompatible_dimensions() {
+    let weights = [1.0, 2.0];
+    let order_cone = OrderCone::new().with_weights(&weights);
+    let mut x = [1.0, 2.0, 3.0];
+    order_cone.project(&mut x);
+}
+
+#[test]
+fn t_try_new_constraints() {
+    let center = [1.0, 2.0];
+    let xmin = [0.0, 1.0];
+    let xmax = [2.0, 3.0, 4.0];
+    let invalid = Some(crate::SolverError::InvalidArgument);
+
+    assert!(Ball1::try_new(Some(&center), 1.0).is_ok());
+    assert!(Ball2::try_new(None, 2.0).is_ok());
+    assert!(BallInf::try_new(Some(&center), 0.5).is_ok());
+    assert!(Sphere2::try_new(None, 1.0).is_ok());
+    assert!(BallComplement::try_new(Some(&center), 1.0).is_ok());
+    assert!(Simplex::try_new(1.0).is_ok());
+    assert!(Rectangle::try_new(Some(&xmin), None).is_ok());
+    assert!(Rectangle::try_new(None, Some(&xmax)).is_ok());
+    assert!(Rectangle::try_new(Some(&xmin), Some(&xmax[..2])).is_ok());
+
+    for &radius in [0.0, -1.0, f64::NAN].iter() {
+        assert_eq!(invalid, Ball1::try_new(None, radius).err());
+        assert_eq!(invalid, Ball2::try_new(Some(&center), radius).err());
+        assert_eq!(invalid, BallInf::try_new(None, radius).err());
+        assert_eq!(invalid, Sphere2::try_new(Some(&center), radius).err());
+        assert_eq!(invalid, BallComplement::try_new(None, radius).err());
+        assert_eq!(invalid, Simplex::try_new(radius).err());
+    }
+    assert_eq!(invalid, Rectangle::<f64>::try_new(None, None).err());
+    assert_eq!(invalid, Rectangle::try_new(Some(&xmin), Some(&xmax)).err());
+}
+
+// projects `x` on the intersection of the ball with center `center` and radius
+// `radius` with the hyperplane {z : <c, z> = b}, where `basis` is an orthonormal
+// basis of the orthogonal complement of c, by searching over a fine grid of the
+// slice of the ball
+fn brute_force_ball_hyperplane(
+    x: &[f64],
+    center: &[f64],
+    radius: f64,
+    normal_vector: &[f64],
+    offset: f64,
+    basis: &[&[f64]],
+) -> Vec<f64> {
+    let n = x.len();
+    let normal_norm_sq = crate::matrix_operations::norm2_squared(normal_vector);
+    let shift =
+        (crate::matrix_operations::inner_product(center, normal_vector) - offset) / normal_norm_sq;
+    let slice_center: Vec<f64> = (0..n)
+        .map(|i| center[i] - shift * normal_vector[i])
+        .collect();
+    let slice_radius = (radius * radius - shift * shift * normal_norm_sq).sqrt();
+    // the slice is a segment (for n = 2) or a disc (for n = 3), which is
+    // sampled in polar coordinates so that its boundary is included
+    let num_points = 400;
+    let coordinates: Vec<Vec<f64>> = match basis.len() {
+        1 => (0..=num_points)
+            .map(|k| vec![slice_radius * (2.0 * (k as f64) / (num_points as f64) - 1.0)])
+            .collect(),
+        _ => (0..=num_points / 2)
+            .flat_map(|k| {
+                let rho = slice_radius * (k as f64) / ((num_points / 2) as f64);
+                (0..4 * num_points).map(move |l| {
+                    let theta = core::f64::consts::PI * (l as f64) / ((2 * num_points) as f64);
+                    vec![rho * theta.cos(), rho * theta.sin()]
+                })
+            })
+            .collect(),
+    };
+    let mut best = slice_center.clone();
+    let mut best_distance = f64::INFINITY;
+    coordinates.iter().for_each(|a| {
+        let z: Vec<f64> = (0..n)
+            .map(|i| {
+                slice_center[i]
+                    + a.iter()
+                        .zip(basis.iter())
+                        .map(|(aj, vj)| aj * vj[i])
+                        .sum::<f64>()
+            })
+            .collect();
+        let distance = crate::matrix_operations::norm2_squared_diff(&z, x);
+        if distance < best_distance {
+            best_distance = distance;
+            best = z;
+        }
+    });
+    best
+}
+
+#[test]
+fn t_ball_hyperplane_2d() {
+    let center = [0.5, -0.2];
+    let normal_vector = [1.0, 1.0];
+    let offset = 0.8;
+    let set = BallHyperplane::new(Some(&center), 1.5, &normal_vector, offset);
+    let direction = [1.0 / 2.0_f64.sqrt(), -1.0 / 2.0_f64.sqrt()];
+    for _ in 0..20 {
+        let x = [
+            5. * (2. * rand::random::<f64>() - 1.),
+            5. * (2. * rand::random::<f64>() - 1.),
+        ];
+        let mut x_proj = x;
+        set.project(&mut x_proj);
+        let expected =
+            brute_force_ball_hyperplane(&x, &center, 1.5, &normal_vector, offset, &[&direction]);
+        unit_test_utils::assert_nearly_equal_array(&expected, &x_proj, 1e-2, 1e-2, "x_proj");
+        unit_test_utils::assert_nearly_equal(
+            offset,
+            crate::matrix_operations::inner_product(&x_proj, &normal_vector),
+            1e-12,
+            1e-12,
+            "on the hyperplane",
+        );
+    }
+    assert!(set.is_convex());
+    assert!(set.is_compact());
+}
+
+#[test]
+fn t_ball_hyperplane_3d() {
+    let center = [1.0, 0.0, -1.0];
+    let normal_vector = [0.0, 0.0, 2.0];
+    let offset = -1.0;
+    let set = BallHyperplane::new(Some(&center), 1.0, &normal_vector, offset);
+    unit_test_utils::assert_nearly_equal(
+        0.75_f64.sqrt(),
+        set.slice_radius(),
+        1e-12,
+        1e-12,
+        "slice radius",
+    );
+    let (e1, e2) = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    for _ in 0..10 {
+        let x = [
+            3. * (2. * rand::random::<f64>() - 1.),
+            3. * (2. * rand::random::<f64>() - 1.),
+            3. * (2. * rand::random::<f64>() - 1.),
+        ];
+        let mut x_proj = x;
+        set.project(&mut x_proj);
+        let expected =
+            brute_force_ball_hyperplane(&x, &center, 1.0, &normal_vector, offset, &[&e1, &e2]);
+        unit_test_utils::assert_nearly_equal_array(&expected, &x_proj, 1e-2, 1e-2, "x_proj");
+    }
+
+    // points of the slice are left unchanged
+    let mut x = [1.5, 0.5, -0.5];
+    set.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[1.5, 0.5, -0.5], &x, 1e-12, 1e-12, "x");
+}
+
+#[test]
+fn t_ball_hyperplane_tangent() {
+    // the hyperplane touches the ball at a single point
+    let normal_vector = [1.0, 1.0, 0.0];
+    let set = BallHyperplane::new(None, 2.0_f64.sqrt(), &normal_vector, 2.0);
+    let mut x = [4.0, -3.0, 7.0];
+    set.project(&mut x);
+    unit_test_utils::assert_nearly_equal_array(&[1.0, 1.0, 0.0], &x, 1e-6, 1e-6, "x");
+}
+
+#[test]
+#[should_panic]
+fn t_ball_hyperplane_empty() {
+    let normal_vector = [1.0, 0.0];
+    let _ = BallHyperplane::new(None, 1.0, &normal_vector, 2.0);
+}
+
+#[test]
+fn t_ball2_scaled() {
+    let center = [1.0, -2.0, 0.5];
+    let ball = Ball2::new(Some(&center), 2.0);
+    let scaled_ball = ball.scaled(0.25);
+    let fresh_ball = Ball2::new(Some(&center), 0.5);
+    assert_eq!(0.5, scaled_ball.radius());
+    assert_eq!(Some(&center[..]), scaled_ball.center());
+    let origin_ball = Ball2::new(None, 1.0).scaled(3.0);
+    let fresh_origin_ball = Ball2::new(None, 3.0);
+    for _ in 0..50 {
+        let x: Vec<f64> = (0..3)
+            .map(|_| 10. * (2. * rand::random::<f64>() - 1.))
+            .collect();
+        let (mut x_scaled, mut x_fresh) = (x.clone(), x.clone());
+        scaled_ball.project(&mut x_scaled);
+        fresh_ball.project(&mut x_fresh);
+        assert_eq!(x_fresh, x_scaled);
+        let (mut x_scaled, mut x_fresh) = (x.clone(), x);
+        origin_ball.project(&mut x_scaled);
+        fresh_origin_ball.project(&mut x_fresh);
+        assert_eq!(x_fresh, x_scaled);
+    }
+}
+
+#[test]
+fn t_ballinf_scaled() {
+    let center = [1.0, -2.0, 0.5];
+    let ball = BallInf::new(Some(&center), 2.0);
+    let scaled_ball = ball.scaled(1.5);
+    let fresh_ball = BallInf::new(Some(&center), 3.0);
+    assert_eq!(3.0, scaled_ball.radius());
+    for _ in 0..50 {
+        let x: Vec<f64> = (0..3)
+            .map(|_| 10. * (2. * rand::random::<f64>() - 1.))
+            .collect();
+        let (mut x_scaled, mut x_fresh) = (x.clone(), x);
+        scaled_ball.project(&mut x_scaled);
+        fresh_ball.project(&mut x_fresh);
+        assert_eq!(x_fresh, x_scaled);
+    }
+}
+
+#[test]
+#[should_panic]
+fn t_ball2_scaled_nonpositive_factor() {
+    let _ = Ball2::<f64>::new(None, 1.0).scaled(0.0);
+}
+
+#[test]
+#[should_panic]
+fn t_ballinf_scaled_nonpositive_factor() {
+    let _ = BallInf::<f64>::new(None, 1.0).scaled(-2.0);
+}
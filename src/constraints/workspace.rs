@@ -0,0 +1,78 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Work memory of a projection, which is kept across projections
+///
+/// Projections take `&self`, so the work memory of a constraint needs
+/// interior mutability; unlike a `RefCell`, this workspace is `Sync`, so the
+/// constraints which own one can still be shared across threads. The buffer
+/// is grown when a projection needs more memory than any previous one and is
+/// reused afterwards, so that repeated projections on a set of fixed
+/// dimension do not allocate memory. If the buffer is in use (that is, the
+/// same constraint is projected on concurrently from another thread), a
+/// temporary buffer is allocated instead.
+pub(crate) struct Workspace<E> {
+    buffer: UnsafeCell<Vec<E>>,
+    /// Value of the new elements when the buffer is grown
+    fill: E,
+    busy: AtomicBool,
+}
+
+// The buffer is only accessed by the thread which has set `busy`
+unsafe impl<E: Send + Sync> Sync for Workspace<E> {}
+
+/// Releases the buffer of a workspace when dropped (also on panic)
+struct Release<'a>(&'a AtomicBool);
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<E> Workspace<E>
+where
+    E: Copy,
+{
+    /// Constructs a workspace of `len` elements, equal to `fill`; the buffer
+    /// is grown with copies of `fill`
+    pub(crate) fn new(len: usize, fill: E) -> Self {
+        Workspace {
+            buffer: UnsafeCell::new(vec![fill; len]),
+            fill,
+            busy: AtomicBool::new(false),
+        }
+    }
+
+    /// Calls `f` with a buffer of `len` elements and returns its result; the
+    /// contents of the buffer are those left by the previous call
+    pub(crate) fn with<R>(&self, len: usize, f: impl FnOnce(&mut [E]) -> R) -> R {
+        if self
+            .busy
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return f(&mut vec![self.fill; len]);
+        }
+        let _release = Release(&self.busy);
+        // SAFETY: `busy` was set by this call, so no other reference to the
+        // buffer exists until `_release` is dropped
+        let buffer = unsafe { &mut *self.buffer.get() };
+        if buffer.len() < len {
+            buffer.resize(len, self.fill);
+        }
+        f(&mut buffer[..len])
+    }
+}
+
+impl<E> Clone for Workspace<E>
+where
+    E: Copy,
+{
+    /// The clone has its own (empty) buffer, which is allocated at its first use
+    fn clone(&self) -> Self {
+        Workspace::new(0, self.fill)
+    }
+}
@@ -24,4 +24,17 @@ where
     fn is_convex(&self) -> bool {
         true
     }
+
+    fn is_compact(&self) -> bool {
+        true
+    }
+
+    fn is_separable(&self) -> bool {
+        true
+    }
+
+    /// Support function of $\\{0\\}$, which is equal to zero
+    fn support(&self, _d: &[T]) -> Option<T> {
+        Some(T::zero())
+    }
 }
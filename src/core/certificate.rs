@@ -0,0 +1,214 @@
+//! Optimality certificates for convex quadratic problems
+//!
+//! For problems of the form
+//!
+//! $$
+//! \mathrm{Minimize}_{u\in U}\ \tfrac{1}{2}u^\top Q u + q^\top u,
+//! $$
+//!
+//! where $Q$ is symmetric and positive semidefinite and $U$ is a closed
+//! convex set, the gradient of the cost is known exactly, so the KKT
+//! conditions, $0 \in Qu + q + N_U(u)$, can be checked at a candidate
+//! solution. The [kkt_residual](fn.kkt_residual.html) of a candidate is zero
+//! if and only if it is optimal, so it serves as a certificate of
+//! (near-)optimality for the output of a solver.
+//!
+use alloc::vec;
+
+use crate::constraints::Constraint;
+use crate::core::OptFloat;
+use crate::matrix_operations;
+
+/// A convex quadratic cost function, $f(u) = \tfrac{1}{2}u^\top Q u + q^\top u$
+///
+/// The Hessian matrix, $Q$, is stored in row-major order
+#[derive(Debug, Clone, Copy)]
+pub struct ConvexQuadratic<'a, T>
+where
+    T: OptFloat,
+{
+    hessian: &'a [T],
+    linear: &'a [T],
+}
+
+impl<'a, T> ConvexQuadratic<'a, T>
+where
+    T: OptFloat,
+{
+    /// Constructs a new convex quadratic function
+    ///
+    /// ## Arguments
+    ///
+    /// - `hessian`: the (symmetric, positive semidefinite) Hessian matrix $Q$,
+    ///   of dimensions `n`-by-`n`, in row-major order
+    /// - `linear`: the linear term $q$, of dimension `n`
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `hessian` is not equal to the square
+    /// of the length of `linear`. Symmetry and positive semidefiniteness are not
+    /// checked.
+    ///
+    pub fn new(hessian: &'a [T], linear: &'a [T]) -> Self {
+        assert_eq!(
+            linear.len() * linear.len(),
+            hessian.len(),
+            "incompatible dimensions of hessian and linear term"
+        );
+        ConvexQuadratic { hessian, linear }
+    }
+
+    /// Dimension of the decision variable
+    pub fn dimension(&self) -> usize {
+        self.linear.len()
+    }
+
+    /// Computes the gradient, $\nabla f(u) = Qu + q$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `u` or `grad` do not have the correct dimension
+    ///
+    pub fn gradient(&self, u: &[T], grad: &mut [T]) {
+        let n = self.dimension();
+        assert_eq!(n, u.len(), "u has wrong dimension");
+        assert_eq!(n, grad.len(), "grad has wrong dimension");
+        grad.iter_mut()
+            .zip(self.hessian.chunks_exact(n))
+            .zip(self.linear.iter())
+            .for_each(|((grad_i, row_i), &q_i)| {
+                *grad_i = matrix_operations::inner_product(row_i, u) + q_i
+            });
+    }
+
+    /// Computes the value of the cost, $f(u) = \tfrac{1}{2}u^\top Q u + q^\top u$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `u` does not have the correct dimension
+    ///
+    pub fn cost(&self, u: &[T]) -> T {
+        let n = self.dimension();
+        assert_eq!(n, u.len(), "u has wrong dimension");
+        self.hessian
+            .chunks_exact(n)
+            .zip(u.iter())
+            .zip(self.linear.iter())
+            .fold(T::zero(), |sum, ((row_i, &u_i), &q_i)| {
+                sum + u_i
+                    * (T::from(0.5).unwrap() * matrix_operations::inner_product(row_i, u) + q_i)
+            })
+    }
+}
+
+/// KKT residual of a candidate solution of a convex quadratic problem
+///
+/// The residual is
+///
+/// $$
+/// r(u) = \Vert{}u - \Pi_U(u - \nabla f(u)){}\Vert,
+/// $$
+///
+/// which is zero if and only if $-\nabla f(u)$ is in the normal cone of $U$
+/// at $u$ (and $u \in U$), that is, if and only if $u$ is a minimizer of $f$
+/// over $U$. In the unconstrained case, this is the norm of the gradient.
+///
+/// ## Arguments
+///
+/// - `quadratic`: the cost function
+/// - `u`: candidate solution
+/// - `constraint`: the constraints, $U$, which should be a convex set
+///
+/// ## Returns
+///
+/// The Euclidean norm of the KKT (stationarity) residual
+///
+/// ## Panics
+///
+/// The method panics if `u` does not have the correct dimension
+///
+/// ## Memory allocation
+///
+/// This function allocates a vector of the same length as `u`
+///
+pub fn kkt_residual<T, C>(quadratic: &ConvexQuadratic<'_, T>, u: &[T], constraint: &C) -> T
+where
+    T: OptFloat,
+    C: Constraint<T>,
+{
+    let mut z = vec![T::zero(); u.len()];
+    quadratic.gradient(u, &mut z);
+    // z <-- u - grad f(u)
+    z.iter_mut()
+        .zip(u.iter())
+        .for_each(|(z_i, &u_i)| *z_i = u_i - *z_i);
+    constraint.project(&mut z);
+    matrix_operations::norm2_diff(u, &z)
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use crate::core::certificate::*;
+    use crate::core::panoc::*;
+    use crate::core::*;
+    use crate::mocks;
+
+    /// Hessian and linear term of `mocks::hard_quadratic_cost`
+    const HARD_HESSIAN: [f64; 9] = [4., 5., 25., 5., 11., 5., 25., 5., 1001.];
+    const HARD_LINEAR: [f64; 3] = [1., 1., 1.];
+
+    #[test]
+    fn t_convex_quadratic_matches_mocks() {
+        let quadratic = ConvexQuadratic::new(&HARD_HESSIAN, &HARD_LINEAR);
+        let u = [1.5, -2.0, 0.3];
+        let mut grad = [0.0; 3];
+        let mut grad_mock = [0.0; 3];
+        let mut cost_mock = 0.0;
+        quadratic.gradient(&u, &mut grad);
+        mocks::hard_quadratic_gradient(&u, &mut grad_mock).unwrap();
+        mocks::hard_quadratic_cost(&u, &mut cost_mock).unwrap();
+        unit_test_utils::assert_nearly_equal_array(&grad_mock, &grad, 1e-12, 1e-12, "gradient");
+        unit_test_utils::assert_nearly_equal(cost_mock, quadratic.cost(&u), 1e-12, 1e-12, "cost");
+    }
+
+    #[test]
+    fn t_kkt_residual_hard_quadratic_unconstrained() {
+        let bounds = constraints::NoConstraints::new();
+        let problem = Problem::new(
+            &bounds,
+            mocks::hard_quadratic_gradient,
+            mocks::hard_quadratic_cost,
+        );
+        let mut panoc_cache = PANOCCache::new(3, 1e-10, 10);
+        let mut u = [-12., -160., 55.];
+        let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(1000)
+            .solve(&mut u)
+            .unwrap();
+        assert!(status.has_converged());
+
+        let quadratic = ConvexQuadratic::new(&HARD_HESSIAN, &HARD_LINEAR);
+        let residual = kkt_residual(&quadratic, &u, &bounds);
+        assert!(residual < 1e-6, "residual = {}", residual);
+        assert!(kkt_residual(&quadratic, &[0.0; 3], &bounds) > 1.0);
+    }
+
+    #[test]
+    fn t_kkt_residual_hard_quadratic_ball() {
+        let bounds = constraints::Ball2::new(None, 0.05);
+        let quadratic = ConvexQuadratic::new(&HARD_HESSIAN, &HARD_LINEAR);
+        let residual = kkt_residual(&quadratic, &mocks::SOLUTION_HARD, &bounds);
+        assert!(residual < 1e-8, "residual = {}", residual);
+        assert!(kkt_residual(&quadratic, &[0.05, 0.0, 0.0], &bounds) > 1e-2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_convex_quadratic_wrong_dimensions() {
+        let _ = ConvexQuadratic::new(&HARD_HESSIAN, &[1.0, 2.0]);
+    }
+}
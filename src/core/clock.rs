@@ -0,0 +1,107 @@
+//! Clocks for the time-based termination of the solvers
+//!
+//! By default, the solvers measure time using the system clock, which
+//! requires the feature `std`. On targets without an operating system, a
+//! [Clock](trait.Clock.html) (for example, a hardware timer) can be provided
+//! instead; otherwise, the elapsed time is reported as zero and the maximum
+//! duration of a solve (e.g., `PANOCOptimizer::with_max_duration`) has no
+//! effect.
+//!
+use core::time::Duration;
+
+/// A monotonic clock
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use optimization_engine::core::Clock;
+///
+/// struct TickClock {
+///     ticks: fn() -> u64,
+/// }
+///
+/// impl Clock for TickClock {
+///     fn now(&self) -> Duration {
+///         // one tick per microsecond
+///         Duration::from_micros((self.ticks)())
+///     }
+/// }
+/// ```
+///
+pub trait Clock {
+    /// Time elapsed since a fixed, but arbitrary, instant (e.g., since boot)
+    ///
+    /// Successive calls must return nondecreasing values
+    fn now(&self) -> Duration;
+}
+
+/// Measures the time elapsed since its creation, using either the given
+/// clock or the system clock
+pub(crate) struct Stopwatch<'a> {
+    clock: Option<&'a dyn Clock>,
+    start: Duration,
+    #[cfg(feature = "std")]
+    start_instant: instant::Instant,
+}
+
+impl<'a> Stopwatch<'a> {
+    /// Starts a stopwatch which uses `clock`, if provided, and otherwise the
+    /// system clock
+    pub(crate) fn start(clock: Option<&'a dyn Clock>) -> Self {
+        Stopwatch {
+            clock,
+            start: clock.map_or(Duration::from_secs(0), |clock| clock.now()),
+            #[cfg(feature = "std")]
+            start_instant: instant::Instant::now(),
+        }
+    }
+
+    /// Time elapsed since the stopwatch was started
+    ///
+    /// Without a clock and without the feature `std`, this is always zero
+    pub(crate) fn elapsed(&self) -> Duration {
+        if let Some(clock) = self.clock {
+            return clock.now().saturating_sub(self.start);
+        }
+        #[cfg(feature = "std")]
+        {
+            self.start_instant.elapsed()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            Duration::from_secs(0)
+        }
+    }
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use core::cell::Cell;
+
+    struct ManualClock {
+        time: Cell<Duration>,
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            self.time.get()
+        }
+    }
+
+    #[test]
+    fn t_stopwatch_with_clock() {
+        let clock = ManualClock {
+            time: Cell::new(Duration::from_millis(10)),
+        };
+        let stopwatch = Stopwatch::start(Some(&clock));
+        assert_eq!(Duration::from_secs(0), stopwatch.elapsed());
+        clock.time.set(Duration::from_millis(25));
+        assert_eq!(Duration::from_millis(15), stopwatch.elapsed());
+    }
+}
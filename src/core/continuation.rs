@@ -0,0 +1,193 @@
+//! Continuation (homotopy) on a scalar parameter
+//!
+//! Problems with a penalty parameter, $c$, such as
+//!
+//! $$
+//! \min_{u \in U} f(u) + \tfrac{c}{2} \Vert F(u) \Vert^2,
+//! $$
+//!
+//! become ill-conditioned as $c$ increases. Such problems are often solved
+//! more robustly (and faster overall) by solving a sequence of problems with
+//! increasing values of $c$, each of which is warm-started from the solution
+//! of the previous one.
+//!
+use crate::core::panoc::{PANOCCache, PANOCOptimizer};
+use crate::core::{OptFloat, Optimizer, Problem, SolverStatus};
+use crate::{constraints, FunctionCallResult, SolverError};
+
+/// Solves a parametric problem with PANOC for a schedule of parameter values,
+/// warm-starting every solve from the solution of the previous one
+///
+/// The first problem is solved after resetting the cache. Every subsequent
+/// problem is solved starting from the solution of the previous problem and
+/// only the scalar state of the cache is reset, so that the LBFGS buffer is
+/// preserved (see `PANOCCache::soft_reset`). The problems need not be solved
+/// to convergence, except for the last one.
+///
+/// ## Arguments
+///
+/// - `problem_for`: closure which constructs the problem for a given value of
+///   the parameter
+/// - `schedule`: the values of the parameter, in the order in which the
+///   problems should be solved (typically increasing)
+/// - `cache`: an instance of `PANOCCache` (constructed once)
+/// - `u`: on entry, the initial guess of the first problem; on exit, the
+///   solution of the last problem
+/// - `max_iter`: maximum number of iterations for every problem
+///
+/// ## Returns
+///
+/// Returns the solver status of the last problem, or the first error that was
+/// encountered, in which case the subsequent problems are not solved
+///
+/// ## Panics
+///
+/// The method panics if `schedule` is empty, or if `max_iter` is zero
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::{constraints::NoConstraints, core::*, panoc::*, FunctionCallResult};
+///
+/// // f(u) = |u|^2 / 2 + c (u_0 + u_1 - 1)^2 / 2
+/// let bounds = NoConstraints::new();
+/// let problem_for = |c: f64| {
+///     let cost = move |u: &[f64], f: &mut f64| -> FunctionCallResult {
+///         *f = 0.5 * (u[0] * u[0] + u[1] * u[1]) + 0.5 * c * (u[0] + u[1] - 1.0).powi(2);
+///         Ok(())
+///     };
+///     let gradient = move |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+///         let violation = u[0] + u[1] - 1.0;
+///         grad[0] = u[0] + c * violation;
+///         grad[1] = u[1] + c * violation;
+///         Ok(())
+///     };
+///     Problem::new(&bounds, gradient, cost)
+/// };
+/// let mut cache = PANOCCache::new(2, 1e-8, 5);
+/// let mut u = [0.0; 2];
+///
+/// let status = continuation(problem_for, &[1.0, 10.0, 100.0], &mut cache, &mut u, 100).unwrap();
+/// assert!(status.has_converged());
+/// assert!((u[0] - 100.0 / 201.0).abs() < 1e-6);
+/// ```
+///
+pub fn continuation<'a, F, GradientType, ConstraintType, CostType, T>(
+    mut problem_for: F,
+    schedule: &[T],
+    cache: &mut PANOCCache<T>,
+    u: &mut [T],
+    max_iter: usize,
+) -> Result<SolverStatus<T>, SolverError>
+where
+    F: FnMut(T) -> Problem<'a, GradientType, ConstraintType, CostType, T>,
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'a,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T> + 'a,
+    T: OptFloat,
+{
+    assert!(!schedule.is_empty(), "the schedule is empty");
+
+    let mut status = None;
+    for (k, &parameter) in schedule.iter().enumerate() {
+        let mut optimizer =
+            PANOCOptimizer::new(problem_for(parameter), &mut *cache).with_max_iter(max_iter);
+        if k > 0 {
+            optimizer = optimizer.with_lbfgs_warm_start();
+        }
+        status = Some(optimizer.solve(u)?);
+    }
+    Ok(status.unwrap())
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::constraints::Ball2;
+
+    const DIM: usize = 5;
+
+    type PenaltyProblem<'a> = Problem<
+        'a,
+        Box<dyn Fn(&[f64], &mut [f64]) -> FunctionCallResult>,
+        Ball2<'static, f64>,
+        Box<dyn Fn(&[f64], &mut f64) -> FunctionCallResult>,
+        f64,
+    >;
+
+    // f(u) = |u - a|^2 / 2 + c (sum(u) - 1)^2 / 2, whose Hessian has condition
+    // number 1 + n * c
+    fn penalty_problem<'a>(bounds: &'a Ball2<'static, f64>, c: f64) -> PenaltyProblem<'a> {
+        let target = |i: usize| 0.1 * i as f64 - 0.5;
+        let cost = move |u: &[f64], f: &mut f64| -> FunctionCallResult {
+            let violation = u.iter().sum::<f64>() - 1.0;
+            *f = 0.5 * c * violation * violation;
+            u.iter()
+                .enumerate()
+                .for_each(|(i, &ui)| *f += 0.5 * (ui - target(i)).powi(2));
+            Ok(())
+        };
+        let gradient = move |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            let violation = u.iter().sum::<f64>() - 1.0;
+            grad.iter_mut()
+                .zip(u.iter())
+                .enumerate()
+                .for_each(|(i, (gi, &ui))| *gi = ui - target(i) + c * violation);
+            Ok(())
+        };
+        Problem::new(bounds, Box::new(gradient), Box::new(cost))
+    }
+
+    #[test]
+    fn t_continuation_penalty() {
+        let tolerance = 1e-8;
+        let bounds = Ball2::new(None, 10.0);
+        let schedule = [1.0, 10.0, 100.0, 1000.0, 10000.0];
+        let c_final = *schedule.last().unwrap();
+
+        let mut cache = PANOCCache::new(DIM, tolerance, 5);
+        let mut u = [0.0; DIM];
+        let status = continuation(
+            |c| penalty_problem(&bounds, c),
+            &schedule,
+            &mut cache,
+            &mut u,
+            1000,
+        )
+        .unwrap();
+        assert!(status.has_converged());
+
+        let mut cold_cache = PANOCCache::new(DIM, tolerance, 5);
+        let mut u_cold = [0.0; DIM];
+        let cold_status = PANOCOptimizer::new(penalty_problem(&bounds, c_final), &mut cold_cache)
+            .with_max_iter(1000)
+            .solve(&mut u_cold)
+            .unwrap();
+        assert!(cold_status.has_converged());
+
+        // the warm-started last solve takes fewer iterations than a cold solve
+        assert!(status.iterations() < cold_status.iterations());
+        // the last problem is ill-conditioned, so the two solutions agree up
+        // to a multiple of the tolerance
+        unit_test_utils::assert_nearly_equal_array(&u_cold, &u, 1e-6, 1e-6, "u");
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_continuation_empty_schedule() {
+        let bounds = Ball2::new(None, 10.0);
+        let mut cache = PANOCCache::new(DIM, 1e-8, 5);
+        let mut u = [0.0; DIM];
+        let _ = continuation(
+            |c| penalty_problem(&bounds, c),
+            &[],
+            &mut cache,
+            &mut u,
+            100,
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! FBS Cache
 //!
-use std::num::NonZeroUsize;
+use alloc::{vec, vec::Vec};
+
+use core::num::NonZeroUsize;
 
 use crate::core::OptFloat;
+use crate::matrix_operations;
 
 /// Cache for the forward-backward splitting (FBS), or projected gradient, algorithm
 ///
@@ -14,8 +17,36 @@ where
     pub(crate) work_gradient_u: Vec<T>,
     pub(crate) work_u_previous: Vec<T>,
     pub(crate) gamma: T,
+    /// Step size given to `new`, which is restored at the beginning of every
+    /// solve with an adaptive step size (and by `reset`)
+    pub(crate) gamma_initial: T,
     pub(crate) tolerance: T,
     pub(crate) norm_fpr: T,
+    /// Extrapolated (momentum) point of the accelerated iterations; this is
+    /// allocated only if acceleration is enabled
+    pub(crate) work_momentum: Option<Vec<T>>,
+    /// Extrapolation parameter `t` of the accelerated iterations
+    pub(crate) t: T,
+    /// Iterate before the previous one, $x_{k-1}$, which is needed by the
+    /// heavy-ball iterations; this is allocated only if momentum is enabled
+    pub(crate) work_u_heavy_ball: Option<Vec<T>>,
+    /// Ring buffer with the most recent iterates, which is used to detect
+    /// cycling; this is allocated only if cycle detection is enabled
+    pub(crate) work_u_history: Option<Vec<Vec<T>>>,
+    /// Number of iterates stored in `work_u_history`
+    pub(crate) history_count: usize,
+    /// Position in `work_u_history` where the next iterate is stored
+    pub(crate) history_next: usize,
+    /// Whether cycling was detected during the last call to `solve`
+    pub(crate) cycling: bool,
+    /// Whether the backtracking line search on gamma reached its maximum
+    /// number of iterations during the last call to `solve`
+    pub(crate) linesearch_failed: bool,
+    /// Number of evaluations of the cost function in the last call to `solve`
+    pub(crate) num_cost_evals: usize,
+    /// Number of evaluations of the gradient of the cost in the last call to
+    /// `solve`
+    pub(crate) num_grad_evals: usize,
 }
 
 impl<T> FBSCache<T>
@@ -50,8 +81,133 @@ where
             work_gradient_u: vec![T::zero(); n.get()],
             work_u_previous: vec![T::zero(); n.get()],
             gamma,
+            gamma_initial: gamma,
             tolerance,
             norm_fpr: T::infinity(),
+            work_momentum: None,
+            t: T::one(),
+            work_u_heavy_ball: None,
+            work_u_history: None,
+            history_count: 0,
+            history_next: 0,
+            cycling: false,
+            linesearch_failed: false,
+            num_cost_evals: 0,
+            num_grad_evals: 0,
+        }
+    }
+
+    /// Resets the cache, so that it can be reused for a new problem
+    ///
+    /// In particular,
+    ///
+    /// - Sets the work vectors (including those of the accelerated and the
+    ///   heavy-ball iterations, if allocated) to zero
+    /// - Sets the norm of the fixed-point residual to infinity
+    /// - Clears the stored iterates and the cycling flag of the cycle detection
+    /// - Restores the step size, `gamma`, given to `new` (the adaptive step
+    ///   size, if activated, may have decreased it)
+    ///
+    /// The tolerance is not changed and no memory is allocated or released.
+    pub fn reset(&mut self) {
+        self.work_gradient_u.iter_mut().for_each(|x| *x = T::zero());
+        self.work_u_previous.iter_mut().for_each(|x| *x = T::zero());
+        if let Some(y) = &mut self.work_momentum {
+            y.iter_mut().for_each(|x| *x = T::zero());
+        }
+        if let Some(u_older) = &mut self.work_u_heavy_ball {
+            u_older.iter_mut().for_each(|x| *x = T::zero());
+        }
+        self.norm_fpr = T::infinity();
+        self.t = T::one();
+        self.gamma = self.gamma_initial;
+        self.linesearch_failed = false;
+        self.reset_cycle_detection();
+    }
+
+    /// Number of evaluations of the cost function in the last call to `solve`
+    pub fn cost_evaluations(&self) -> usize {
+        self.num_cost_evals
+    }
+
+    /// Number of evaluations of the gradient of the cost in the last call to
+    /// `solve`
+    pub fn gradient_evaluations(&self) -> usize {
+        self.num_grad_evals
+    }
+
+    /// Allocates memory for the accelerated (FISTA) iterations, unless
+    /// already allocated
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `n` floats the first time it is called
+    ///
+    pub(crate) fn allocate_acceleration(&mut self) {
+        if self.work_momentum.is_none() {
+            self.work_momentum = Some(vec![T::zero(); self.work_u_previous.len()]);
+        }
+    }
+
+    /// Allocates memory for the heavy-ball iterations, unless already
+    /// allocated
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `n` floats the first time it is called
+    ///
+    pub(crate) fn allocate_heavy_ball(&mut self) {
+        if self.work_u_heavy_ball.is_none() {
+            self.work_u_heavy_ball = Some(vec![T::zero(); self.work_u_previous.len()]);
+        }
+    }
+
+    /// Allocates memory for the last `history_len` iterates, which are used
+    /// to detect cycling, unless memory for as many iterates is already
+    /// allocated
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `history_len * n` floats
+    ///
+    pub(crate) fn allocate_cycle_detection(&mut self, history_len: usize) {
+        let is_allocated = self
+            .work_u_history
+            .as_ref()
+            .is_some_and(|history| history.len() == history_len);
+        if !is_allocated {
+            let n = self.work_u_previous.len();
+            self.work_u_history = Some(vec![vec![T::zero(); n]; history_len]);
+        }
+        self.reset_cycle_detection();
+    }
+
+    /// Clears the stored iterates and the cycling flag
+    pub(crate) fn reset_cycle_detection(&mut self) {
+        self.history_count = 0;
+        self.history_next = 0;
+        self.cycling = false;
+    }
+
+    /// Checks whether `u` coincides, up to the tolerance, with one of the
+    /// stored iterates and then stores it, overwriting the oldest iterate
+    /// if the buffer is full; returns `true` if a repeat was found
+    pub(crate) fn record_iterate(&mut self, u: &[T]) -> bool {
+        let tolerance = self.tolerance;
+        let count = self.history_count;
+        let next = self.history_next;
+        if let Some(history) = &mut self.work_u_history {
+            let is_repeat = history
+                .iter()
+                .take(count)
+                .any(|u_old| matrix_operations::norm_inf_diff(u, u_old) <= tolerance);
+            history[next].copy_from_slice(u);
+            self.history_next = (next + 1) % history.len();
+            self.history_count = (count + 1).min(history.len());
+            self.cycling = is_repeat;
+            is_repeat
+        } else {
+            false
         }
     }
 }
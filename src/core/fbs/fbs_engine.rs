@@ -1,9 +1,13 @@
 //! FBS Engine
 //!
 use crate::core::fbs::FBSCache;
+use crate::core::problem::ProxOperator;
 use crate::core::{AlgorithmEngine, OptFloat, Problem};
 use crate::{constraints, matrix_operations, FunctionCallResult, SolverError};
 
+/// Maximum number of backtracking iterations (reductions of gamma) per step
+const MAX_BACKTRACKING_ITERATIONS: usize = 50;
+
 /// The FBE Engine defines the steps of the FBE algorithm and the termination criterion
 ///
 pub struct FBSEngine<'a, GradientType, ConstraintType, CostType, T>
@@ -15,6 +19,18 @@ where
 {
     pub(crate) problem: Problem<'a, GradientType, ConstraintType, CostType, T>,
     pub(crate) cache: &'a mut FBSCache<T>,
+    /// Whether to use Nesterov's acceleration (FISTA)
+    pub(crate) accelerated: bool,
+    /// Parameters `(beta, gamma_max)` of the backtracking line search on
+    /// gamma; `None` if gamma is fixed
+    pub(crate) adaptive_step: Option<(T, T)>,
+    /// Heavy-ball momentum parameter, beta; `None` if momentum is not used
+    pub(crate) momentum: Option<T>,
+    /// Proximal operator of the regularizer, which is applied before the
+    /// projection; `None` if there is no regularizer
+    pub(crate) prox: Option<ProxOperator<'a, T>>,
+    /// Whether to reject problems with nonconvex constraints at `init`
+    pub(crate) require_convex: bool,
 }
 
 impl<'a, GradientType, ConstraintType, CostType, T>
@@ -39,7 +55,41 @@ where
         problem: Problem<'a, GradientType, ConstraintType, CostType, T>,
         cache: &'a mut FBSCache<T>,
     ) -> FBSEngine<'a, GradientType, ConstraintType, CostType, T> {
-        FBSEngine { problem, cache }
+        FBSEngine {
+            problem,
+            cache,
+            accelerated: false,
+            adaptive_step: None,
+            momentum: None,
+            prox: None,
+            require_convex: false,
+        }
+    }
+
+    /// Replaces the constraints of the problem (see
+    /// `Problem::set_constraints`); the cache is not modified
+    pub fn set_constraints(&mut self, constraints: &'a ConstraintType) {
+        self.problem.set_constraints(constraints);
+    }
+
+    /// Activates the backtracking line search on gamma with parameters
+    /// `beta` and `gamma_max`
+    pub(crate) fn enable_adaptive_step(&mut self, beta: T, gamma_max: T) {
+        self.adaptive_step = Some((beta, gamma_max));
+    }
+
+    /// Activates heavy-ball momentum with parameter `beta`; this allocates
+    /// memory in the cache, if not already allocated
+    pub(crate) fn enable_momentum(&mut self, beta: T) {
+        self.cache.allocate_heavy_ball();
+        self.momentum = Some(beta);
+    }
+
+    /// Activates Nesterov's acceleration (FISTA); this allocates memory
+    /// in the cache, if not already allocated
+    pub(crate) fn enable_acceleration(&mut self) {
+        self.cache.allocate_acceleration();
+        self.accelerated = true;
     }
 
     fn gradient_step(&mut self, u_current: &mut [T]) {
@@ -48,17 +98,150 @@ where
             (self.problem.gradf)(u_current, &mut self.cache.work_gradient_u),
             "The computation of the gradient of the cost failed miserably"
         );
+        self.cache.num_grad_evals += 1;
 
         // take a gradient step: u_currect -= gamma * gradient
-        u_current
-            .iter_mut()
-            .zip(self.cache.work_gradient_u.iter())
-            .for_each(|(u, w)| *u -= self.cache.gamma * *w);
+        matrix_operations::sub_scaled_in_place(
+            u_current,
+            self.cache.gamma,
+            &self.cache.work_gradient_u,
+        );
     }
 
     fn projection_step(&mut self, u_current: &mut [T]) {
+        if let Some(prox) = self.prox {
+            prox(u_current, self.cache.gamma);
+        }
         self.problem.constraints.project(u_current);
     }
+
+    /// The point at which the current forward-backward step is taken; this is
+    /// the momentum point if acceleration is enabled, otherwise it is the
+    /// previous iterate
+    fn base_point(&self) -> &[T] {
+        match &self.cache.work_momentum {
+            Some(y) if self.accelerated => y,
+            _ => &self.cache.work_u_previous,
+        }
+    }
+
+    /// Backtracking line search on gamma
+    ///
+    /// On entry, `u_current` is the forward-backward step, $u^+$, from the base
+    /// point, $u$, and the gradient of $f$ at $u$ is stored in the cache. While
+    /// the sufficient decrease condition
+    ///
+    /// $$
+    /// f(u^+) \leq f(u) + \langle \nabla f(u), u^+ - u \rangle +
+    /// \tfrac{1}{2\gamma}\Vert{}u^+ - u{}\Vert^2
+    /// $$
+    ///
+    /// fails, $\gamma$ is multiplied by $\beta$ and $u^+$ is recomputed. If the
+    /// condition still fails after `MAX_BACKTRACKING_ITERATIONS` reductions
+    /// of $\gamma$, the last $u^+$ is kept and the failure is recorded in the
+    /// cache, so that the solver terminates.
+    fn backtrack_gamma(&mut self, u_current: &mut [T]) -> FunctionCallResult {
+        let step_beta = match self.adaptive_step {
+            Some((step_beta, _)) => step_beta,
+            None => return Ok(()),
+        };
+        let mut cost_base = T::zero();
+        (self.problem.cost)(self.base_point(), &mut cost_base)?;
+        self.cache.num_cost_evals += 1;
+
+        let mut num_backtracking_iterations = 0;
+        loop {
+            let mut cost_plus = T::zero();
+            (self.problem.cost)(u_current, &mut cost_plus)?;
+            self.cache.num_cost_evals += 1;
+            let base = self.base_point();
+            let inner_product = self
+                .cache
+                .work_gradient_u
+                .iter()
+                .zip(u_current.iter())
+                .zip(base.iter())
+                .fold(T::zero(), |sum, ((&df_i, &u_plus_i), &u_i)| {
+                    sum + df_i * (u_plus_i - u_i)
+                });
+            let dist_squared = matrix_operations::norm2_squared_diff(u_current, base);
+            let rhs = cost_base
+                + inner_product
+                + dist_squared / (T::from(2.0).unwrap() * self.cache.gamma);
+            if cost_plus <= rhs {
+                return Ok(());
+            }
+            if num_backtracking_iterations == MAX_BACKTRACKING_ITERATIONS {
+                self.cache.linesearch_failed = true;
+                return Ok(());
+            }
+
+            // gamma <-- beta * gamma and recompute u_plus
+            self.cache.gamma *= step_beta;
+            let gamma = self.cache.gamma;
+            u_current.copy_from_slice(self.base_point());
+            matrix_operations::sub_scaled_in_place(u_current, gamma, &self.cache.work_gradient_u);
+            self.projection_step(u_current);
+            num_backtracking_iterations += 1;
+        }
+    }
+
+    /// Heavy-ball step
+    ///
+    /// On entry, `u_current` is $x_k$ and the previous iterate, $x_{k-1}$, is
+    /// stored in the cache. The method computes
+    /// $x_{k+1} = \Pi(x_k - \gamma \nabla f(x_k) + \beta(x_k - x_{k-1}))$
+    fn heavy_ball_step(&mut self, u_current: &mut [T], beta: T) {
+        self.cache.work_u_previous.copy_from_slice(u_current); // x_k
+        self.gradient_step(u_current);
+        if let Some(u_older) = &mut self.cache.work_u_heavy_ball {
+            // u_current += beta * (x_k - x_{k-1}), then x_{k-1} <-- x_k
+            u_current
+                .iter_mut()
+                .zip(u_older.iter_mut())
+                .zip(self.cache.work_u_previous.iter())
+                .for_each(|((u, u_older_i), &u_previous_i)| {
+                    *u += beta * (u_previous_i - *u_older_i);
+                    *u_older_i = u_previous_i;
+                });
+        }
+        self.projection_step(u_current); // x_{k+1}
+        self.cache.norm_fpr =
+            matrix_operations::norm_inf_diff(u_current, &self.cache.work_u_previous);
+    }
+
+    /// Accelerated (FISTA) step
+    ///
+    /// On entry, `u_current` is $x_{k-1}$ and the momentum point in the cache is
+    /// $y_k$. The method computes $x_k = \Pi(y_k - \gamma \nabla f(y_k))$,
+    /// $t_{k+1} = (1+\sqrt{1+4t_k^2})/2$ and
+    /// $y_{k+1} = x_k + \tfrac{t_k-1}{t_{k+1}}(x_k - x_{k-1})$
+    fn accelerated_step(&mut self, u_current: &mut [T]) -> FunctionCallResult {
+        self.cache.work_u_previous.copy_from_slice(u_current); // x_{k-1}
+        if let Some(y) = &self.cache.work_momentum {
+            u_current.copy_from_slice(y);
+        }
+        self.gradient_step(u_current);
+        self.projection_step(u_current); // x_k
+        self.backtrack_gamma(u_current)?;
+
+        let t = self.cache.t;
+        let t_next =
+            (T::one() + (T::one() + T::from(4.0).unwrap() * t * t).sqrt()) / T::from(2.0).unwrap();
+        let beta = (t - T::one()) / t_next;
+        self.cache.t = t_next;
+
+        let cache = &mut self.cache;
+        if let Some(y) = &mut cache.work_momentum {
+            // fixed-point residual at the momentum point: ||x_k - y_k||
+            cache.norm_fpr = matrix_operations::norm_inf_diff(u_current, y);
+            y.iter_mut()
+                .zip(u_current.iter())
+                .zip(cache.work_u_previous.iter())
+                .for_each(|((yi, &xi), &xi_prev)| *yi = xi + beta * (xi - xi_prev));
+        }
+        Ok(())
+    }
 }
 
 impl<'a, GradientType, ConstraintType, CostType, T> AlgorithmEngine<T>
@@ -85,16 +268,53 @@ where
     /// The method may panick if the computation of the gradient of the cost function
     /// or the cost function panics.
     fn step(&mut self, u_current: &mut [T]) -> Result<bool, SolverError> {
-        self.cache.work_u_previous.copy_from_slice(u_current); // cache the previous step
-        self.gradient_step(u_current); // compute the gradient
-        self.projection_step(u_current); // project
-        self.cache.norm_fpr =
-            matrix_operations::norm_inf_diff(u_current, &self.cache.work_u_previous);
+        if self.accelerated {
+            self.accelerated_step(u_current)?;
+        } else if let Some(beta) = self.momentum {
+            self.heavy_ball_step(u_current, beta);
+        } else {
+            self.cache.work_u_previous.copy_from_slice(u_current); // cache the previous step
+            self.gradient_step(u_current); // compute the gradient
+            self.projection_step(u_current); // project
+            self.backtrack_gamma(u_current)?; // adapt gamma (if activated)
+            self.cache.norm_fpr =
+                matrix_operations::norm_inf_diff(u_current, &self.cache.work_u_previous);
+        }
 
-        Ok(self.cache.norm_fpr > self.cache.tolerance)
+        // terminate if the iterate repeats a recent one (if cycle detection is
+        // activated) or if the backtracking line search on gamma failed
+        let should_continue =
+            self.cache.norm_fpr > self.cache.tolerance && !self.cache.linesearch_failed;
+        Ok(should_continue && !self.cache.record_iterate(u_current))
     }
 
-    fn init(&mut self, _u_current: &mut [T]) -> FunctionCallResult {
+    fn init(&mut self, u_current: &mut [T]) -> FunctionCallResult {
+        if self.require_convex && !self.problem.constraints.is_convex() {
+            return Err(SolverError::InvalidProblem);
+        }
+        self.cache.reset_cycle_detection();
+        self.cache.record_iterate(u_current);
+        self.cache.num_cost_evals = 0;
+        self.cache.num_grad_evals = 0;
+        self.cache.linesearch_failed = false;
+        if let Some((_, gamma_max)) = self.adaptive_step {
+            // start from the step size given to the cache, not from the one
+            // to which the previous solve has decreased it
+            self.cache.gamma = self.cache.gamma_initial.min(gamma_max);
+        }
+        if self.momentum.is_some() {
+            // x_{-1} = x_0
+            if let Some(u_older) = &mut self.cache.work_u_heavy_ball {
+                u_older.copy_from_slice(u_current);
+            }
+        }
+        if self.accelerated {
+            // y_1 = x_0, t_1 = 1
+            self.cache.t = T::one();
+            if let Some(y) = &mut self.cache.work_momentum {
+                y.copy_from_slice(u_current);
+            }
+        }
         Ok(())
     }
 }
@@ -1,11 +1,14 @@
 //! FBS Algorithm
 //!
 
-use std::time;
+use core::time;
 
 use crate::core::fbs::fbs_engine::FBSEngine;
 use crate::core::fbs::FBSCache;
-use crate::core::{AlgorithmEngine, ExitStatus, OptFloat, Optimizer, Problem, SolverStatus};
+use crate::core::{
+    clock::Stopwatch, AlgorithmEngine, Clock, ExitStatus, OptFloat, Optimizer, Problem,
+    ProximalProblem, SolverStatus,
+};
 use crate::{constraints, matrix_operations, FunctionCallResult, SolverError};
 
 const MAX_ITER: usize = 100_usize;
@@ -30,6 +33,7 @@ where
     fbs_engine: FBSEngine<'a, GradientType, ConstraintType, CostType, T>,
     max_iter: usize,
     max_duration: Option<time::Duration>,
+    clock: Option<&'a dyn Clock>,
 }
 
 impl<'a, GradientType, ConstraintType, CostType, T>
@@ -54,9 +58,63 @@ where
             fbs_engine: FBSEngine::new(problem, cache),
             max_iter: MAX_ITER,
             max_duration: None,
+            clock: None,
         }
     }
 
+    /// Constructs a new instance of `FBSOptimizer` for a problem with a
+    /// proximable regularizer
+    ///
+    /// The backward step applies the proximal operator of the regularizer
+    /// and then projects on the set of constraints (see
+    /// [ProximalProblem](../struct.ProximalProblem.html) for the conditions
+    /// under which this composition is exact).
+    ///
+    /// ## Arguments
+    ///
+    /// - `problem`: problem definition
+    /// - `cache`: instance of `FBSCache`
+    pub fn new_proximal(
+        problem: ProximalProblem<'a, GradientType, ConstraintType, CostType, T>,
+        cache: &'a mut FBSCache<T>,
+    ) -> Self {
+        let mut optimizer = FBSOptimizer::new(problem.problem, cache);
+        optimizer.fbs_engine.prox = Some(problem.prox);
+        optimizer
+    }
+
+    /// Resets the cache (see `FBSCache::reset`), so that the optimizer can be
+    /// reused for a new solve without being reconstructed
+    pub fn reset(&mut self) {
+        self.fbs_engine.cache.reset();
+    }
+
+    /// Replaces the constraints of the problem, so that the optimizer and its
+    /// cache can be reused when the constraints change from one solve to the
+    /// next
+    ///
+    /// The constraints are borrowed for the lifetime `'a` of the optimizer
+    /// (see `Problem::set_constraints`), so the new constraints must be
+    /// declared before the optimizer, e.g., as a collection of sets among
+    /// which the optimizer switches. Constraints which are constructed anew
+    /// in every cycle with fresh data cannot be borrowed for that long; in
+    /// that case, construct a new optimizer in every cycle instead, which
+    /// does not allocate memory since the cache is reused.
+    ///
+    /// ## Arguments
+    ///
+    /// - `constraints`: the new constraints, of the same type as the
+    ///   current ones
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn set_constraints(&mut self, constraints: &'a ConstraintType) -> &mut Self {
+        self.fbs_engine.set_constraints(constraints);
+        self
+    }
+
     /// Sets the tolerance
     ///
     /// ## Panics
@@ -81,6 +139,175 @@ where
         self
     }
 
+    /// Activates Nesterov's acceleration (FISTA)
+    ///
+    /// The accelerated iterations maintain an extrapolation sequence
+    /// $t_{k+1} = (1+\sqrt{1+4t_k^2})/2$ and a momentum point, $y_k$, at which
+    /// the gradient and projection steps are taken. For convex problems this
+    /// requires that $\gamma \leq 1/L$, where $L$ is the Lipschitz constant of
+    /// the gradient of the cost.
+    ///
+    /// ## Memory allocation
+    ///
+    /// Unless already allocated, this method allocates memory for `n` floats
+    /// in the cache
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if heavy-ball momentum has been activated (see
+    /// `with_momentum`)
+    ///
+    pub fn with_acceleration(
+        mut self,
+    ) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        assert!(
+            self.fbs_engine.momentum.is_none(),
+            "acceleration cannot be combined with momentum"
+        );
+        self.fbs_engine.enable_acceleration();
+        self
+    }
+
+    /// Activates heavy-ball (Polyak) momentum
+    ///
+    /// The iterations become
+    ///
+    /// $$
+    /// x_{k+1} = \Pi_U(x_k - \gamma\nabla f(x_k) + \beta(x_k - x_{k-1})).
+    /// $$
+    ///
+    /// For unconstrained convex problems with $L$-Lipschitz gradient, the
+    /// iterations converge if $0 \leq \beta < 1$ and
+    /// $0 < \gamma < 2(1-\beta)/L$; for $\mu$-strongly convex quadratic
+    /// costs, the fastest rate is obtained with
+    /// $\beta = \left(\tfrac{\sqrt{L}-\sqrt{\mu}}{\sqrt{L}+\sqrt{\mu}}\right)^2$.
+    /// Momentum cannot be combined with Nesterov's acceleration and the
+    /// adaptive step (see `with_adaptive_step`) is not applied to heavy-ball
+    /// iterations.
+    ///
+    /// ## Arguments
+    ///
+    /// - `beta`: momentum parameter
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `beta` is not in $[0, 1)$, or if Nesterov's
+    /// acceleration has been activated
+    ///
+    /// ## Memory allocation
+    ///
+    /// Unless already allocated, this method allocates memory for `n` floats
+    /// in the cache
+    ///
+    pub fn with_momentum(
+        mut self,
+        beta: T,
+    ) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        assert!(
+            beta >= T::zero() && beta < T::one(),
+            "beta must be in [0, 1)"
+        );
+        assert!(
+            !self.fbs_engine.accelerated,
+            "momentum cannot be combined with acceleration"
+        );
+        self.fbs_engine.enable_momentum(beta);
+        self
+    }
+
+    /// Activates a backtracking line search on the step size, gamma
+    ///
+    /// At every iteration, the sufficient decrease condition
+    ///
+    /// $$
+    /// f(u^+) \leq f(u) + \langle \nabla f(u), u^+ - u \rangle +
+    /// \tfrac{1}{2\gamma}\Vert{}u^+ - u{}\Vert^2
+    /// $$
+    ///
+    /// is checked, where $u^+$ is the forward-backward step from $u$, and
+    /// $\gamma$ is multiplied by $\beta$ until this condition holds. This is
+    /// useful when the Lipschitz constant of $\nabla f$ is not known, in which
+    /// case the initial value of $\gamma$ (specified in the cache) can be large;
+    /// an initial estimate can also be obtained using
+    /// [LipschitzEstimator](../../lipschitz_estimator/struct.LipschitzEstimator.html).
+    ///
+    /// Each backtracking iteration costs one evaluation of the cost function.
+    /// Gamma is never increased during a solve and its final value is stored
+    /// in the cache; every solve starts again from the value of gamma given to
+    /// `FBSCache::new`. If the sufficient decrease condition still fails after
+    /// 50 reductions of gamma in one iteration, the solver terminates with exit
+    /// status `ExitStatus::LineSearchFailed`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `beta`: factor by which gamma is multiplied whenever the sufficient
+    ///   decrease condition fails
+    /// - `gamma_max`: maximum value of gamma; the initial value of gamma is
+    ///   the minimum of the value given to `FBSCache::new` and `gamma_max`
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `beta` is not in $(0, 1)$ or if `gamma_max` is
+    /// not positive
+    ///
+    pub fn with_adaptive_step(
+        mut self,
+        beta: T,
+        gamma_max: T,
+    ) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        assert!(
+            beta > T::zero() && beta < T::one(),
+            "beta must be in (0, 1)"
+        );
+        assert!(gamma_max > T::zero(), "gamma_max must be positive");
+        self.fbs_engine.enable_adaptive_step(beta, gamma_max);
+        self
+    }
+
+    /// Activates the detection of cycles
+    ///
+    /// On nonconvex sets (e.g., spheres), the projected gradient iterations
+    /// may cycle between a few points without ever converging. When cycle
+    /// detection is activated, every new iterate is compared with the last
+    /// `history_len` iterates and if it is within the specified tolerance
+    /// (in the infinity norm) from one of them, the solver terminates with
+    /// exit status `ExitStatus::Cycling`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `history_len`: number of recent iterates that are stored; in order
+    ///   to detect a cycle of length $p$ this needs to be at least $p$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `history_len` is zero
+    ///
+    /// ## Memory allocation
+    ///
+    /// Unless already allocated, this method allocates memory for
+    /// `history_len * n` floats in the cache
+    ///
+    pub fn with_cycle_detection(
+        self,
+        history_len: usize,
+    ) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        assert!(history_len > 0, "history_len must be positive");
+        self.fbs_engine.cache.allocate_cycle_detection(history_len);
+        self
+    }
+
+    /// Requires that the constraints be convex, which is when FBS comes with
+    /// convergence guarantees
+    ///
+    /// If the set of constraints is not convex (see
+    /// [Constraint::is_convex](../../constraints/trait.Constraint.html#tymethod.is_convex)),
+    /// `solve` fails with `SolverError::InvalidProblem` before performing any
+    /// iterations. By default, nonconvex constraints are accepted.
+    pub fn require_convex(mut self) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        self.fbs_engine.require_convex = true;
+        self
+    }
+
     /// Sets the maximum number of iterations
     pub fn with_max_duration(
         mut self,
@@ -89,33 +316,61 @@ where
         self.max_duration = Some(max_duration);
         self
     }
+
+    /// Sets the clock which is used to measure the solution time
+    ///
+    /// By default, the system clock is used, which requires the feature
+    /// `std`; without it, and unless a clock is provided, the solution time is
+    /// reported as zero and the maximum duration has no effect (see
+    /// [Clock](../clock/trait.Clock.html)).
+    pub fn with_clock(
+        mut self,
+        clock: &'a dyn Clock,
+    ) -> FBSOptimizer<'a, GradientType, ConstraintType, CostType, T> {
+        self.clock = Some(clock);
+        self
+    }
 }
 
-impl<'life, GradientType, ConstraintType, CostType, T> Optimizer<T>
-    for FBSOptimizer<'life, GradientType, ConstraintType, CostType, T>
+impl<'life, GradientType, ConstraintType, CostType, T>
+    FBSOptimizer<'life, GradientType, ConstraintType, CostType, T>
 where
     GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'life,
     CostType: Fn(&[T], &mut T) -> FunctionCallResult + 'life,
     ConstraintType: constraints::Constraint<T> + 'life,
     T: OptFloat,
 {
-    fn solve(&mut self, u: &mut [T]) -> Result<SolverStatus<T>, SolverError> {
-        let now = instant::Instant::now();
+    /// Solves the problem, invoking the callback (if any) once per iteration
+    fn solve_with_optional_callback(
+        &mut self,
+        u: &mut [T],
+        mut callback: Option<&mut dyn FnMut(usize, T, T) -> bool>,
+    ) -> Result<SolverStatus<T>, SolverError> {
+        let now = Stopwatch::start(self.clock);
 
         // Initialize - propagate error upstream, if any
         self.fbs_engine.init(u)?;
 
         let mut num_iter: usize = 0;
+        let mut continue_callback = true;
         let mut step_flag = self.fbs_engine.step(u)?;
 
         if let Some(dur) = self.max_duration {
             while step_flag && num_iter < self.max_iter && dur <= now.elapsed() {
                 num_iter += 1;
+                continue_callback = self.invoke_callback(&mut callback, num_iter, u)?;
+                if !continue_callback {
+                    break;
+                }
                 step_flag = self.fbs_engine.step(u)?
             }
         } else {
             while step_flag && num_iter < self.max_iter {
                 num_iter += 1;
+                continue_callback = self.invoke_callback(&mut callback, num_iter, u)?;
+                if !continue_callback {
+                    break;
+                }
                 step_flag = self.fbs_engine.step(u)?
             }
         }
@@ -123,14 +378,25 @@ where
         // cost at the solution [propagate error upstream]
         let mut cost_value: T = T::zero();
         (self.fbs_engine.problem.cost)(u, &mut cost_value)?;
+        self.fbs_engine.cache.num_cost_evals += 1;
 
-        if !matrix_operations::is_finite(u) || !cost_value.is_finite() {
+        if let Some(index) = matrix_operations::first_non_finite(u) {
+            return Err(SolverError::NotFiniteSolution {
+                index,
+                iteration: num_iter,
+            });
+        }
+        if !cost_value.is_finite() {
             return Err(SolverError::NotFiniteComputation);
         }
 
         // export solution status
         Ok(SolverStatus::new(
-            if num_iter < self.max_iter {
+            if self.fbs_engine.cache.cycling {
+                ExitStatus::Cycling
+            } else if self.fbs_engine.cache.linesearch_failed {
+                ExitStatus::LineSearchFailed
+            } else if num_iter < self.max_iter && continue_callback {
                 ExitStatus::Converged
             } else {
                 ExitStatus::NotConvergedIterations
@@ -139,6 +405,65 @@ where
             now.elapsed(),
             self.fbs_engine.cache.norm_fpr,
             cost_value,
+            None,
+            self.fbs_engine.cache.gamma,
+        )
+        .with_evaluations(
+            self.fbs_engine.cache.num_cost_evals,
+            self.fbs_engine.cache.num_grad_evals,
         ))
     }
+
+    /// Invokes the callback, if any, at the current iterate and returns
+    /// whether the iterations should continue
+    fn invoke_callback(
+        &mut self,
+        callback: &mut Option<&mut dyn FnMut(usize, T, T) -> bool>,
+        iteration: usize,
+        u: &[T],
+    ) -> Result<bool, SolverError> {
+        if let Some(callback) = callback {
+            let mut cost_value = T::zero();
+            (self.fbs_engine.problem.cost)(u, &mut cost_value)?;
+            self.fbs_engine.cache.num_cost_evals += 1;
+            Ok(callback(
+                iteration,
+                self.fbs_engine.cache.norm_fpr,
+                cost_value,
+            ))
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+impl<'life, GradientType, ConstraintType, CostType, T> Optimizer<T>
+    for FBSOptimizer<'life, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'life,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult + 'life,
+    ConstraintType: constraints::Constraint<T> + 'life,
+    T: OptFloat,
+{
+    fn solve(&mut self, u: &mut [T]) -> Result<SolverStatus<T>, SolverError> {
+        self.solve_with_optional_callback(u, None)
+    }
+
+    /// Note that this evaluates the cost function at every iteration
+    fn solve_with_callback(
+        &mut self,
+        u: &mut [T],
+        callback: &mut dyn FnMut(usize, T, T) -> bool,
+    ) -> Result<SolverStatus<T>, SolverError> {
+        self.solve_with_optional_callback(u, Some(callback))
+    }
+
+    fn max_iter(&self) -> usize {
+        self.max_iter
+    }
+
+    fn set_max_iter(&mut self, max_iter: usize) {
+        assert!(max_iter > 0, "max_iter must be larger than 0");
+        self.max_iter = max_iter;
+    }
 }
@@ -4,6 +4,7 @@ use super::super::*;
 use super::*;
 use crate::constraints;
 use crate::core::fbs::fbs_engine::FBSEngine;
+use crate::matrix_operations;
 
 const N_DIM: usize = 2;
 
@@ -49,7 +50,7 @@ fn t_solve_fbs_hard_failure_nan() {
     let mut u = [-12., -160., 55.];
     let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache).with_max_iter(10000);
     let status = optimizer.solve(&mut u);
-    assert_eq!(Err(SolverError::NotFiniteComputation), status);
+    assert!(matches!(status, Err(SolverError::NotFiniteSolution { .. })));
 }
 
 #[test]
@@ -118,6 +119,69 @@ fn t_solve_fbs() {
     unit_test_utils::assert_nearly_equal_array(&mocks::SOLUTION_A, &u, 1e-4, 1e-5, "u");
 }
 
+#[test]
+fn t_solve_fbs_with_callback() {
+    let radius = 0.2;
+    let box_constraints = constraints::Ball2::new(None, radius);
+    let problem = Problem::new(&box_constraints, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let mut u = [0.0; N_DIM];
+    let mut fbs = FBSOptimizer::new(problem, &mut fbs_cache);
+
+    // the optimizer is only accessed through the trait `Optimizer`
+    let optimizer: &mut dyn Optimizer<f64> = &mut fbs;
+    let mut num_calls = 0;
+    let mut last_cost = f64::INFINITY;
+    let status = optimizer
+        .solve_with_callback(&mut u, &mut |iteration, _norm_fpr, cost| {
+            num_calls += 1;
+            assert_eq!(num_calls, iteration);
+            assert!(cost <= last_cost);
+            last_cost = cost;
+            iteration < 3
+        })
+        .unwrap();
+
+    assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+    assert_eq!(3, status.iterations());
+    assert_eq!(3, num_calls);
+    assert!(status.norm_fpr() > 1e-6);
+}
+
+#[test]
+fn t_fbs_optimizer_reset() {
+    let radius = 0.2;
+    let box_constraints = constraints::Ball2::new(None, radius);
+    let initial_guesses = [[0.0, 0.0], [1.0, -2.0]];
+
+    // two solves with the same optimizer and a reset in between
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let problem = Problem::new(&box_constraints, mocks::my_gradient, mocks::my_cost);
+    let mut fbs = FBSOptimizer::new(problem, &mut fbs_cache).with_acceleration();
+    let mut solutions = initial_guesses;
+    let status_first = fbs.solve(&mut solutions[0]).unwrap();
+    fbs.reset();
+    let status_second = fbs.solve(&mut solutions[1]).unwrap();
+
+    // two solves with fresh caches and optimizers
+    for (k, (u0, status)) in initial_guesses
+        .iter()
+        .zip([status_first, status_second].iter())
+        .enumerate()
+    {
+        let mut fresh_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+        let problem = Problem::new(&box_constraints, mocks::my_gradient, mocks::my_cost);
+        let mut u = *u0;
+        let fresh_status = FBSOptimizer::new(problem, &mut fresh_cache)
+            .with_acceleration()
+            .solve(&mut u)
+            .unwrap();
+        assert_eq!(fresh_status.iterations(), status.iterations());
+        assert_eq!(fresh_status.norm_fpr(), status.norm_fpr());
+        assert_eq!(u, solutions[k]);
+    }
+}
+
 #[test]
 fn t_solve_fbs_many_times() {
     // Algorithm configuration
@@ -148,3 +212,419 @@ fn t_solve_fbs_many_times() {
         assert!(status.norm_fpr() < tolerance);
     }
 }
+
+#[test]
+fn t_solve_fbs_hard_accelerated() {
+    let bounds = constraints::NoConstraints::new();
+    let gamma = 0.0005;
+    let tolerance = 1e-6;
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(3).unwrap(), gamma, tolerance);
+
+    let problem = Problem::new(
+        &bounds,
+        mocks::hard_quadratic_gradient,
+        mocks::hard_quadratic_cost,
+    );
+    let mut u_plain = [-12., -160., 55.];
+    let status_plain = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(100_000)
+        .solve(&mut u_plain)
+        .unwrap();
+
+    let problem = Problem::new(
+        &bounds,
+        mocks::hard_quadratic_gradient,
+        mocks::hard_quadratic_cost,
+    );
+    let mut u_fista = [-12., -160., 55.];
+    let status_fista = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(100_000)
+        .with_acceleration()
+        .solve(&mut u_fista)
+        .unwrap();
+
+    println!(
+        "iterations: plain = {}, accelerated = {}",
+        status_plain.iterations(),
+        status_fista.iterations()
+    );
+    assert!(status_plain.has_converged());
+    assert!(status_fista.has_converged());
+    assert!(status_fista.norm_fpr() < tolerance);
+    assert!(status_fista.iterations() < status_plain.iterations());
+
+    let (mut cost_plain, mut cost_fista) = (0.0, 0.0);
+    mocks::hard_quadratic_cost(&u_plain, &mut cost_plain).unwrap();
+    mocks::hard_quadratic_cost(&u_fista, &mut cost_fista).unwrap();
+    unit_test_utils::assert_nearly_equal(cost_plain, cost_fista, 1e-5, 1e-8, "cost");
+}
+
+#[test]
+fn t_solve_fbs_hard_adaptive_step() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(
+        &bounds,
+        mocks::hard_quadratic_gradient,
+        mocks::hard_quadratic_cost,
+    );
+    // this value of gamma is too large (see `t_solve_fbs_hard_failure_nan`)
+    let gamma = 0.005;
+    let tolerance = 1e-6;
+
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(3).unwrap(), gamma, tolerance);
+    let mut u = [-12., -160., 55.];
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(100_000)
+        .with_adaptive_step(0.5, 1.0);
+    let status = optimizer.solve(&mut u).unwrap();
+
+    assert!(status.has_converged());
+    assert!(status.norm_fpr() < tolerance);
+    assert!(fbs_cache.gamma < gamma);
+    assert_eq!(fbs_cache.gamma, status.final_gamma());
+    assert_eq!(None, status.lipschitz_estimate());
+}
+
+#[test]
+fn t_fbs_adaptive_step_restores_gamma() {
+    let bounds = constraints::NoConstraints::new();
+    let gamma = 0.005;
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(3).unwrap(), gamma, 1e-6);
+    let problem = Problem::new(
+        &bounds,
+        mocks::hard_quadratic_gradient,
+        mocks::hard_quadratic_cost,
+    );
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(100_000)
+        .with_adaptive_step(0.5, 1.0);
+
+    // every solve starts again from the initial value of gamma, so a solve
+    // which has decreased gamma does not slow down the next one
+    let mut u = [-12., -160., 55.];
+    let status = optimizer.solve(&mut u).unwrap();
+    let mut u_again = [-12., -160., 55.];
+    let status_again = optimizer.solve(&mut u_again).unwrap();
+    assert!(status.final_gamma() < gamma);
+    assert_eq!(status.iterations(), status_again.iterations());
+    assert_eq!(status.final_gamma(), status_again.final_gamma());
+
+    // `reset` restores the initial value of gamma too
+    optimizer.reset();
+    assert_eq!(gamma, fbs_cache.gamma);
+}
+
+#[test]
+fn t_fbs_adaptive_step_linesearch_failed() {
+    // the gradient has the wrong sign, so the sufficient decrease condition
+    // fails for every value of gamma
+    let bounds = constraints::NoConstraints::new();
+    let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = u[0] * u[0];
+        Ok(())
+    };
+    let wrong_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        grad[0] = -2.0 * u[0];
+        Ok(())
+    };
+    let problem = Problem::new(&bounds, wrong_gradient, cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(1).unwrap(), 1.0, 1e-6);
+    let mut u = [1.0];
+    let status = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_adaptive_step(0.5, 1.0)
+        .with_max_iter(100)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::LineSearchFailed, status.exit_status());
+    assert_eq!(0, status.iterations());
+}
+
+#[test]
+#[should_panic]
+fn t_fbs_adaptive_step_wrong_beta() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let _ = FBSOptimizer::new(problem, &mut fbs_cache).with_adaptive_step(1.5, 1.0);
+}
+
+#[test]
+fn t_solve_fbs_momentum() {
+    let bounds = constraints::NoConstraints::new();
+    let gamma = 0.1;
+    let tolerance = 1e-8;
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), gamma, tolerance);
+
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut u_plain = [1.0, -2.0];
+    let status_plain = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(10_000)
+        .solve(&mut u_plain)
+        .unwrap();
+
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut u_momentum = [1.0, -2.0];
+    let status_momentum = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(10_000)
+        .with_momentum(0.5)
+        .solve(&mut u_momentum)
+        .unwrap();
+
+    println!(
+        "iterations: plain = {}, heavy ball = {}",
+        status_plain.iterations(),
+        status_momentum.iterations()
+    );
+    assert!(status_plain.has_converged());
+    assert!(status_momentum.has_converged());
+    assert!(status_momentum.iterations() < status_plain.iterations());
+    // unconstrained minimizer of `my_cost`
+    unit_test_utils::assert_nearly_equal_array(&[-3.0, 2.0], &u_momentum, 1e-5, 1e-6, "u");
+}
+
+#[test]
+#[should_panic]
+fn t_fbs_momentum_with_acceleration() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let _ = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_acceleration()
+        .with_momentum(0.5);
+}
+
+#[test]
+fn t_solve_fbs_cycling() {
+    // with f(u) = ||u||^2 and gamma = 1, the gradient step maps u to -u, so the
+    // iterates on the unit circle cycle between u0 and -u0
+    let sphere = constraints::Sphere2::new(None, 1.0);
+    let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = matrix_operations::norm2_squared(u);
+        Ok(())
+    };
+    let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        grad.iter_mut()
+            .zip(u.iter())
+            .for_each(|(g, &ui)| *g = 2.0 * ui);
+        Ok(())
+    };
+    let tolerance = 1e-6;
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 1.0, tolerance);
+
+    let mut u = [0.6, 0.8];
+    let problem = Problem::new(&sphere, gradient, cost);
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache).with_max_iter(1000);
+    let status = optimizer.solve(&mut u).unwrap();
+    assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+
+    let mut u = [0.6, 0.8];
+    let problem = Problem::new(&sphere, gradient, cost);
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(1000)
+        .with_cycle_detection(2);
+    let status = optimizer.solve(&mut u).unwrap();
+    assert_eq!(ExitStatus::Cycling, status.exit_status());
+    assert!(!status.has_converged());
+    assert!(status.iterations() <= 2);
+}
+
+#[test]
+fn t_solve_fbs_cycle_detection_converges() {
+    let radius = 0.2;
+    let box_constraints = constraints::Ball2::new(None, radius);
+    let problem = Problem::new(&box_constraints, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let mut u = [0.0; N_DIM];
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache).with_cycle_detection(5);
+    let status = optimizer.solve(&mut u).unwrap();
+    assert_eq!(ExitStatus::Converged, status.exit_status());
+}
+
+#[test]
+#[should_panic]
+fn t_fbs_cycle_detection_zero_history() {
+    let no_constraints = constraints::NoConstraints::new();
+    let problem = Problem::new(&no_constraints, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let _optimizer = FBSOptimizer::new(problem, &mut fbs_cache).with_cycle_detection(0);
+}
+
+#[test]
+fn t_solve_fbs_status_summary() {
+    let radius = 0.2;
+    let box_constraints = constraints::Ball2::new(None, radius);
+    let problem = Problem::new(&box_constraints, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+    let mut u = [0.0; N_DIM];
+    let mut optimizer = FBSOptimizer::new(problem, &mut fbs_cache);
+    let status = optimizer.solve(&mut u).unwrap();
+
+    let summary = status.summary(&u, &box_constraints, 1e-10);
+    assert!(summary.converged());
+    assert!(summary.feasible());
+    assert_eq!(status.norm_fpr(), summary.fpr_norm());
+    assert_eq!(status.cost_value(), summary.cost());
+    assert_eq!(status.iterations(), summary.iterations());
+    assert_eq!(status.solve_time().as_micros() as u64, summary.time_us());
+
+    // a point outside the ball is not feasible
+    let summary = status.summary(&[radius, radius], &box_constraints, 1e-10);
+    assert!(!summary.feasible());
+}
+
+#[test]
+fn t_solve_fbs_proximal_lasso() {
+    // f(u) = 0.5 * sum_i (a_i u_i - b_i)^2 and g(u) = lambda * ||u||_1, so the
+    // solution is u_i = soft(a_i b_i, lambda) / a_i^2
+    let a = [2.0, 1.0, 0.5, 1.0];
+    let b = [3.0, -0.2, 4.0, -2.0];
+    let lambda = 0.5;
+    let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = 0.5
+            * u.iter()
+                .zip(a.iter().zip(b.iter()))
+                .map(|(ui, (ai, bi))| (ai * ui - bi).powi(2))
+                .sum::<f64>();
+        Ok(())
+    };
+    let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        grad.iter_mut()
+            .zip(u.iter().zip(a.iter().zip(b.iter())))
+            .for_each(|(gi, (ui, (ai, bi)))| *gi = ai * (ai * ui - bi));
+        Ok(())
+    };
+    let soft_thresholding = |v: &mut [f64], gamma: f64| {
+        v.iter_mut()
+            .for_each(|vi| *vi = vi.signum() * (vi.abs() - lambda * gamma).max(0.0));
+    };
+    let bounds = constraints::NoConstraints::new();
+    let problem = ProximalProblem::new(&bounds, &soft_thresholding, gradient, cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(4).unwrap(), 0.2, 1e-10);
+    let mut u = [0.0; 4];
+    let status = FBSOptimizer::new_proximal(problem, &mut fbs_cache)
+        .with_max_iter(10_000)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+
+    let u_star: Vec<f64> = a
+        .iter()
+        .zip(b.iter())
+        .map(|(ai, bi)| {
+            let v: f64 = ai * bi;
+            v.signum() * (v.abs() - lambda).max(0.0) / (ai * ai)
+        })
+        .collect();
+    assert_eq!(0.0, u[1]);
+    unit_test_utils::assert_nearly_equal_array(&u_star, &u, 1e-8, 1e-10, "u");
+}
+
+#[test]
+fn t_solve_fbs_require_convex() {
+    let sphere = constraints::Sphere2::new(None, 0.2);
+    let tolerance = 1e-6;
+
+    // by default, nonconvex constraints are accepted
+    let problem = Problem::new(&sphere, mocks::my_gradient, mocks::my_cost);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, tolerance);
+    let mut u = [0.0; N_DIM];
+    assert!(FBSOptimizer::new(problem, &mut fbs_cache)
+        .solve(&mut u)
+        .is_ok());
+
+    // ... unless convexity is required
+    let problem = Problem::new(&sphere, mocks::my_gradient, mocks::my_cost);
+    let mut u = [0.0; N_DIM];
+    let result = FBSOptimizer::new(problem, &mut fbs_cache)
+        .require_convex()
+        .solve(&mut u);
+    assert_eq!(Err(SolverError::InvalidProblem), result);
+    assert_eq!([0.0; N_DIM], u);
+
+    // convex constraints pass the check
+    let ball = constraints::Ball2::new(None, 0.2);
+    let problem = Problem::new(&ball, mocks::my_gradient, mocks::my_cost);
+    let status = FBSOptimizer::new(problem, &mut fbs_cache)
+        .require_convex()
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+}
+
+#[test]
+fn t_fbs_set_constraints() {
+    let (xmin_1, xmax_1) = ([-1.0, -1.0], [1.0, 1.0]);
+    let (xmin_2, xmax_2) = ([-5.0, 0.5], [0.0, 2.0]);
+    let rectangle_1 = constraints::Rectangle::new(Some(&xmin_1), Some(&xmax_1));
+    let rectangle_2 = constraints::Rectangle::new(Some(&xmin_2), Some(&xmax_2));
+
+    // minimize 0.5*||u - (2, -3)||^2 over a box
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        grad[0] = u[0] - 2.0;
+        grad[1] = u[1] + 3.0;
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = 0.5 * ((u[0] - 2.0).powi(2) + (u[1] + 3.0).powi(2));
+        Ok(())
+    };
+    let problem = Problem::new(&rectangle_1, cost_gradient, cost_function);
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(2).unwrap(), 0.5, 1e-10);
+    let mut fbs = FBSOptimizer::new(problem, &mut fbs_cache).with_max_iter(100);
+
+    let mut u = [0.0; 2];
+    assert!(fbs.solve(&mut u).unwrap().has_converged());
+    unit_test_utils::assert_nearly_equal_array(&[1.0, -1.0], &u, 1e-8, 1e-8, "u (1)");
+
+    fbs.set_constraints(&rectangle_2);
+    let mut u = [0.0; 2];
+    assert!(fbs.solve(&mut u).unwrap().has_converged());
+    unit_test_utils::assert_nearly_equal_array(&[0.0, 0.5], &u, 1e-8, 1e-8, "u (2)");
+}
+
+#[test]
+fn t_fbs_function_evaluations() {
+    let cost_calls = core::cell::Cell::new(0);
+    let gradient_calls = core::cell::Cell::new(0);
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        cost_calls.set(cost_calls.get() + 1);
+        mocks::my_cost(u, c)
+    };
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        gradient_calls.set(gradient_calls.get() + 1);
+        mocks::my_gradient(u, grad)
+    };
+    let box_constraints = constraints::NoConstraints::new();
+    let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), 0.1, 1e-6);
+
+    // with a fixed step size, every step evaluates the gradient once (the
+    // first step is taken before the first iteration is counted) and the
+    // cost is evaluated once at the solution
+    let problem = Problem::new(&box_constraints, cost_gradient, cost_function);
+    let mut u = [1.0, -1.0];
+    let status = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_max_iter(5)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(5, status.iterations());
+    assert_eq!(1, status.cost_evaluations());
+    assert_eq!(6, status.gradient_evaluations());
+    assert_eq!(1, cost_calls.get());
+    assert_eq!(6, gradient_calls.get());
+
+    // with the adaptive step size, the cost is also evaluated in the line
+    // search
+    cost_calls.set(0);
+    gradient_calls.set(0);
+    let problem = Problem::new(&box_constraints, cost_gradient, cost_function);
+    let mut u = [1.0, -1.0];
+    let status = FBSOptimizer::new(problem, &mut fbs_cache)
+        .with_adaptive_step(0.5, 1.0)
+        .with_max_iter(50)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.cost_evaluations() > status.iterations());
+    assert_eq!(cost_calls.get(), status.cost_evaluations());
+    assert_eq!(gradient_calls.get(), status.gradient_evaluations());
+    assert_eq!(cost_calls.get(), fbs_cache.cost_evaluations());
+}
@@ -0,0 +1,165 @@
+//! Verification of user-provided gradients
+//!
+use crate::core::OptFloat;
+use crate::{matrix_operations, FunctionCallResult, SolverError};
+
+/// Compares a gradient function against a finite-difference approximation
+///
+/// The gradient of the cost at `u` is approximated by central differences,
+///
+/// $$
+/// \frac{\partial f}{\partial u_i}(u) \approx \frac{f(u + he_i) - f(u - he_i)}{2h},
+/// $$
+///
+/// and the infinity norm of the difference between this approximation and the
+/// gradient computed by `grad` is returned. For a correct gradient of a smooth
+/// cost, this is of the order of $h^2$ (plus round-off errors of the order of
+/// $\epsilon/h$), so a large value indicates an error in the gradient.
+///
+/// ## Arguments
+///
+/// - `cost`: the cost function
+/// - `grad`: the gradient of the cost function
+/// - `u`: the point at which the gradient is checked
+/// - `h`: the finite-difference step size (e.g., `1e-6` for `f64`)
+/// - `work_u`: scratch buffer of the same length as `u`
+/// - `work_gradient`: scratch buffer of the same length as `u`; on exit, it
+///   contains the gradient computed by `grad`
+///
+/// ## Returns
+///
+/// The infinity norm of the difference between the analytic gradient and its
+/// finite-difference approximation, or the error returned by the cost or its
+/// gradient
+///
+/// ## Panics
+///
+/// The method panics if `h` is not positive, or if the scratch buffers do not
+/// have the same length as `u`
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::core::check_gradient;
+/// use optimization_engine::FunctionCallResult;
+///
+/// let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+///     *c = u[0] * u[0] + 3.0 * u[1];
+///     Ok(())
+/// };
+/// let grad = |u: &[f64], g: &mut [f64]| -> FunctionCallResult {
+///     g[0] = 2.0 * u[0];
+///     g[1] = 3.0;
+///     Ok(())
+/// };
+/// let (mut work_u, mut work_gradient) = ([0.0; 2], [0.0; 2]);
+/// let error = check_gradient(&cost, &grad, &[1.0, 2.0], 1e-6, &mut work_u, &mut work_gradient);
+/// assert!(error.unwrap() < 1e-6);
+/// ```
+///
+pub fn check_gradient<CostType, GradientType, T>(
+    cost: &CostType,
+    grad: &GradientType,
+    u: &[T],
+    h: T,
+    work_u: &mut [T],
+    work_gradient: &mut [T],
+) -> Result<T, SolverError>
+where
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    T: OptFloat,
+{
+    assert!(h > T::zero(), "h must be positive");
+    assert_eq!(u.len(), work_u.len(), "work_u has wrong length");
+    assert_eq!(
+        u.len(),
+        work_gradient.len(),
+        "work_gradient has wrong length"
+    );
+
+    grad(u, work_gradient)?;
+    work_u.copy_from_slice(u);
+
+    let mut max_error = T::zero();
+    let (mut cost_plus, mut cost_minus) = (T::zero(), T::zero());
+    for i in 0..u.len() {
+        work_u[i] = u[i] + h;
+        cost(work_u, &mut cost_plus)?;
+        work_u[i] = u[i] - h;
+        cost(work_u, &mut cost_minus)?;
+        work_u[i] = u[i];
+
+        let fd_derivative = (cost_plus - cost_minus) / (T::from(2.0).unwrap() * h);
+        max_error = max_error.max((fd_derivative - work_gradient[i]).abs());
+    }
+
+    if !max_error.is_finite() || !matrix_operations::is_finite(work_gradient) {
+        return Err(SolverError::NotFiniteComputation);
+    }
+    Ok(max_error)
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use crate::core::check_gradient;
+    use crate::{mocks, FunctionCallResult, SolverError};
+
+    #[test]
+    fn t_check_gradient_correct() {
+        let (mut work_u, mut work_gradient) = ([0.0; 2], [0.0; 2]);
+        let u = [1.5, -0.7];
+        let error = check_gradient(
+            &mocks::my_cost,
+            &mocks::my_gradient,
+            &u,
+            1e-6,
+            &mut work_u,
+            &mut work_gradient,
+        )
+        .unwrap();
+        assert!(error < 1e-7, "error = {}", error);
+        // the work vector is restored
+        assert_eq!(u, work_u);
+    }
+
+    #[test]
+    fn t_check_gradient_wrong() {
+        let wrong_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::my_gradient(u, grad)?;
+            grad[1] *= 2.0;
+            Ok(())
+        };
+        let (mut work_u, mut work_gradient) = ([0.0; 2], [0.0; 2]);
+        let error = check_gradient(
+            &mocks::my_cost,
+            &wrong_gradient,
+            &[1.5, -0.7],
+            1e-6,
+            &mut work_u,
+            &mut work_gradient,
+        )
+        .unwrap();
+        unit_test_utils::assert_nearly_equal(0.9, error, 1e-6, 1e-8, "error");
+    }
+
+    #[test]
+    fn t_check_gradient_cost_error() {
+        let failing_cost =
+            |_u: &[f64], _c: &mut f64| -> FunctionCallResult { Err(SolverError::Cost) };
+        let (mut work_u, mut work_gradient) = ([0.0; 2], [0.0; 2]);
+        let result = check_gradient(
+            &failing_cost,
+            &mocks::my_gradient,
+            &[1.5, -0.7],
+            1e-6,
+            &mut work_u,
+            &mut work_gradient,
+        );
+        assert_eq!(Err(SolverError::Cost), result);
+    }
+}
@@ -0,0 +1,156 @@
+//! Finite-difference Jacobian-transpose-vector products
+//!
+use crate::core::OptFloat;
+use crate::{matrix_operations, FunctionCallResult, SolverError};
+
+/// Approximates the product $JF_1(u)^\top d$ by finite differences
+///
+/// The ALM/PM interface requires the product of the transpose of the Jacobian
+/// of the mapping $F_1$ with a vector, $JF_1(u)^\top d$. Its $i$-th element
+/// is the derivative of $\langle F_1(u), d \rangle$ with respect to $u_i$,
+/// which is approximated by central differences,
+///
+/// $$
+/// (JF_1(u)^\top d)_i \approx
+/// \frac{\langle F_1(u + he_i) - F_1(u - he_i), d \rangle}{2h}.
+/// $$
+///
+/// This requires $2n$ evaluations of $F_1$, where $n$ is the length of $u$,
+/// so it is meant for prototyping and for checking hand-coded products, not
+/// for production code.
+///
+/// ## Arguments
+///
+/// - `mapping`: the mapping $F_1$
+/// - `u`: the point at which the Jacobian is computed
+/// - `d`: the vector $d$
+/// - `out`: on exit, the approximation of $JF_1(u)^\top d$
+/// - `h`: the finite-difference step size (e.g., `1e-6` for `f64`)
+/// - `work_u`: scratch buffer of the same length as `u`
+/// - `work_mapping`: scratch buffer of twice the length of `d`
+///
+/// ## Returns
+///
+/// `Ok(())`, or the error returned by the mapping; if the result is not
+/// finite, `SolverError::NotFiniteComputation` is returned
+///
+/// ## Panics
+///
+/// The method panics if `h` is not positive, if `out` does not have the same
+/// length as `u`, or if the scratch buffers have wrong lengths
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::core::jacobian_transpose_product_fd;
+/// use optimization_engine::FunctionCallResult;
+///
+/// // F1(u) = (u_0 u_1, u_1)
+/// let mapping = |u: &[f64], f1u: &mut [f64]| -> FunctionCallResult {
+///     f1u[0] = u[0] * u[1];
+///     f1u[1] = u[1];
+///     Ok(())
+/// };
+/// let (mut work_u, mut work_mapping) = ([0.0; 2], [0.0; 4]);
+/// let mut out = [0.0; 2];
+/// jacobian_transpose_product_fd(
+///     &mapping, &[2.0, 3.0], &[1.0, 1.0], &mut out, 1e-6, &mut work_u, &mut work_mapping,
+/// )
+/// .unwrap();
+/// assert!((out[0] - 3.0).abs() < 1e-6 && (out[1] - 3.0).abs() < 1e-6);
+/// ```
+///
+pub fn jacobian_transpose_product_fd<MappingType, T>(
+    mapping: &MappingType,
+    u: &[T],
+    d: &[T],
+    out: &mut [T],
+    h: T,
+    work_u: &mut [T],
+    work_mapping: &mut [T],
+) -> FunctionCallResult
+where
+    MappingType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    T: OptFloat,
+{
+    assert!(h > T::zero(), "h must be positive");
+    assert_eq!(u.len(), out.len(), "out has wrong length");
+    assert_eq!(u.len(), work_u.len(), "work_u has wrong length");
+    assert_eq!(
+        2 * d.len(),
+        work_mapping.len(),
+        "work_mapping has wrong length"
+    );
+
+    let (mapping_plus, mapping_minus) = work_mapping.split_at_mut(d.len());
+    work_u.copy_from_slice(u);
+    let two_h = T::from(2.0).unwrap() * h;
+    for (i, out_i) in out.iter_mut().enumerate() {
+        work_u[i] = u[i] + h;
+        mapping(work_u, mapping_plus)?;
+        work_u[i] = u[i] - h;
+        mapping(work_u, mapping_minus)?;
+        work_u[i] = u[i];
+
+        *out_i = (matrix_operations::inner_product(mapping_plus, d)
+            - matrix_operations::inner_product(mapping_minus, d))
+            / two_h;
+    }
+
+    if !matrix_operations::is_finite(out) {
+        return Err(SolverError::NotFiniteComputation);
+    }
+    Ok(())
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use crate::core::jacobian_transpose_product_fd;
+    use crate::{mocks, FunctionCallResult, SolverError};
+
+    #[test]
+    fn t_jacobian_transpose_product_fd_affine() {
+        let u = [0.5, -1.5, 2.0];
+        let d = [1.2, -0.4];
+        let (mut work_u, mut work_mapping) = ([0.0; 3], [0.0; 4]);
+        let mut out = [0.0; 3];
+        jacobian_transpose_product_fd(
+            &mocks::mapping_f1_affine,
+            &u,
+            &d,
+            &mut out,
+            1e-6,
+            &mut work_u,
+            &mut work_mapping,
+        )
+        .unwrap();
+
+        let mut expected = [0.0; 3];
+        mocks::mapping_f1_affine_jacobian_product(&u, &d, &mut expected).unwrap();
+        unit_test_utils::assert_nearly_equal_array(&expected, &out, 1e-7, 1e-8, "product");
+        // the work vector is restored
+        assert_eq!(u, work_u);
+    }
+
+    #[test]
+    fn t_jacobian_transpose_product_fd_mapping_error() {
+        let failing_mapping =
+            |_u: &[f64], _f1u: &mut [f64]| -> FunctionCallResult { Err(SolverError::Cost) };
+        let (mut work_u, mut work_mapping) = ([0.0; 3], [0.0; 4]);
+        let mut out = [0.0; 3];
+        let result = jacobian_transpose_product_fd(
+            &failing_mapping,
+            &[0.5, -1.5, 2.0],
+            &[1.2, -0.4],
+            &mut out,
+            1e-6,
+            &mut work_u,
+            &mut work_mapping,
+        );
+        assert_eq!(Err(SolverError::Cost), result);
+    }
+}
@@ -3,21 +3,40 @@
 //!
 //!
 
+pub mod certificate;
+pub mod clock;
+mod continuation;
 pub mod fbs;
+mod gradient_check;
+mod jacobian_fd;
+mod multi_start;
 pub mod opt_float;
 pub mod panoc;
 pub mod problem;
+mod retry;
+pub mod separable;
+pub mod solver_params;
 pub mod solver_status;
+mod trust_region;
 
 pub use crate::{constraints, FunctionCallResult, SolverError};
+pub use clock::Clock;
+pub use continuation::continuation;
+pub use gradient_check::check_gradient;
+pub use jacobian_fd::jacobian_transpose_product_fd;
+pub use multi_start::multi_start;
 pub use opt_float::OptFloat;
-pub use problem::Problem;
-pub use solver_status::SolverStatus;
+pub use problem::{Problem, ProximalProblem};
+pub use retry::solve_with_retry;
+pub use solver_params::SolverParams;
+pub use solver_status::{SolverStatus, StatusSummary};
+pub use trust_region::trust_region_solve;
 
 /// Exit status of an algorithm (not algorithm specific)
 ///
 ///
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExitStatus {
     /// The algorithm has converged
     ///
@@ -28,18 +47,59 @@ pub enum ExitStatus {
     NotConvergedIterations,
     /// Failed to converge because the maximum execution time was reached
     NotConvergedOutOfTime,
+    /// Failed to converge because the iterates cycle
+    ///
+    /// The algorithm terminated because an iterate coincided (up to the
+    /// tolerance) with a recent one; this may happen with nonconvex constraints
+    Cycling,
+    /// Failed to converge because the cost stalled
+    ///
+    /// The algorithm terminated because the relative decrease of the
+    /// forward-backward envelope over a window of recent iterations was below a threshold (see
+    /// `PANOCOptimizer::with_stall_detection`)
+    Stalled,
+    /// Failed to converge because the line search failed
+    ///
+    /// The algorithm terminated because the line search condition was still
+    /// violated after the maximum number of line search iterations (see
+    /// `PANOCOptimizer::stop_on_linesearch_failure` and
+    /// `FBSOptimizer::with_adaptive_step`)
+    LineSearchFailed,
 }
 
 /// A general optimizer
 pub trait Optimizer<T>
 where
-    T: OptFloat + std::fmt::Debug,
+    T: OptFloat,
 {
     /// solves a given problem and updates the initial estimate `u` with the solution
     ///
     /// Returns the solver status
     ///
     fn solve(&mut self, u: &mut [T]) -> Result<SolverStatus<T>, SolverError>;
+
+    /// solves a given problem, like `solve`, invoking a callback once per
+    /// iteration
+    ///
+    /// The callback receives the iteration count, the current norm of the
+    /// fixed-point residual and the current cost value. If the callback
+    /// returns `false`, the solver stops and reports the exit status
+    /// `NotConvergedIterations`.
+    ///
+    fn solve_with_callback(
+        &mut self,
+        u: &mut [T],
+        callback: &mut dyn FnMut(usize, T, T) -> bool,
+    ) -> Result<SolverStatus<T>, SolverError>;
+
+    /// maximum number of iterations of every call to `solve`
+    fn max_iter(&self) -> usize;
+
+    /// sets the maximum number of iterations of every call to `solve`
+    ///
+    /// Panics if `max_iter` is zero
+    ///
+    fn set_max_iter(&mut self, max_iter: usize);
 }
 
 /// Engine supporting an algorithm
@@ -53,7 +113,7 @@ where
 ///
 pub trait AlgorithmEngine<T>
 where
-    T: OptFloat + std::fmt::Debug,
+    T: OptFloat,
 {
     /// Take a step of the algorithm and return `Ok(true)` only if the iterations should continue
     fn step(&mut self, u: &mut [T]) -> Result<bool, SolverError>;
@@ -0,0 +1,175 @@
+//! Multi-start solution of nonconvex problems
+//!
+use alloc::vec::Vec;
+
+use crate::core::{OptFloat, SolverStatus};
+use crate::SolverError;
+
+/// Solves a problem starting from several initial points and returns the
+/// solution with the lowest cost
+///
+/// On nonconvex problems, solvers such as PANOC converge to local minima,
+/// which depend on the initial point. This function runs the solver from
+/// every given initial point and keeps the result with the lowest final cost.
+///
+/// The solver is provided as a closure, `solve_from`, which constructs a
+/// fresh optimizer and solves the problem starting from the given point,
+/// which it updates with the solution. All optimizers may (and should) use
+/// the same cache, which is reset at the beginning of every solve (for
+/// instance, `PANOCOptimizer` resets its cache unless the L-BFGS warm start
+/// is activated), so no memory is allocated for the solver.
+///
+/// ## Arguments
+///
+/// - `solve_from`: closure which solves the problem starting from a given
+///   point (which it updates with the solution) and returns the solver status
+/// - `initial_points`: the initial points
+///
+/// ## Returns
+///
+/// Returns the solution with the lowest cost and its solver status. Starts
+/// which fail are skipped; if all of them fail, the error of the first one
+/// is returned.
+///
+/// ## Panics
+///
+/// The function panics if `initial_points` is empty
+///
+/// ## Memory allocation
+///
+/// This function allocates two vectors of the same dimension as the initial
+/// points
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::{constraints::Ball2, core::*, panoc::*, FunctionCallResult};
+///
+/// // f(u) = (u^2 - 1)^2 + u/2 has two local minima, near -1 and near 1
+/// let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+///     *c = (u[0] * u[0] - 1.0).powi(2) + 0.5 * u[0];
+///     Ok(())
+/// };
+/// let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+///     grad[0] = 4.0 * u[0] * (u[0] * u[0] - 1.0) + 0.5;
+///     Ok(())
+/// };
+/// let bounds = Ball2::new(None, 2.0);
+/// let mut cache = PANOCCache::new(1, 1e-8, 5);
+///
+/// let (solution, status) = multi_start(
+///     |u: &mut [f64]| {
+///         let problem = Problem::new(&bounds, gradient, cost);
+///         PANOCOptimizer::new(problem, &mut cache).solve(u)
+///     },
+///     &[vec![1.5], vec![-1.5]],
+/// )
+/// .unwrap();
+/// assert!(status.has_converged());
+/// assert!(solution[0] < 0.0);
+/// ```
+///
+pub fn multi_start<T, F>(
+    mut solve_from: F,
+    initial_points: &[Vec<T>],
+) -> Result<(Vec<T>, SolverStatus<T>), SolverError>
+where
+    T: OptFloat,
+    F: FnMut(&mut [T]) -> Result<SolverStatus<T>, SolverError>,
+{
+    assert!(!initial_points.is_empty(), "no initial points");
+    let mut u = initial_points[0].clone();
+    let mut best: Option<(Vec<T>, SolverStatus<T>)> = None;
+    let mut first_error = None;
+
+    for initial_point in initial_points {
+        u.copy_from_slice(initial_point);
+        match solve_from(&mut u) {
+            Ok(status) => match &mut best {
+                Some((best_u, best_status)) => {
+                    if status.cost_value() < best_status.cost_value() {
+                        best_u.copy_from_slice(&u);
+                        *best_status = status;
+                    }
+                }
+                None => best = Some((u.clone(), status)),
+            },
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    best.ok_or_else(|| first_error.unwrap())
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::core::panoc::{PANOCCache, PANOCOptimizer};
+    use crate::core::{Optimizer, Problem};
+    use crate::{constraints, mocks, FunctionCallResult};
+
+    #[test]
+    fn t_multi_start_rosenbrock() {
+        // the Rosenbrock function restricted to the circle of radius 1 centered
+        // at (0, 1) has a local minimum near (-0.58, 0.19) and the global
+        // minimum near (0.79, 0.39)
+        let (a, b) = (1.0, 100.0);
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a, b, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a, b, u);
+            Ok(())
+        };
+        let center = [0.0, 1.0];
+        let bounds = constraints::Sphere2::new(Some(&center), 1.0);
+        let mut cache = PANOCCache::new(2, 1e-8, 8);
+        let mut costs = Vec::new();
+
+        let (solution, status) = multi_start(
+            |u: &mut [f64]| {
+                let problem = Problem::new(&bounds, cost_gradient, cost_function);
+                let status = PANOCOptimizer::new(problem, &mut cache)
+                    .with_max_iter(500)
+                    .solve(u);
+                if let Ok(status) = &status {
+                    costs.push(status.cost_value());
+                }
+                status
+            },
+            &[vec![-1.0, 1.0], vec![-0.5, 0.0], vec![1.0, 1.0]],
+        )
+        .unwrap();
+
+        let lowest_cost = costs.iter().cloned().fold(f64::INFINITY, f64::min);
+        assert_eq!(3, costs.len());
+        assert_eq!(lowest_cost, status.cost_value());
+        assert!(costs.iter().any(|&cost| cost > lowest_cost + 1.0));
+        assert!(solution[0] > 0.0);
+    }
+
+    #[test]
+    fn t_multi_start_all_fail() {
+        let mut calls = 0;
+        let result = multi_start(
+            |_u: &mut [f64]| {
+                calls += 1;
+                if calls == 1 {
+                    Err(SolverError::Cost)
+                } else {
+                    Err(SolverError::NotFiniteComputation)
+                }
+            },
+            &[vec![0.0], vec![1.0]],
+        );
+        assert_eq!(Err(SolverError::Cost), result);
+        assert_eq!(2, calls);
+    }
+}
@@ -3,16 +3,26 @@ use num::Float;
 
 /// Trait that combines Float functionality with optimization-specific constants
 /// This allows different float types to have different optimization parameters
+///
+/// Note that the fused multiply-add operation of `Float` (`mul_add`) is used
+/// in the inner loops of the solvers (see `matrix_operations`)
+///
+/// The provided methods `blas_*` and `parallel_*` are used by
+/// `matrix_operations` to compute reductions with BLAS or with several
+/// threads; by default, they return `None` (or `false`), so the computation is
+/// done serially in Rust. They are overridden for `f32` and `f64` if the
+/// features `blas` and `parallel` are activated, so the bounds of this trait
+/// do not depend on the activated features.
 pub trait OptFloat:
     Float
-    + std::iter::Sum<Self>
+    + core::iter::Sum<Self>
     + num::FromPrimitive
     + num::ToPrimitive
-    + std::fmt::Debug
-    + std::ops::AddAssign
-    + std::ops::SubAssign
-    + std::ops::MulAssign
-    + std::ops::DivAssign
+    + core::fmt::Debug
+    + core::ops::AddAssign
+    + core::ops::SubAssign
+    + core::ops::MulAssign
+    + core::ops::DivAssign
 {
     /// Minimum estimated Lipschitz constant (initial estimate)
     fn min_l_estimate() -> Self;
@@ -31,6 +41,49 @@ pub trait OptFloat:
 
     /// Maximum possible Lipschitz constant
     fn max_lipschitz_constant() -> Self;
+
+    /// Inner product of `a` and `b` computed by BLAS (`xDOT`), or `None` if
+    /// BLAS is not used (see `matrix_operations::inner_product`)
+    fn blas_dot(_a: &[Self], _b: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// 2-norm of `a` computed by BLAS (`xNRM2`), or `None` if BLAS is not used
+    /// (see `matrix_operations::norm2`)
+    fn blas_nrm2(_a: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// 1-norm of `a` computed by BLAS (`xASUM`), or `None` if BLAS is not used
+    /// (see `matrix_operations::norm1`)
+    fn blas_asum(_a: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// Inner product of `a` and `b` computed with several threads, or `None`
+    /// if it is to be computed serially (see `matrix_operations::inner_product`)
+    fn parallel_inner_product(_a: &[Self], _b: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// Squared 2-norm of `a` computed with several threads, or `None` if it
+    /// is to be computed serially (see `matrix_operations::norm2_squared`)
+    fn parallel_norm2_squared(_a: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// Sum of the elements of `a` computed with several threads, or `None` if
+    /// it is to be computed serially (see `matrix_operations::sum`)
+    fn parallel_sum(_a: &[Self]) -> Option<Self> {
+        None
+    }
+
+    /// Computes `y <- y + a x` with several threads and returns `true`, or
+    /// returns `false` (leaving `y` unchanged) if it is to be computed serially
+    /// (see `matrix_operations::axpy`)
+    fn parallel_axpy(_y: &mut [Self], _a: Self, _x: &[Self]) -> bool {
+        false
+    }
 }
 
 /// Default implementation for f64 with original constants
@@ -58,6 +111,41 @@ impl OptFloat for f64 {
     fn max_lipschitz_constant() -> Self {
         1e9
     }
+
+    #[cfg(feature = "blas")]
+    fn blas_dot(a: &[Self], b: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::ddot(a, b)
+    }
+
+    #[cfg(feature = "blas")]
+    fn blas_nrm2(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::dnrm2(a)
+    }
+
+    #[cfg(feature = "blas")]
+    fn blas_asum(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::dasum(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_inner_product(a: &[Self], b: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::inner_product(a, b)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_norm2_squared(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::norm2_squared(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_sum(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::sum(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_axpy(y: &mut [Self], a: Self, x: &[Self]) -> bool {
+        crate::matrix_operations::parallel::axpy(y, a, x)
+    }
 }
 
 /// Default implementation for f32 with scaled constants
@@ -85,6 +173,74 @@ impl OptFloat for f32 {
     fn max_lipschitz_constant() -> Self {
         1e9
     }
+
+    #[cfg(feature = "blas")]
+    fn blas_dot(a: &[Self], b: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::sdot(a, b)
+    }
+
+    #[cfg(feature = "blas")]
+    fn blas_nrm2(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::snrm2(a)
+    }
+
+    #[cfg(feature = "blas")]
+    fn blas_asum(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::blas::sasum(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_inner_product(a: &[Self], b: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::inner_product(a, b)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_norm2_squared(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::norm2_squared(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_sum(a: &[Self]) -> Option<Self> {
+        crate::matrix_operations::parallel::sum(a)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parallel_axpy(y: &mut [Self], a: Self, x: &[Self]) -> bool {
+        crate::matrix_operations::parallel::axpy(y, a, x)
+    }
+}
+
+/// Implementation for half-precision floats (requires the feature `f16`)
+///
+/// The machine epsilon of `f16` is about `9.8e-4` and its largest finite value
+/// is `65504`, so the constants are scaled accordingly; in particular, the
+/// largest Lipschitz constant and the smallest initial estimate are chosen so
+/// that `gamma` and `1/gamma` remain finite
+#[cfg(feature = "f16")]
+impl OptFloat for half::f16 {
+    fn min_l_estimate() -> Self {
+        half::f16::from_f32(1e-2)
+    }
+
+    fn gamma_l_coeff() -> Self {
+        half::f16::from_f32(0.95)
+    }
+
+    fn delta_lipschitz() -> Self {
+        half::f16::from_f32(1e-2)
+    }
+
+    fn epsilon_lipschitz() -> Self {
+        half::f16::from_f32(6e-2)
+    }
+
+    fn lipschitz_update_epsilon() -> Self {
+        half::f16::from_f32(4e-3)
+    }
+
+    fn max_lipschitz_constant() -> Self {
+        half::f16::from_f32(1e4)
+    }
 }
 
 #[cfg(test)]
@@ -110,4 +266,15 @@ mod tests {
         assert_eq!(f32::lipschitz_update_epsilon(), 2.62e-4);
         assert_eq!(f32::max_lipschitz_constant(), 1e9);
     }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn test_f16_constants() {
+        use half::f16;
+        assert!(f16::min_l_estimate() > f16::from_f32(0.0));
+        assert!((f16::gamma_l_coeff() / f16::min_l_estimate()).is_finite());
+        assert!(f16::max_lipschitz_constant().is_finite());
+        assert!(f16::lipschitz_update_epsilon() > f16::epsilon());
+        assert!(f16::epsilon_lipschitz() > f16::epsilon());
+    }
 }
@@ -0,0 +1,78 @@
+use core::mem::size_of;
+
+use crate::core::OptFloat;
+
+/// The L-BFGS buffer of PANOC
+///
+/// This is a thin wrapper around `lbfgs::Lbfgs`, which additionally keeps its
+/// dimensions, so that the memory it allocates can be queried
+#[derive(Debug)]
+pub(crate) struct LbfgsBuffer<T>
+where
+    T: OptFloat,
+{
+    lbfgs: lbfgs::Lbfgs<T>,
+    problem_size: usize,
+    memory_size: usize,
+}
+
+impl<T> LbfgsBuffer<T>
+where
+    T: OptFloat,
+{
+    /// Constructs an L-BFGS buffer with memory `memory_size` for problems of
+    /// size `problem_size` and the given CBFGS parameters
+    pub(crate) fn new(
+        problem_size: usize,
+        memory_size: usize,
+        cbfgs_alpha: T,
+        cbfgs_epsilon: T,
+        sy_epsilon: T,
+    ) -> Self {
+        LbfgsBuffer {
+            lbfgs: lbfgs::Lbfgs::new(problem_size, memory_size)
+                .with_cbfgs_alpha(cbfgs_alpha)
+                .with_cbfgs_epsilon(cbfgs_epsilon)
+                .with_sy_epsilon(sy_epsilon),
+            problem_size,
+            memory_size,
+        }
+    }
+
+    /// Sets the CBFGS parameters of the buffer
+    pub(crate) fn with_cbfgs_parameters(mut self, alpha: T, epsilon: T, sy_epsilon: T) -> Self {
+        self.lbfgs = self
+            .lbfgs
+            .with_cbfgs_alpha(alpha)
+            .with_cbfgs_epsilon(epsilon)
+            .with_sy_epsilon(sy_epsilon);
+        self
+    }
+
+    /// Number of bytes allocated by the buffer
+    ///
+    /// The buffer stores `memory_size` pairs of vectors $s$ and $y$, the
+    /// scalars $\rho$ and $\alpha$ of every pair, and the state and gradient of
+    /// the previous update, that is,
+    /// `2*memory_size*problem_size + 2*memory_size + 2*problem_size` floats
+    pub(crate) fn memory_footprint(&self) -> usize {
+        let floats =
+            2 * self.memory_size * self.problem_size + 2 * self.memory_size + 2 * self.problem_size;
+        floats * size_of::<T>()
+    }
+
+    /// Empties the buffer
+    pub(crate) fn reset(&mut self) {
+        self.lbfgs.reset();
+    }
+
+    /// Updates the buffer with gradient `g` and state `state`
+    pub(crate) fn update_hessian(&mut self, g: &[T], state: &[T]) -> lbfgs::UpdateStatus {
+        self.lbfgs.update_hessian(g, state)
+    }
+
+    /// Applies the L-BFGS approximation of the inverse Hessian to `q`
+    pub(crate) fn apply_hessian(&mut self, q: &mut [T]) {
+        self.lbfgs.apply_hessian(q);
+    }
+}
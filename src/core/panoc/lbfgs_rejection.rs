@@ -0,0 +1,142 @@
+//! Log of rejected L-BFGS updates
+//!
+use alloc::{vec, vec::Vec};
+
+use super::panoc_cache::vec_footprint;
+use crate::core::OptFloat;
+
+/// Test which caused the rejection of an L-BFGS update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LbfgsRejectionReason {
+    /// The curvature condition failed, that is, $\langle s, y \rangle$ did not
+    /// exceed `sy_epsilon` (or $s$ was zero)
+    Curvature,
+    /// The CBFGS condition of Li and Fukushima failed, that is,
+    /// $\langle s, y \rangle / \Vert{}s{}\Vert^2$ did not exceed
+    /// $\epsilon\Vert{}g{}\Vert^\alpha$
+    Cbfgs,
+}
+
+/// A rejected L-BFGS update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LbfgsRejection {
+    iteration: usize,
+    reason: LbfgsRejectionReason,
+}
+
+impl LbfgsRejection {
+    /// Iteration of PANOC at which the update was rejected
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Test which caused the rejection
+    pub fn reason(&self) -> LbfgsRejectionReason {
+        self.reason
+    }
+}
+
+/// Detailed log of the rejected L-BFGS updates of a solve
+///
+/// The log is recorded only if it has been activated with
+/// [PANOCCache::enable_lbfgs_rejection_log](struct.PANOCCache.html#method.enable_lbfgs_rejection_log)
+///
+#[derive(Debug)]
+pub struct LbfgsRejectionLog<T>
+where
+    T: OptFloat,
+{
+    rejections: Vec<LbfgsRejection>,
+    /// State of the last accepted update (as in the L-BFGS buffer)
+    state_previous: Vec<T>,
+    /// Gradient (here, gamma times the FPR) of the last accepted update
+    g_previous: Vec<T>,
+    /// Whether `state_previous` and `g_previous` are set
+    has_previous: bool,
+}
+
+impl<T> LbfgsRejectionLog<T>
+where
+    T: OptFloat,
+{
+    pub(crate) fn new(n: usize, capacity: usize) -> Self {
+        LbfgsRejectionLog {
+            rejections: Vec::with_capacity(capacity),
+            state_previous: vec![T::zero(); n],
+            g_previous: vec![T::zero(); n],
+            has_previous: false,
+        }
+    }
+
+    /// Clears the log, but keeps the state of the last accepted update,
+    /// which remains in the L-BFGS buffer
+    pub(crate) fn clear(&mut self) {
+        self.rejections.clear();
+    }
+
+    /// Number of bytes allocated for the log
+    pub(crate) fn memory_footprint(&self) -> usize {
+        vec_footprint(&self.rejections)
+            + vec_footprint(&self.state_previous)
+            + vec_footprint(&self.g_previous)
+    }
+
+    /// Forgets the state of the last accepted update; this must be called
+    /// whenever the L-BFGS buffer is reset
+    pub(crate) fn forget_previous(&mut self) {
+        self.has_previous = false;
+    }
+
+    /// Records the outcome of an update of the L-BFGS buffer with gradient `g`
+    /// and state `state`; if the update was rejected, the test that failed is
+    /// determined by repeating the curvature test of the L-BFGS buffer
+    pub(crate) fn record(
+        &mut self,
+        accepted: bool,
+        iteration: usize,
+        g: &[T],
+        state: &[T],
+        sy_epsilon: T,
+    ) {
+        if accepted || !self.has_previous {
+            self.state_previous.copy_from_slice(state);
+            self.g_previous.copy_from_slice(g);
+            self.has_previous = true;
+            return;
+        }
+        let (ys, norm_s_squared) = self
+            .state_previous
+            .iter()
+            .zip(state.iter())
+            .zip(self.g_previous.iter().zip(g.iter()))
+            .fold(
+                (T::zero(), T::zero()),
+                |(ys, ss), ((&x_prev, &x), (&g_prev, &g_i))| {
+                    let s_i = x - x_prev;
+                    (ys + s_i * (g_i - g_prev), ss + s_i * s_i)
+                },
+            );
+        // the CBFGS condition is checked only if the curvature condition holds
+        let curvature_fails = norm_s_squared <= T::min_positive_value()
+            || (sy_epsilon > T::zero() && ys <= sy_epsilon);
+        let reason = if curvature_fails {
+            LbfgsRejectionReason::Curvature
+        } else {
+            LbfgsRejectionReason::Cbfgs
+        };
+        self.rejections.push(LbfgsRejection { iteration, reason });
+    }
+
+    /// Rejected updates, in chronological order
+    pub fn rejections(&self) -> &[LbfgsRejection] {
+        &self.rejections
+    }
+
+    /// Number of updates that were rejected for the given reason
+    pub fn count(&self, reason: LbfgsRejectionReason) -> usize {
+        self.rejections
+            .iter()
+            .filter(|rejection| rejection.reason == reason)
+            .count()
+    }
+}
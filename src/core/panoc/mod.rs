@@ -2,15 +2,27 @@
 
 #![deny(missing_docs)]
 
+mod lbfgs_buffer;
+mod lbfgs_rejection;
 mod panoc_cache;
 mod panoc_engine;
+mod panoc_iterator;
 mod panoc_optimizer;
+mod panoc_progress;
+mod panoc_sequence;
 
-pub use panoc_cache::PANOCCache;
+pub use lbfgs_rejection::{LbfgsRejection, LbfgsRejectionLog, LbfgsRejectionReason};
+pub use panoc_cache::{BestIterateCriterion, PANOCCache, PANOCHistory};
+pub use panoc_iterator::PanocIterator;
 pub use panoc_optimizer::PANOCOptimizer;
+pub use panoc_progress::PanocProgress;
+pub use panoc_sequence::{solve_sequence, ResetPolicy};
 
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
 mod tests_f32;
+
+#[cfg(all(test, feature = "f16"))]
+mod tests_f16;
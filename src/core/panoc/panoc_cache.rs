@@ -1,4 +1,10 @@
+use alloc::{vec, vec::Vec};
+use core::mem::size_of;
+
+use crate::core::panoc::lbfgs_buffer::LbfgsBuffer;
+use crate::core::panoc::LbfgsRejectionLog;
 use crate::core::OptFloat;
+use crate::SolverError;
 
 const DEFAULT_SY_EPSILON: f64 = 1e-10;
 const DEFAULT_CBFGS_EPSILON: f64 = 1e-8;
@@ -18,7 +24,7 @@ pub struct PANOCCache<T>
 where
     T: OptFloat,
 {
-    pub(crate) lbfgs: lbfgs::Lbfgs<T>,
+    pub(crate) lbfgs: LbfgsBuffer<T>,
     pub(crate) gradient_u: Vec<T>,
     /// Stores the gradient of the cost at the previous iteration. This is
     /// an optional field because it is used (and needs to be allocated)
@@ -33,6 +39,10 @@ where
     pub(crate) gamma_fpr: Vec<T>,
     pub(crate) gamma: T,
     pub(crate) tolerance: T,
+    /// Tolerance on the norm of gamma*FPR in effect during the current solve
+    /// with an adaptive tolerance (see `PANOCOptimizer::with_adaptive_tolerance`),
+    /// which replaces `tolerance`; this is `None` without an adaptive tolerance
+    pub(crate) adaptive_tolerance: Option<T>,
     pub(crate) norm_gamma_fpr: T,
     pub(crate) tau: T,
     pub(crate) lipschitz_constant: T,
@@ -40,6 +50,296 @@ where
     pub(crate) cost_value: T,
     pub(crate) iteration: usize,
     pub(crate) akkt_tolerance: Option<T>,
+    /// Relative tolerance on the norm of gamma*FPR; this is `None` unless
+    /// activated with `set_relative_tolerance`
+    pub(crate) relative_tolerance: Option<T>,
+    /// Norm of gamma*FPR at the initial point of the current solve
+    pub(crate) norm_gamma_fpr_initial: T,
+    /// History of `(cost_value, norm_gamma_fpr, gamma)`, one entry per
+    /// iteration; this is `None` unless activated with `enable_history`
+    pub(crate) history: Option<PANOCHistory<T>>,
+    /// Maximum number of L-BFGS-accelerated steps per solve; `None` means
+    /// that there is no limit
+    pub(crate) max_lbfgs_applications: Option<usize>,
+    /// Number of L-BFGS-accelerated steps performed in the current solve
+    pub(crate) num_lbfgs_applications: usize,
+    /// Whether the cache has been initialized by a solver since it was
+    /// constructed or last reset
+    pub(crate) initialized: bool,
+    /// CBFGS parameter alpha used by the L-BFGS buffer
+    pub(crate) cbfgs_alpha: T,
+    /// CBFGS parameter epsilon used by the L-BFGS buffer
+    pub(crate) cbfgs_epsilon: T,
+    /// Curvature threshold, sy_epsilon, used by the L-BFGS buffer
+    pub(crate) sy_epsilon: T,
+    /// Number of accepted L-BFGS updates in the current solve
+    pub(crate) num_lbfgs_acceptances: usize,
+    /// Number of rejected L-BFGS updates in the current solve
+    pub(crate) num_lbfgs_rejections: usize,
+    /// Total number of halvings of tau in the line searches of the current
+    /// solve
+    pub(crate) num_linesearch_steps: usize,
+    /// Number of evaluations of the cost function in the current solve
+    pub(crate) num_cost_evals: usize,
+    /// Number of evaluations of the gradient of the cost in the current solve
+    pub(crate) num_grad_evals: usize,
+    /// Whether the solve was terminated because the line search reached its
+    /// maximum number of iterations (see
+    /// `PANOCOptimizer::stop_on_linesearch_failure`)
+    pub(crate) linesearch_failed: bool,
+    /// Detailed log of rejected L-BFGS updates; this is `None` unless
+    /// activated with `enable_lbfgs_rejection_log`
+    pub(crate) lbfgs_rejection_log: Option<LbfgsRejectionLog<T>>,
+    /// Best iterate of the current solve; this is `None` unless activated
+    /// with `PANOCOptimizer::keep_best`
+    pub(crate) best_iterate: Option<BestIterate<T>>,
+    /// Diagonal preconditioner; this is `None` unless activated with
+    /// `with_preconditioner`
+    pub(crate) preconditioner: Option<Preconditioner<T>>,
+    /// Recent cost values, which are used to detect stalling; this is `None`
+    /// unless activated with `PANOCOptimizer::with_stall_detection`
+    pub(crate) stall_detection: Option<StallDetection<T>>,
+}
+
+/// Diagonal preconditioner, $D = \mathrm{diag}(d)$, with work vectors
+#[derive(Debug)]
+pub(crate) struct Preconditioner<T>
+where
+    T: OptFloat,
+{
+    /// Diagonal of $D$ (positive)
+    pub(crate) diag: Vec<T>,
+    /// Work vector for scaled iterates, $D u$
+    pub(crate) work_state: Vec<T>,
+    /// Work vector for scaled residuals, $D \gamma R_\gamma(u)$
+    pub(crate) work_fpr: Vec<T>,
+}
+
+impl<T> Preconditioner<T>
+where
+    T: OptFloat,
+{
+    /// Squared norm of `a - b` in the metric of $D^2$, that is,
+    /// $\Vert D (a - b) \Vert^2$
+    pub(crate) fn norm2_squared_diff(&self, a: &[T], b: &[T]) -> T {
+        a.iter()
+            .zip(b.iter())
+            .zip(self.diag.iter())
+            .fold(T::zero(), |sum, ((&ai, &bi), &di)| {
+                sum + (di * (ai - bi)).powi(2)
+            })
+    }
+
+    /// Squared norm of `a` in the metric of $D^2$, that is, $\Vert D a \Vert^2$
+    pub(crate) fn norm2_squared(&self, a: &[T]) -> T {
+        a.iter()
+            .zip(self.diag.iter())
+            .fold(T::zero(), |sum, (&ai, &di)| sum + (di * ai).powi(2))
+    }
+
+    /// Squared norm of `g` in the metric of $D^{-2}$, that is,
+    /// $\Vert D^{-1} g \Vert^2$ (the metric of gradients)
+    pub(crate) fn dual_norm2_squared(&self, g: &[T]) -> T {
+        g.iter()
+            .zip(self.diag.iter())
+            .fold(T::zero(), |sum, (&gi, &di)| sum + (gi / di).powi(2))
+    }
+}
+
+/// Criterion used to select the best iterate of PANOC
+///
+/// See [PANOCOptimizer::keep_best](struct.PANOCOptimizer.html#method.keep_best)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BestIterateCriterion {
+    /// The best iterate has the lowest norm of gamma*FPR
+    NormGammaFpr,
+    /// The best iterate has the lowest cost
+    CostValue,
+}
+
+/// Ring buffer with the most recent values of the forward-backward envelope,
+/// which is used to detect stalling
+#[derive(Debug)]
+pub(crate) struct StallDetection<T>
+where
+    T: OptFloat,
+{
+    /// Threshold on the relative decrease of the FBE over the window
+    rel_threshold: T,
+    /// FBE values of the last iterations (at most as many as the window)
+    values: Vec<T>,
+    /// Number of FBE values stored in `values`
+    count: usize,
+    /// Position in `values` where the next FBE value is stored
+    next: usize,
+    /// Whether stalling was detected during the current solve
+    pub(crate) stalled: bool,
+}
+
+impl<T> StallDetection<T>
+where
+    T: OptFloat,
+{
+    fn new(window: usize, rel_threshold: T) -> Self {
+        StallDetection {
+            rel_threshold,
+            values: vec![T::zero(); window],
+            count: 0,
+            next: 0,
+            stalled: false,
+        }
+    }
+
+    /// Clears the stored FBE values and the stalling flag
+    fn clear(&mut self) {
+        self.count = 0;
+        self.next = 0;
+        self.stalled = false;
+    }
+
+    /// Stores `fbe`, overwriting the oldest FBE value if the buffer is full,
+    /// and returns `true` if the FBE has decreased by less than
+    /// `rel_threshold` (relative to the oldest FBE value) over the window
+    fn record(&mut self, fbe: T) -> bool {
+        let window = self.values.len();
+        if self.count == window {
+            let oldest = self.values[self.next];
+            self.stalled = oldest - fbe <= self.rel_threshold * oldest.abs();
+        }
+        self.values[self.next] = fbe;
+        self.next = (self.next + 1) % window;
+        self.count = (self.count + 1).min(window);
+        self.stalled
+    }
+}
+
+/// Best iterate of a solve, together with its norm of gamma*FPR and cost
+#[derive(Debug)]
+pub(crate) struct BestIterate<T>
+where
+    T: OptFloat,
+{
+    pub(crate) criterion: BestIterateCriterion,
+    pub(crate) u: Vec<T>,
+    pub(crate) norm_gamma_fpr: T,
+    pub(crate) cost_value: T,
+    pub(crate) recorded: bool,
+}
+
+impl<T> BestIterate<T>
+where
+    T: OptFloat,
+{
+    fn new(problem_size: usize, criterion: BestIterateCriterion) -> Self {
+        BestIterate {
+            criterion,
+            u: vec![T::zero(); problem_size],
+            norm_gamma_fpr: T::infinity(),
+            cost_value: T::infinity(),
+            recorded: false,
+        }
+    }
+
+    /// Records `u` if it is better than the best iterate so far
+    fn record(&mut self, u: &[T], norm_gamma_fpr: T, cost_value: T) {
+        let is_better = !self.recorded
+            || match self.criterion {
+                BestIterateCriterion::NormGammaFpr => norm_gamma_fpr < self.norm_gamma_fpr,
+                BestIterateCriterion::CostValue => cost_value < self.cost_value,
+            };
+        if is_better {
+            self.u.copy_from_slice(u);
+            self.norm_gamma_fpr = norm_gamma_fpr;
+            self.cost_value = cost_value;
+            self.recorded = true;
+        }
+    }
+}
+
+/// History of PANOC iterations
+///
+/// The history is recorded only if it has been activated with
+/// [PANOCCache::enable_history](struct.PANOCCache.html#method.enable_history)
+///
+#[derive(Debug)]
+pub struct PANOCHistory<T>
+where
+    T: OptFloat,
+{
+    cost_values: Vec<T>,
+    norm_gamma_fprs: Vec<T>,
+    gammas: Vec<T>,
+    akkt_residuals: Vec<T>,
+}
+
+impl<T> PANOCHistory<T>
+where
+    T: OptFloat,
+{
+    fn with_capacity(capacity: usize) -> Self {
+        PANOCHistory {
+            cost_values: Vec::with_capacity(capacity),
+            norm_gamma_fprs: Vec::with_capacity(capacity),
+            gammas: Vec::with_capacity(capacity),
+            akkt_residuals: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, cost_value: T, norm_gamma_fpr: T, gamma: T, akkt_residual: Option<T>) {
+        self.cost_values.push(cost_value);
+        self.norm_gamma_fprs.push(norm_gamma_fpr);
+        self.gammas.push(gamma);
+        if let Some(akkt_residual) = akkt_residual {
+            self.akkt_residuals.push(akkt_residual);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.cost_values.clear();
+        self.norm_gamma_fprs.clear();
+        self.gammas.clear();
+        self.akkt_residuals.clear();
+    }
+
+    /// Number of bytes allocated for the history vectors
+    fn memory_footprint(&self) -> usize {
+        vec_footprint(&self.cost_values)
+            + vec_footprint(&self.norm_gamma_fprs)
+            + vec_footprint(&self.gammas)
+            + vec_footprint(&self.akkt_residuals)
+    }
+
+    /// Cost values, one per iteration
+    pub fn cost_values(&self) -> &[T] {
+        &self.cost_values
+    }
+
+    /// Norms of gamma times the fixed-point residual, one per iteration
+    pub fn norm_gamma_fprs(&self) -> &[T] {
+        &self.norm_gamma_fprs
+    }
+
+    /// Values of the step size, gamma, one per iteration
+    pub fn gammas(&self) -> &[T] {
+        &self.gammas
+    }
+
+    /// AKKT residuals, one per iteration, if the AKKT termination criterion
+    /// is activated (see `PANOCCache::set_akkt_tolerance`), otherwise empty
+    pub fn akkt_residuals(&self) -> &[T] {
+        &self.akkt_residuals
+    }
+
+    /// Number of recorded iterations
+    pub fn len(&self) -> usize {
+        self.cost_values.len()
+    }
+
+    /// Whether no iterations have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.cost_values.is_empty()
+    }
 }
 
 impl<T> PANOCCache<T>
@@ -81,10 +381,13 @@ where
             gamma: T::zero(),
             tolerance,
             norm_gamma_fpr: T::infinity(),
-            lbfgs: lbfgs::Lbfgs::<T>::new(problem_size, lbfgs_memory_size)
-                .with_cbfgs_alpha(T::from(DEFAULT_CBFGS_ALPHA).unwrap())
-                .with_cbfgs_epsilon(T::from(DEFAULT_CBFGS_EPSILON).unwrap())
-                .with_sy_epsilon(T::from(DEFAULT_SY_EPSILON).unwrap()),
+            lbfgs: LbfgsBuffer::new(
+                problem_size,
+                lbfgs_memory_size,
+                T::from(DEFAULT_CBFGS_ALPHA).unwrap(),
+                T::from(DEFAULT_CBFGS_EPSILON).unwrap(),
+                T::from(DEFAULT_SY_EPSILON).unwrap(),
+            ),
             lhs_ls: T::zero(),
             rhs_ls: T::zero(),
             tau: T::one(),
@@ -93,9 +396,274 @@ where
             cost_value: T::zero(),
             iteration: 0,
             akkt_tolerance: None,
+            adaptive_tolerance: None,
+            relative_tolerance: None,
+            norm_gamma_fpr_initial: T::zero(),
+            history: None,
+            max_lbfgs_applications: None,
+            num_lbfgs_applications: 0,
+            initialized: false,
+            cbfgs_alpha: T::from(DEFAULT_CBFGS_ALPHA).unwrap(),
+            cbfgs_epsilon: T::from(DEFAULT_CBFGS_EPSILON).unwrap(),
+            sy_epsilon: T::from(DEFAULT_SY_EPSILON).unwrap(),
+            num_lbfgs_acceptances: 0,
+            num_lbfgs_rejections: 0,
+            num_linesearch_steps: 0,
+            num_cost_evals: 0,
+            num_grad_evals: 0,
+            linesearch_failed: false,
+            lbfgs_rejection_log: None,
+            best_iterate: None,
+            preconditioner: None,
+            stall_detection: None,
+        }
+    }
+
+    /// Construct a new instance of `PANOCCache` like `new`, but without
+    /// panicking on invalid arguments
+    ///
+    /// ## Returns
+    ///
+    /// The new cache, or `SolverError::InvalidArgument` if `problem_size` or
+    /// `lbfgs_memory_size` is zero, or if `tolerance` is not positive
+    ///
+    /// ## Memory allocation
+    ///
+    /// Same as `new`
+    ///
+    pub fn try_new(
+        problem_size: usize,
+        tolerance: T,
+        lbfgs_memory_size: usize,
+    ) -> Result<PANOCCache<T>, SolverError> {
+        if problem_size > 0 && lbfgs_memory_size > 0 && tolerance > T::zero() {
+            Ok(PANOCCache::new(problem_size, tolerance, lbfgs_memory_size))
+        } else {
+            Err(SolverError::InvalidArgument)
+        }
+    }
+
+    /// Activates the recording of the history of `(cost_value, norm_gamma_fpr, gamma)`
+    ///
+    /// Once activated, the values are recorded at every iteration of PANOC
+    /// and can be accessed with `history`. The history is cleared whenever
+    /// the cache is reset (that is, at the beginning of every solve).
+    ///
+    /// ## Arguments
+    ///
+    /// - `capacity`: initial capacity of the history vectors; this should be
+    ///   the maximum number of iterations, otherwise the history vectors may
+    ///   need to reallocate memory while the solver runs
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `4*capacity` floats. If the history
+    /// is not activated, no memory is allocated.
+    ///
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(PANOCHistory::with_capacity(capacity));
+    }
+
+    /// History of the iterations, if activated with `enable_history`,
+    /// otherwise `None`
+    pub fn history(&self) -> Option<&PANOCHistory<T>> {
+        self.history.as_ref()
+    }
+
+    /// Activates the detailed log of rejected L-BFGS updates
+    ///
+    /// Once activated, for every rejected update of the L-BFGS buffer, the
+    /// iteration and the test which failed (the curvature test on
+    /// $\langle s, y \rangle$, or the CBFGS test) are recorded and can be
+    /// accessed with `lbfgs_rejection_log`. The log is cleared whenever the
+    /// cache is reset (that is, at the beginning of every solve).
+    ///
+    /// ## Arguments
+    ///
+    /// - `capacity`: initial capacity of the log; since at most one update is
+    ///   rejected per iteration, this should be the maximum number of
+    ///   iterations, otherwise the log may need to reallocate memory while the
+    ///   solver runs
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `2*n` floats, where `n` is the
+    /// problem size, and for `capacity` entries of the log
+    ///
+    pub fn enable_lbfgs_rejection_log(&mut self, capacity: usize) {
+        self.lbfgs_rejection_log = Some(LbfgsRejectionLog::new(self.gamma_fpr.len(), capacity));
+    }
+
+    /// Detailed log of rejected L-BFGS updates, if activated with
+    /// `enable_lbfgs_rejection_log`, otherwise `None`
+    pub fn lbfgs_rejection_log(&self) -> Option<&LbfgsRejectionLog<T>> {
+        self.lbfgs_rejection_log.as_ref()
+    }
+
+    /// Number of rejected L-BFGS updates in the last solve
+    pub fn lbfgs_rejections(&self) -> usize {
+        self.num_lbfgs_rejections
+    }
+
+    /// Number of accepted L-BFGS updates in the last solve
+    ///
+    /// If this is small compared to `lbfgs_rejections`, PANOC mostly
+    /// performs gradient steps, which suggests that the CBFGS parameters
+    /// (see `with_cbfgs_parameters`) are too strict
+    pub fn lbfgs_acceptances(&self) -> usize {
+        self.num_lbfgs_acceptances
+    }
+
+    /// Total number of line search steps (halvings of tau) in the last solve
+    ///
+    /// A large number of line search steps per iteration means that the
+    /// L-BFGS directions are often rejected by the line search; the number
+    /// of steps per iteration is bounded by
+    /// `PANOCOptimizer::with_max_linesearch_iters`
+    pub fn linesearch_steps(&self) -> usize {
+        self.num_linesearch_steps
+    }
+
+    /// Number of evaluations of the cost function in the last solve
+    pub fn cost_evaluations(&self) -> usize {
+        self.num_cost_evals
+    }
+
+    /// Number of evaluations of the gradient of the cost in the last solve
+    /// (including those of the initial estimation of the Lipschitz constant)
+    pub fn gradient_evaluations(&self) -> usize {
+        self.num_grad_evals
+    }
+
+    /// CBFGS parameter alpha used by the L-BFGS buffer
+    pub fn cbfgs_alpha(&self) -> T {
+        self.cbfgs_alpha
+    }
+
+    /// CBFGS parameter epsilon used by the L-BFGS buffer
+    pub fn cbfgs_epsilon(&self) -> T {
+        self.cbfgs_epsilon
+    }
+
+    /// Curvature threshold, sy_epsilon, used by the L-BFGS buffer; updates
+    /// with $\langle s, y \rangle \leq$ `sy_epsilon` are rejected
+    pub fn sy_epsilon(&self) -> T {
+        self.sy_epsilon
+    }
+
+    /// Counts (and logs, if activated) the outcome of an update of the
+    /// L-BFGS buffer at the given state
+    pub(crate) fn record_lbfgs_update(&mut self, status: lbfgs::UpdateStatus, state: &[T]) {
+        let accepted = matches!(status, lbfgs::UpdateStatus::UpdateOk);
+        if accepted {
+            self.num_lbfgs_acceptances += 1;
+        } else {
+            self.num_lbfgs_rejections += 1;
+        }
+        if let Some(log) = &mut self.lbfgs_rejection_log {
+            log.record(
+                accepted,
+                self.iteration,
+                &self.gamma_fpr,
+                state,
+                self.sy_epsilon,
+            );
+        }
+    }
+
+    /// Activates the tracking of the best iterate (see `PANOCOptimizer::keep_best`)
+    pub(crate) fn enable_best_iterate(&mut self, criterion: BestIterateCriterion) {
+        self.best_iterate = Some(BestIterate::new(self.gamma_fpr.len(), criterion));
+    }
+
+    /// Records the current half step, whose cost is `cost_u_half_step`, as
+    /// the best iterate, if it is better than the best iterate so far (if
+    /// activated); a half step with a non-finite cost is never recorded
+    pub(crate) fn record_best_iterate(&mut self, cost_u_half_step: T) {
+        if let Some(best) = &mut self.best_iterate {
+            if cost_u_half_step.is_finite() {
+                best.record(&self.u_half_step, self.norm_gamma_fpr, cost_u_half_step);
+            }
+        }
+    }
+
+    /// Activates the detection of stalling with a window of `window` values
+    /// of the FBE (see `PANOCOptimizer::with_stall_detection`)
+    pub(crate) fn enable_stall_detection(&mut self, window: usize, rel_threshold: T) {
+        self.stall_detection = Some(StallDetection::new(window, rel_threshold));
+    }
+
+    /// Records the value of the forward-backward envelope at the current
+    /// iterate, `fbe`, for the detection of stalling and returns `true` if the
+    /// FBE has stalled (if activated)
+    pub(crate) fn record_stall_detection(&mut self, fbe: T) -> bool {
+        self.stall_detection
+            .as_mut()
+            .is_some_and(|stall_detection| stall_detection.record(fbe))
+    }
+
+    /// Whether stalling was detected during the current solve
+    pub(crate) fn is_stalled(&self) -> bool {
+        self.stall_detection
+            .as_ref()
+            .is_some_and(|stall_detection| stall_detection.stalled)
+    }
+
+    /// Records the current cost value, norm of gamma*FPR and gamma in the
+    /// history (if activated)
+    pub(crate) fn record_history(&mut self) {
+        if self.history.is_none() {
+            return;
+        }
+        let akkt_residual = self.akkt_residual_value();
+        if let Some(history) = &mut self.history {
+            history.push(
+                self.cost_value,
+                self.norm_gamma_fpr,
+                self.gamma,
+                akkt_residual,
+            );
         }
     }
 
+    /// Sets a relative tolerance on the norm of gamma*FPR and activates the
+    /// corresponding termination criterion
+    ///
+    /// Once activated, the solver also terminates when
+    /// $\Vert \gamma R_\gamma(u) \Vert < \epsilon_{\rm rel} (1 + \Vert \gamma_0 R_{\gamma_0}(u_0) \Vert)$,
+    /// where $u_0$ is the initial guess and $\gamma_0$ the initial step size;
+    /// this is useful for badly scaled problems where the absolute criterion,
+    /// `norm_gamma_fpr < tolerance`, cannot be met. The absolute criterion
+    /// remains active.
+    ///
+    /// ## Arguments
+    ///
+    /// - `relative_tolerance`: relative tolerance, $\epsilon_{\rm rel}$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `relative_tolerance` is nonpositive
+    ///
+    pub fn set_relative_tolerance(&mut self, relative_tolerance: T) {
+        assert!(
+            relative_tolerance > T::zero(),
+            "relative_tolerance must be positive"
+        );
+        self.relative_tolerance = Some(relative_tolerance);
+    }
+
+    /// Tolerance on the norm of gamma*FPR in effect at the end of the last
+    /// solve with an adaptive tolerance
+    ///
+    /// ## Returns
+    ///
+    /// The adaptive tolerance (see `PANOCOptimizer::with_adaptive_tolerance`),
+    /// or `None` if the last solve did not use an adaptive tolerance
+    ///
+    pub fn adaptive_tolerance(&self) -> Option<T> {
+        self.adaptive_tolerance
+    }
+
     /// Sets the AKKT-specific tolerance and activates the corresponding
     /// termination criterion
     ///
@@ -116,6 +684,37 @@ where
         self.gradient_u_previous = Some(vec![T::zero(); self.gradient_step.len()]);
     }
 
+    /// Changes the memory of the L-BFGS buffer
+    ///
+    /// The L-BFGS buffer is rebuilt with the new memory and the current cBFGS
+    /// parameters (see `with_cbfgs_parameters`), whereas all other buffers of
+    /// the cache are kept. Note that this resets the L-BFGS history.
+    ///
+    /// ## Arguments
+    ///
+    /// - `lbfgs_memory_size`: new memory of the L-BFGS buffer
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `lbfgs_memory_size` is zero
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for
+    /// `2*lbfgs_memory_size*problem_size + 2*lbfgs_memory_size + 2*problem_size`
+    /// floats
+    ///
+    pub fn set_lbfgs_memory(&mut self, lbfgs_memory_size: usize) {
+        assert!(lbfgs_memory_size > 0, "lbfgs_memory_size must be positive");
+        self.lbfgs = LbfgsBuffer::new(
+            self.gamma_fpr.len(),
+            lbfgs_memory_size,
+            self.cbfgs_alpha,
+            self.cbfgs_epsilon,
+            self.sy_epsilon,
+        );
+    }
+
     /// Copies the value of the current cost gradient to `gradient_u_previous`,
     /// which stores the previous gradient vector
     ///
@@ -149,9 +748,38 @@ where
         r
     }
 
+    /// Current value of the AKKT residual, which is used in the AKKT
+    /// termination criterion,
+    ///
+    /// $$
+    /// \Vert \gamma R_\gamma(u) + \gamma(\nabla f(u^+) - \nabla f(u)) \Vert
+    /// $$
+    ///
+    /// ## Returns
+    ///
+    /// The AKKT residual, or `None` if the AKKT termination criterion has not
+    /// been activated (see `set_akkt_tolerance`)
+    ///
+    pub fn akkt_residual_value(&self) -> Option<T> {
+        self.gradient_u_previous
+            .as_ref()
+            .map(|_| self.akkt_residual())
+    }
+
     /// Returns true iff the norm of gamma*FPR is below the desired tolerance
+    /// (or the adaptive tolerance, if activated), or below the relative
+    /// tolerance (if activated)
     fn fpr_exit_condition(&self) -> bool {
-        self.norm_gamma_fpr < self.tolerance
+        let tolerance = self.adaptive_tolerance.unwrap_or(self.tolerance);
+        self.norm_gamma_fpr < tolerance || self.relative_exit_condition()
+    }
+
+    /// Returns true iff the relative tolerance is activated and the norm of
+    /// gamma*FPR is below it
+    pub(crate) fn relative_exit_condition(&self) -> bool {
+        self.relative_tolerance.is_some_and(|relative_tolerance| {
+            self.norm_gamma_fpr < relative_tolerance * (T::one() + self.norm_gamma_fpr_initial)
+        })
     }
 
     /// Checks whether the AKKT-specific termination condition is satisfied
@@ -164,28 +792,83 @@ where
         exit_condition
     }
 
+    /// Returns `true` iff the maximum number of L-BFGS-accelerated steps
+    /// (if any) has been reached
+    pub(crate) fn lbfgs_applications_exhausted(&self) -> bool {
+        self.max_lbfgs_applications
+            .is_some_and(|max| self.num_lbfgs_applications >= max)
+    }
+
     /// Returns `true` iff all termination conditions are satisfied
     ///
     /// It checks whether:
-    ///  - the FPR condition, `gamma*||fpr|| < epsilon` ,
+    ///  - the FPR condition, `gamma*||fpr|| < epsilon` (or, if activated,
+    ///    `gamma*||fpr|| < epsilon_rel * (1 + gamma_0*||fpr_0||)`),
     ///  - (if activated) the AKKT condition `||gamma*fpr + (df - df_prev)|| < eps_akkt`
     /// are satisfied.
     pub fn exit_condition(&self) -> bool {
         self.fpr_exit_condition() && self.akkt_exit_condition()
     }
 
+    /// Empties the L-BFGS buffer; the log of rejected updates (if activated)
+    /// forgets the last accepted update, which is no longer in the buffer
+    ///
+    /// Every reset of the L-BFGS buffer must go through this method
+    pub(crate) fn reset_lbfgs(&mut self) {
+        self.lbfgs.reset();
+        if let Some(log) = &mut self.lbfgs_rejection_log {
+            log.forget_previous();
+        }
+    }
+
     /// Resets the cache to its initial virgin state.
     ///
     /// In particular,
     ///
     /// - Resets/empties the LBFGS buffer
+    /// - Resets the scalar state of the cache (see `soft_reset`)
+    /// - Marks the cache as not initialized (see `cache_is_initialized`)
+    ///
+    /// This is appropriate when the next problem to be solved is unrelated
+    /// to the previous one. This method is called at the beginning of every
+    /// solve, unless the L-BFGS warm start is activated in the optimizer.
+    pub fn reset(&mut self) {
+        self.reset_lbfgs();
+        self.soft_reset();
+        self.initialized = false;
+    }
+
+    /// Whether the cache has been initialized by a solver
+    ///
+    /// This is `false` for a newly constructed cache and after a call to
+    /// `reset`, and `true` once a solver has been initialized with this
+    /// cache (that is, after the first call to `solve`). Note that `soft_reset`
+    /// does not change this flag, since it keeps the LBFGS buffer.
+    pub fn cache_is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// Resets the scalar state of the cache, but keeps the LBFGS buffer
+    ///
+    /// In particular,
+    ///
     /// - Sets tau = 1.0
-    /// - Sets the iteration count to 0
+    /// - Sets the iteration count, the counts of L-BFGS applications,
+    ///   acceptances and rejections, the count of line search steps and the
+    ///   counts of cost and gradient evaluations to 0
     /// - Sets the internal variables `lhs_ls`, `rhs_ls`,
     ///   `lipschitz_constant`, `sigma`, `cost_value`
     ///   and `gamma` to 0.0
-    pub fn reset(&mut self) {
-        self.lbfgs.reset();
+    /// - Clears the history, the log of rejected L-BFGS updates, the best
+    ///   iterate and the cost values of the stall detection (if activated),
+    ///   keeping their capacity
+    ///
+    /// This is appropriate for warm starting when a sequence of similar
+    /// problems is solved, as in model predictive control, where the problem
+    /// changes only slightly from one control cycle to the next. The curvature
+    /// information in the LBFGS buffer is then carried over to the next problem;
+    /// if the problem has changed substantially, use `reset` instead.
+    pub fn soft_reset(&mut self) {
         self.lhs_ls = T::zero();
         self.rhs_ls = T::zero();
         self.tau = T::one();
@@ -193,7 +876,26 @@ where
         self.sigma = T::zero();
         self.cost_value = T::zero();
         self.iteration = 0;
+        self.num_lbfgs_applications = 0;
+        self.num_lbfgs_acceptances = 0;
+        self.num_lbfgs_rejections = 0;
+        self.num_linesearch_steps = 0;
+        self.num_cost_evals = 0;
+        self.num_grad_evals = 0;
+        self.linesearch_failed = false;
         self.gamma = T::zero();
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        if let Some(log) = &mut self.lbfgs_rejection_log {
+            log.clear();
+        }
+        if let Some(best) = &mut self.best_iterate {
+            best.recorded = false;
+        }
+        if let Some(stall_detection) = &mut self.stall_detection {
+            stall_detection.clear();
+        }
     }
 
     /// Sets the CBFGS parameters `alpha` and `epsilon`
@@ -214,11 +916,115 @@ where
     /// is negative.
     ///
     pub fn with_cbfgs_parameters(mut self, alpha: T, epsilon: T, sy_epsilon: T) -> Self {
-        self.lbfgs = self
-            .lbfgs
-            .with_cbfgs_alpha(alpha)
-            .with_cbfgs_epsilon(epsilon)
-            .with_sy_epsilon(sy_epsilon);
+        self.lbfgs = self.lbfgs.with_cbfgs_parameters(alpha, epsilon, sy_epsilon);
+        self.cbfgs_alpha = alpha;
+        self.cbfgs_epsilon = epsilon;
+        self.sy_epsilon = sy_epsilon;
         self
     }
+
+    /// Activates a diagonal preconditioner, $D = \mathrm{diag}(d)$
+    ///
+    /// PANOC is then applied to the problem in the scaled variable $v = Du$,
+    /// that is, the gradient steps are $u - \gamma D^{-2} \nabla f(u)$, the
+    /// norm of the fixed-point residual is measured in the metric of $D^2$
+    /// and the L-BFGS directions are computed in the scaled variable. A good
+    /// choice is the Jacobi preconditioner, $d_i = \sqrt{(\nabla^2 f)_{ii}}$,
+    /// which balances the curvature along the coordinates.
+    ///
+    /// Projections are computed in the original coordinates, that is, on the
+    /// set $U$ and in the Euclidean norm. This coincides with the projection
+    /// in the metric of $D^2$ only if $U$ is separable (see
+    /// `Constraint::is_separable`; e.g., `NoConstraints`, `Rectangle`,
+    /// `BallInf`, `Zero`, or Cartesian products of such sets); for other
+    /// sets, the fixed points of the algorithm would change, so PANOC fails
+    /// with `SolverError::InvalidProblem`. Also note that the AKKT
+    /// termination criterion (see `set_akkt_tolerance`) is computed in the
+    /// original coordinates.
+    ///
+    /// ## Arguments
+    ///
+    /// - `diag`: the diagonal, $d$, of $D$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `diag` is not equal to the problem
+    /// size, or if any of its elements is not positive and finite
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `2*n` floats, where `n` is the
+    /// problem size
+    ///
+    pub fn with_preconditioner(mut self, diag: Vec<T>) -> Self {
+        let n = self.gamma_fpr.len();
+        assert_eq!(n, diag.len(), "diag has wrong length");
+        assert!(
+            diag.iter().all(|&di| di > T::zero() && di.is_finite()),
+            "the elements of diag must be positive and finite"
+        );
+        self.preconditioner = Some(Preconditioner {
+            diag,
+            work_state: vec![T::zero(); n],
+            work_fpr: vec![T::zero(); n],
+        });
+        self
+    }
+
+    /// Diagonal of the preconditioner, if activated with
+    /// `with_preconditioner`, otherwise `None`
+    pub fn preconditioner(&self) -> Option<&[T]> {
+        self.preconditioner.as_ref().map(|p| p.diag.as_slice())
+    }
+
+    /// Approximate number of bytes of heap memory used by the cache
+    ///
+    /// This is the sum of the capacities of all internal vectors (including
+    /// those of the history, the best iterate, the preconditioner, the stall
+    /// detection and the log of L-BFGS rejections, if activated), times the
+    /// size of their elements, plus the memory of the L-BFGS buffer, which
+    /// stores `2*lbfgs_memory_size*problem_size + 2*lbfgs_memory_size +
+    /// 2*problem_size` floats.
+    ///
+    /// The size of the `PANOCCache` struct itself and the bookkeeping of the
+    /// allocator are not included.
+    ///
+    /// ## Returns
+    ///
+    /// The number of bytes of heap memory used by the cache
+    ///
+    pub fn memory_footprint(&self) -> usize {
+        let work_vectors = vec_footprint(&self.gradient_u)
+            + self.gradient_u_previous.as_ref().map_or(0, vec_footprint)
+            + vec_footprint(&self.u_half_step)
+            + vec_footprint(&self.gradient_step)
+            + vec_footprint(&self.direction_lbfgs)
+            + vec_footprint(&self.u_plus)
+            + vec_footprint(&self.gamma_fpr);
+        let optional_features = self
+            .history
+            .as_ref()
+            .map_or(0, PANOCHistory::memory_footprint)
+            + self
+                .lbfgs_rejection_log
+                .as_ref()
+                .map_or(0, LbfgsRejectionLog::memory_footprint)
+            + self
+                .best_iterate
+                .as_ref()
+                .map_or(0, |best| vec_footprint(&best.u))
+            + self.preconditioner.as_ref().map_or(0, |p| {
+                vec_footprint(&p.diag) + vec_footprint(&p.work_state) + vec_footprint(&p.work_fpr)
+            })
+            + self
+                .stall_detection
+                .as_ref()
+                .map_or(0, |stall| vec_footprint(&stall.values));
+        self.lbfgs.memory_footprint() + work_vectors + optional_features
+    }
+}
+
+/// Number of bytes allocated by a vector
+pub(crate) fn vec_footprint<E>(v: &Vec<E>) -> usize {
+    v.capacity() * size_of::<E>()
 }
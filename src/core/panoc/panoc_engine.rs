@@ -1,12 +1,12 @@
 use crate::core::panoc::PANOCCache;
-use crate::core::{AlgorithmEngine, OptFloat, Problem};
-use crate::{constraints, matrix_operations, FunctionCallResult, SolverError};
+use crate::core::{AlgorithmEngine, OptFloat, Problem, SolverParams};
+use crate::{constraints, lipschitz_estimator, matrix_operations, FunctionCallResult, SolverError};
 
 /// Maximum iterations of updating the Lipschitz constant
 const MAX_LIPSCHITZ_UPDATE_ITERATIONS: usize = 10;
 
-/// Maximum number of linesearch iterations
-const MAX_LINESEARCH_ITERATIONS: u32 = 10;
+/// Default maximum number of linesearch iterations
+const MAX_LINESEARCH_ITERATIONS: usize = 10;
 
 /// Engine for PANOC algorithm
 pub struct PANOCEngine<'a, GradientType, ConstraintType, CostType, T>
@@ -18,6 +18,33 @@ where
 {
     problem: Problem<'a, GradientType, ConstraintType, CostType, T>,
     pub(crate) cache: &'a mut PANOCCache<T>,
+    /// Whether to keep the LBFGS buffer of the cache at initialization
+    pub(crate) lbfgs_warm_start: bool,
+    /// Whether to project the initial guess on the constraints at
+    /// initialization
+    pub(crate) feasible_init: bool,
+    /// Initial estimate of the Lipschitz constant, which is used instead of
+    /// the estimate computed at initialization (if provided)
+    pub(crate) initial_lipschitz: Option<T>,
+    /// Whether to start from the Lipschitz constant left in the cache by the
+    /// previous solve (if any) instead of estimating it at initialization
+    pub(crate) reuse_lipschitz: bool,
+    /// Period (in iterations) of the periodic reset of the L-BFGS buffer;
+    /// `None` if the buffer is not reset periodically
+    pub(crate) lbfgs_reset_every: Option<usize>,
+    /// Whether to reset the L-BFGS buffer kept by the warm start when the
+    /// initial step size differs from the final one of the previous solve
+    pub(crate) lbfgs_reset_on_initial_gamma_change: bool,
+    /// Maximum number of halvings of tau in each line search
+    pub(crate) max_linesearch_iters: usize,
+    /// Whether to terminate when the line search reaches
+    /// `max_linesearch_iters` (otherwise, the last candidate is accepted)
+    pub(crate) stop_on_linesearch_failure: bool,
+    /// Initial tolerance, final tolerance and tightening factor of the
+    /// adaptive tolerance; `None` if the tolerance is fixed
+    pub(crate) adaptive_tolerance: Option<(T, T, T)>,
+    /// Numerical constants (overrides of the defaults of `OptFloat`)
+    pub(crate) params: SolverParams<T>,
 }
 
 impl<'a, GradientType, ConstraintType, CostType, T>
@@ -43,25 +70,98 @@ where
         problem: Problem<'a, GradientType, ConstraintType, CostType, T>,
         cache: &'a mut PANOCCache<T>,
     ) -> PANOCEngine<'a, GradientType, ConstraintType, CostType, T> {
-        PANOCEngine { problem, cache }
+        PANOCEngine {
+            problem,
+            cache,
+            lbfgs_warm_start: false,
+            feasible_init: false,
+            initial_lipschitz: None,
+            reuse_lipschitz: false,
+            lbfgs_reset_every: None,
+            lbfgs_reset_on_initial_gamma_change: false,
+            max_linesearch_iters: MAX_LINESEARCH_ITERATIONS,
+            stop_on_linesearch_failure: false,
+            adaptive_tolerance: None,
+            params: SolverParams::new(),
+        }
+    }
+
+    /// Replaces the constraints of the problem (see
+    /// `Problem::set_constraints`); the cache is not modified
+    pub fn set_constraints(&mut self, constraints: &'a ConstraintType) {
+        self.problem.set_constraints(constraints);
     }
 
     /// Estimate the local Lipschitz constant at `u`
+    ///
+    /// With a preconditioner, $D$, this is the Lipschitz constant of the
+    /// gradient of $v \mapsto f(D^{-1} v)$ at $v = D u$
+    ///
+    /// The vector `gradient_step` of the cache is used as workspace, so no
+    /// memory is allocated. On exit, `u` is perturbed (see
+    /// `lipschitz_estimator`) and `gradient_u` is the gradient at the
+    /// given `u`.
     fn estimate_loc_lip(&mut self, u: &mut [T]) -> FunctionCallResult {
-        let mut lipest = crate::lipschitz_estimator::LipschitzEstimator::new(
-            u,
+        let delta = self.params.delta_lipschitz();
+        let epsilon = self.params.epsilon_lipschitz();
+        let cache = &mut *self.cache;
+        if let Some(preconditioner) = &mut cache.preconditioner {
+            let diag = &preconditioner.diag;
+            let gradf = &self.problem.gradf;
+            let u_work = core::cell::RefCell::new(&mut preconditioner.work_state);
+            // gradient of v -> f(D^{-1} v), which is D^{-1} grad f(D^{-1} v)
+            let scaled_gradf = |v: &[T], grad: &mut [T]| -> FunctionCallResult {
+                let mut u_work = u_work.borrow_mut();
+                u_work
+                    .iter_mut()
+                    .zip(v.iter().zip(diag.iter()))
+                    .for_each(|(ui, (&vi, &di))| *ui = vi / di);
+                gradf(&u_work, grad)?;
+                grad.iter_mut()
+                    .zip(diag.iter())
+                    .for_each(|(gi, &di)| *gi /= di);
+                Ok(())
+            };
+            let v = &mut preconditioner.work_fpr;
+            v.iter_mut()
+                .zip(u.iter().zip(diag.iter()))
+                .for_each(|(vi, (&ui, &di))| *vi = di * ui);
+            cache.lipschitz_constant = lipschitz_estimator::estimate_local_lipschitz(
+                &scaled_gradf,
+                v,
+                &mut cache.gradient_u,
+                &mut cache.gradient_step,
+                delta,
+                epsilon,
+            )?;
+            cache.num_grad_evals += 2; // at v and at a perturbation of v
+                                       // gradient_u is the scaled gradient, D^{-1} grad f(u), and u is
+                                       // the perturbed point, D^{-1} v
+            cache
+                .gradient_u
+                .iter_mut()
+                .zip(diag.iter())
+                .for_each(|(gi, &di)| *gi *= di);
+            u.iter_mut()
+                .zip(v.iter().zip(diag.iter()))
+                .for_each(|(ui, (&vi, &di))| *ui = vi / di);
+            return Ok(());
+        }
+        cache.lipschitz_constant = lipschitz_estimator::estimate_local_lipschitz(
             &self.problem.gradf,
-            &mut self.cache.gradient_u,
-        )
-        .with_delta(T::delta_lipschitz())
-        .with_epsilon(T::epsilon_lipschitz());
-        self.cache.lipschitz_constant = lipest.estimate_local_lipschitz()?;
+            u,
+            &mut cache.gradient_u,
+            &mut cache.gradient_step,
+            delta,
+            epsilon,
+        )?;
+        cache.num_grad_evals += 2; // at u and at a perturbation of u
 
         Ok(())
     }
 
-    /// Computes the FPR and its norm
-    fn compute_fpr(&mut self, u_current: &[T]) {
+    /// Computes the FPR and its norm; fails if the norm is not finite
+    fn compute_fpr(&mut self, u_current: &[T]) -> FunctionCallResult {
         // compute the FPR:
         // fpr ← u - u_half_step
         let cache = &mut self.cache;
@@ -71,8 +171,35 @@ where
             .zip(u_current.iter())
             .zip(cache.u_half_step.iter())
             .for_each(|((fpr, u), uhalf)| *fpr = *u - *uhalf);
-        // compute the norm of FPR
-        cache.norm_gamma_fpr = matrix_operations::norm2(&cache.gamma_fpr);
+        // compute the norm of FPR (in the metric of D^2, if preconditioned)
+        let norm_gamma_fpr = match &cache.preconditioner {
+            Some(preconditioner) => Ok(preconditioner.norm2_squared(&cache.gamma_fpr).sqrt()),
+            None => matrix_operations::norm2_checked(&cache.gamma_fpr),
+        };
+        match norm_gamma_fpr {
+            Ok(norm_gamma_fpr) if norm_gamma_fpr.is_finite() => {
+                cache.norm_gamma_fpr = norm_gamma_fpr;
+                Ok(())
+            }
+            _ => Err(self.not_finite_error(u_current)),
+        }
+    }
+
+    /// Error for a NaN/infinite value detected at the current iterate
+    ///
+    /// This is `NotFiniteSolution` with the index of the first non-finite
+    /// element of `u_current` or, if it is finite, of the FPR (which is
+    /// not finite if the gradient is not finite), and `NotFiniteComputation`
+    /// if both are finite (e.g., if only the cost is not finite)
+    fn not_finite_error(&self, u_current: &[T]) -> SolverError {
+        matrix_operations::first_non_finite(u_current)
+            .or_else(|| matrix_operations::first_non_finite(&self.cache.gamma_fpr))
+            .map_or(SolverError::NotFiniteComputation, |index| {
+                SolverError::NotFiniteSolution {
+                    index,
+                    iteration: self.cache.iteration,
+                }
+            })
     }
 
     /// Computes a gradient step; does not compute the gradient
@@ -81,12 +208,8 @@ where
         // gradient_step ← u_current - gamma * gradient
         let cache = &mut self.cache;
         let gamma = cache.gamma;
-        cache
-            .gradient_step
-            .iter_mut()
-            .zip(u_current.iter())
-            .zip(cache.gradient_u.iter())
-            .for_each(|((grad_step, u), grad)| *grad_step = *u - gamma * *grad);
+        cache.gradient_step.copy_from_slice(u_current);
+        scaled_gradient_step(cache, gamma);
     }
 
     /// Takes a gradient step on u_plus
@@ -95,12 +218,8 @@ where
         // gradient_step ← u_plus - gamma * gradient
         let cache = &mut self.cache;
         let gamma = cache.gamma;
-        cache
-            .gradient_step
-            .iter_mut()
-            .zip(cache.u_plus.iter())
-            .zip(cache.gradient_u.iter())
-            .for_each(|((grad_step, u), grad)| *grad_step = *u - gamma * *grad);
+        cache.gradient_step.copy_from_slice(&cache.u_plus);
+        scaled_gradient_step(cache, gamma);
     }
 
     /// Computes a projection on `gradient_step`
@@ -114,14 +233,42 @@ where
     /// Computes an LBFGS direction; updates `cache.direction_lbfgs`
     fn lbfgs_direction(&mut self, u_current: &[T]) {
         let cache = &mut self.cache;
+        if let Some(preconditioner) = &mut cache.preconditioner {
+            // the L-BFGS buffer is updated with (D*u, D*fpr) and the direction
+            // is D^{-1} H (D*fpr)
+            let diag = &preconditioner.diag;
+            let (v, v_fpr) = (&mut preconditioner.work_state, &mut preconditioner.work_fpr);
+            v.iter_mut()
+                .zip(u_current.iter().zip(diag.iter()))
+                .for_each(|(vi, (&ui, &di))| *vi = di * ui);
+            v_fpr
+                .iter_mut()
+                .zip(cache.gamma_fpr.iter().zip(diag.iter()))
+                .for_each(|(vi, (&fpr_i, &di))| *vi = di * fpr_i);
+            let update_status = cache.lbfgs.update_hessian(v_fpr, v);
+            if cache.iteration > 0 {
+                cache.direction_lbfgs.copy_from_slice(v_fpr);
+                cache.lbfgs.apply_hessian(&mut cache.direction_lbfgs);
+                cache
+                    .direction_lbfgs
+                    .iter_mut()
+                    .zip(diag.iter())
+                    .for_each(|(dir_i, &di)| *dir_i /= di);
+                cache.num_lbfgs_applications += 1;
+            }
+            cache.record_lbfgs_update(update_status, u_current);
+            return;
+        }
         // update the LBFGS buffer
-        cache.lbfgs.update_hessian(&cache.gamma_fpr, u_current);
+        let update_status = cache.lbfgs.update_hessian(&cache.gamma_fpr, u_current);
+        cache.record_lbfgs_update(update_status, u_current);
 
         // direction ← fpr
         if cache.iteration > 0 {
             cache.direction_lbfgs.copy_from_slice(&cache.gamma_fpr);
             // compute an LBFGS direction, that is direction ← H(fpr)
             cache.lbfgs.apply_hessian(&mut cache.direction_lbfgs);
+            cache.num_lbfgs_applications += 1;
         }
     }
 
@@ -137,12 +284,14 @@ where
 
         // rhs ← cost + LIP_EPS * |f| - <gradfx, gamma_fpr> + (L/2/gamma) ||gamma_fpr||^2
         cost_value + T::lipschitz_update_epsilon() * cost_value.abs() - inner_prod_grad_fpr
-            + (T::gamma_l_coeff() / (T::from(2.0).unwrap() * gamma))
+            + (self.params.gamma_l_coeff() / (T::from(2.0).unwrap() * gamma))
                 * (cache.norm_gamma_fpr.powi(2))
     }
 
     /// Updates the estimate of the Lipscthiz constant
-    fn update_lipschitz_constant(&mut self, u_current: &[T]) -> FunctionCallResult {
+    ///
+    /// Returns the cost at the (updated) half step
+    fn update_lipschitz_constant(&mut self, u_current: &[T]) -> Result<T, SolverError> {
         let mut cost_u_half_step = T::zero();
 
         // Compute the cost at the half step
@@ -150,14 +299,15 @@ where
 
         // Compute the cost at u_current (save it in `cache.cost_value`)
         (self.problem.cost)(u_current, &mut self.cache.cost_value)?;
+        self.cache.num_cost_evals += 2;
 
         let mut it_lipschitz_search = 0;
 
         while cost_u_half_step > self.lipschitz_check_rhs()
             && it_lipschitz_search < MAX_LIPSCHITZ_UPDATE_ITERATIONS
-            && self.cache.lipschitz_constant < T::max_lipschitz_constant()
+            && self.cache.lipschitz_constant < self.params.max_lipschitz_constant()
         {
-            self.cache.lbfgs.reset(); // invalidate the L-BFGS buffer
+            self.cache.reset_lbfgs(); // gamma changes: invalidate the L-BFGS buffer
 
             // update L, sigma and gamma...
             self.cache.lipschitz_constant = self.cache.lipschitz_constant * T::from(2.0).unwrap();
@@ -170,15 +320,16 @@ where
             // recompute the cost at the half step
             // update `cost_u_half_step`
             (self.problem.cost)(&self.cache.u_half_step, &mut cost_u_half_step)?;
+            self.cache.num_cost_evals += 1;
 
             // recompute the FPR and the square of its norm
-            self.compute_fpr(u_current);
+            self.compute_fpr(u_current)?;
             it_lipschitz_search += 1;
         }
         self.cache.sigma =
-            (T::one() - T::gamma_l_coeff()) / (T::from(4.0).unwrap() * self.cache.gamma);
+            (T::one() - self.params.gamma_l_coeff()) / (T::from(4.0).unwrap() * self.cache.gamma);
 
-        Ok(())
+        Ok(cost_u_half_step)
     }
 
     /// Computes u_plus ← u - gamma * (1-tau) * fpr - tau * dir,
@@ -202,18 +353,10 @@ where
     fn compute_rhs_ls(&mut self) {
         let cache = &mut self.cache;
 
-        // dist squared ← norm(gradient step - u half step)^2
-        let dist_squared =
-            matrix_operations::norm2_squared_diff(&cache.gradient_step, &cache.u_half_step);
-
         // rhs_ls ← f - (gamma/2) * norm(gradf)^2
         //            + 0.5 * dist squared / gamma
         //            - sigma * norm_gamma_fpr^2
-        let fbe = cache.cost_value
-            - T::from(0.5).unwrap()
-                * cache.gamma
-                * matrix_operations::norm2_squared(&cache.gradient_u)
-            + T::from(0.5).unwrap() * dist_squared / cache.gamma;
+        let fbe = forward_backward_envelope(cache);
         let sigma_fpr_sq = cache.sigma * cache.norm_gamma_fpr.powi(2);
         cache.rhs_ls = fbe - sigma_fpr_sq;
     }
@@ -221,8 +364,6 @@ where
     /// Computes the left hand side of the line search condition and compares it with the RHS;
     /// returns `true` if and only if lhs > rhs (when the line search should continue)
     fn line_search_condition(&mut self, u: &[T]) -> Result<bool, SolverError> {
-        let gamma = self.cache.gamma;
-
         // u_plus ← u - (1-tau)*gamma_fpr + tau*direction
         self.compute_u_plus(u);
 
@@ -231,22 +372,14 @@ where
         // point `u_plus`
         (self.problem.cost)(&self.cache.u_plus, &mut self.cache.cost_value)?;
         (self.problem.gradf)(&self.cache.u_plus, &mut self.cache.gradient_u)?;
+        self.cache.num_cost_evals += 1;
+        self.cache.num_grad_evals += 1;
 
         self.gradient_step_uplus(); // gradient_step ← u_plus - gamma * gradient_u
         self.half_step(); // u_half_step ← project(gradient_step)
 
-        // Compute: dist_squared ← norm(gradient_step - u_half_step)^2
-        let dist_squared = matrix_operations::norm2_squared_diff(
-            &self.cache.gradient_step,
-            &self.cache.u_half_step,
-        );
-
-        // Update the LHS of the line search condition
-        self.cache.lhs_ls = self.cache.cost_value
-            - T::from(0.5).unwrap()
-                * gamma
-                * matrix_operations::norm2_squared(&self.cache.gradient_u)
-            + T::from(0.5).unwrap() * dist_squared / self.cache.gamma;
+        // Update the LHS of the line search condition (the FBE at u_plus)
+        self.cache.lhs_ls = forward_backward_envelope(self.cache);
 
         Ok(self.cache.lhs_ls > self.cache.rhs_ls)
     }
@@ -256,33 +389,82 @@ where
         u_current.copy_from_slice(&self.cache.u_half_step); // set u_current ← u_half_step
         (self.problem.cost)(u_current, &mut self.cache.cost_value)?; // cost value
         (self.problem.gradf)(u_current, &mut self.cache.gradient_u)?; // compute gradient
+        self.cache.num_cost_evals += 1;
+        self.cache.num_grad_evals += 1;
         self.gradient_step(u_current); // updated self.cache.gradient_step
         self.half_step(); // updates self.cache.u_half_step
+        self.cache.initialized = true;
 
         Ok(())
     }
 
-    /// Performs a line search to select tau
-    fn linesearch(&mut self, u_current: &mut [T]) -> FunctionCallResult {
+    /// Performs a line search to select tau; returns `true` if and only if
+    /// the line search condition is still violated after the maximum number
+    /// of halvings of tau, in which case the last candidate is accepted
+    fn linesearch(&mut self, u_current: &mut [T]) -> Result<bool, SolverError> {
         // perform line search
         self.compute_rhs_ls(); // compute the right hand side of the line search
         self.cache.tau = T::from(1.0).unwrap(); // initialise tau ← 1.0
         let mut num_ls_iters = 0;
-        while self.line_search_condition(u_current)? && num_ls_iters < MAX_LINESEARCH_ITERATIONS {
+        let mut cap_reached = false;
+        while self.line_search_condition(u_current)? {
+            if num_ls_iters == self.max_linesearch_iters {
+                cap_reached = true;
+                break;
+            }
             self.cache.tau = self.cache.tau / T::from(2.0).unwrap();
             num_ls_iters += 1;
         }
-        if num_ls_iters == MAX_LINESEARCH_ITERATIONS {
-            self.cache.tau = T::zero();
-            u_current.copy_from_slice(&self.cache.u_half_step);
-        }
+        self.cache.num_linesearch_steps += num_ls_iters;
         // Sets `u_current` to `u_plus` (u_current ← u_plus)
         u_current.copy_from_slice(&self.cache.u_plus);
 
-        Ok(())
+        Ok(cap_reached)
+    }
+}
+
+/// Computes gradient_step ← gradient_step - gamma * D^{-2} * gradient, where
+/// D is the preconditioner (if any, otherwise the identity)
+fn scaled_gradient_step<T: OptFloat>(cache: &mut PANOCCache<T>, gamma: T) {
+    match &cache.preconditioner {
+        Some(preconditioner) => cache
+            .gradient_step
+            .iter_mut()
+            .zip(cache.gradient_u.iter().zip(preconditioner.diag.iter()))
+            .for_each(|(step_i, (&grad_i, &di))| *step_i -= gamma * grad_i / (di * di)),
+        None => matrix_operations::sub_scaled_in_place(
+            &mut cache.gradient_step,
+            gamma,
+            &cache.gradient_u,
+        ),
+    }
+}
+
+/// Returns the squared norm of `gradient_step - u_half_step` in the metric of
+/// D^2 and the squared norm of the gradient in the metric of D^{-2}, where D
+/// is the preconditioner (if any, otherwise the identity)
+fn metric_norms_squared<T: OptFloat>(cache: &PANOCCache<T>) -> (T, T) {
+    match &cache.preconditioner {
+        Some(preconditioner) => (
+            preconditioner.norm2_squared_diff(&cache.gradient_step, &cache.u_half_step),
+            preconditioner.dual_norm2_squared(&cache.gradient_u),
+        ),
+        None => (
+            matrix_operations::norm2_squared_diff(&cache.gradient_step, &cache.u_half_step),
+            matrix_operations::norm2_squared(&cache.gradient_u),
+        ),
     }
 }
 
+/// Returns the forward-backward envelope at the point whose cost, gradient,
+/// gradient step and half step are stored in the cache, that is,
+/// f - (gamma/2) * norm(gradf)^2 + 0.5 * norm(gradient_step - u_half_step)^2 / gamma
+fn forward_backward_envelope<T: OptFloat>(cache: &PANOCCache<T>) -> T {
+    let (dist_squared, norm_gradient_squared) = metric_norms_squared(cache);
+    cache.cost_value - T::from(0.5).unwrap() * cache.gamma * norm_gradient_squared
+        + T::from(0.5).unwrap() * dist_squared / cache.gamma
+}
+
 /// Implementation of the `step` and `init` methods of [trait.AlgorithmEngine.html]
 impl<'a, GradientType, ConstraintType, CostType, T> AlgorithmEngine<T>
     for PANOCEngine<'a, GradientType, ConstraintType, CostType, T>
@@ -290,7 +472,7 @@ where
     GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
     CostType: Fn(&[T], &mut T) -> FunctionCallResult,
     ConstraintType: constraints::Constraint<T>,
-    T: OptFloat + std::fmt::Debug,
+    T: OptFloat,
 {
     /// PANOC step
     ///
@@ -306,24 +488,63 @@ where
         // caches the previous gradient vector (copies df to df_previous)
         self.cache.cache_previous_gradient();
 
-        // compute the fixed point residual
-        self.compute_fpr(u_current);
+        // compute the fixed point residual; a NaN or infinite value in the
+        // cost or the gradient at u_current is caught here
+        self.compute_fpr(u_current)?;
+        if !self.cache.cost_value.is_finite() {
+            return Err(self.not_finite_error(u_current));
+        }
 
         // exit if the exit conditions are satisfied (||gamma*fpr|| < eps and,
-        // if activated, ||gamma*r + df - df_prev|| < eps_akkt)
+        // if activated, ||gamma*r + df - df_prev|| < eps_akkt); with an
+        // adaptive tolerance, eps is tightened instead (once per iteration)
+        // until it reaches its final value, unless the relative criterion is
+        // satisfied
         if self.cache.exit_condition() {
-            return Ok(false);
+            match (self.adaptive_tolerance, self.cache.adaptive_tolerance) {
+                (Some((_, final_tolerance, factor)), Some(tolerance))
+                    if tolerance > final_tolerance && !self.cache.relative_exit_condition() =>
+                {
+                    self.cache.adaptive_tolerance =
+                        Some(T::max(tolerance * factor, final_tolerance));
+                }
+                _ => return Ok(false),
+            }
         }
-        self.update_lipschitz_constant(u_current)?; // update lipschitz constant
-        self.lbfgs_direction(u_current); // compute LBFGS direction (update LBFGS buffer)
-        if self.cache.iteration == 0 {
-            // first iteration, no line search is performed
+        let cost_u_half_step = self.update_lipschitz_constant(u_current)?; // update lipschitz constant
+        self.cache.record_best_iterate(cost_u_half_step);
+        if let Some(period) = self.lbfgs_reset_every {
+            if self.cache.iteration > 0 && self.cache.iteration % period == 0 {
+                self.cache.reset_lbfgs(); // periodic reset of the L-BFGS buffer
+            }
+        }
+        if self.cache.lbfgs_applications_exhausted() {
+            // the L-BFGS budget is exhausted: take a forward-backward step
+            self.cache.tau = T::zero();
+            self.update_no_linesearch(u_current)?;
+        } else if self.cache.iteration == 0 {
+            self.lbfgs_direction(u_current); // update LBFGS buffer
+                                             // first iteration, no line search is performed
             self.update_no_linesearch(u_current)?;
         } else {
-            self.linesearch(u_current)?;
+            self.lbfgs_direction(u_current); // compute LBFGS direction (update LBFGS buffer)
+            if self.linesearch(u_current)? && self.stop_on_linesearch_failure {
+                self.cache.linesearch_failed = true;
+            }
         }
 
         self.cache.iteration += 1;
+        self.cache.record_history();
+        if self.cache.linesearch_failed {
+            return Ok(false);
+        }
+
+        // terminate if the FBE has stalled (if stall detection is activated);
+        // unlike the cost, the FBE decreases monotonically along the iterates
+        if self.cache.stall_detection.is_some() {
+            let fbe = forward_backward_envelope(self.cache);
+            return Ok(!self.cache.record_stall_detection(fbe));
+        }
         Ok(true)
     }
 
@@ -337,15 +558,53 @@ where
     /// a gradient step and a half step (projected gradient step)
     ///
     fn init(&mut self, u_current: &mut [T]) -> FunctionCallResult {
-        self.cache.reset();
+        // the projection in the metric of the preconditioner is the Euclidean
+        // projection only on separable sets
+        if self.cache.preconditioner.is_some() && !self.problem.constraints.is_separable() {
+            return Err(SolverError::InvalidProblem);
+        }
+        // the Lipschitz constant and step size of the previous solve (zero if none)
+        let previous_lipschitz = self.cache.lipschitz_constant;
+        let previous_gamma = self.cache.gamma;
+        if self.lbfgs_warm_start {
+            self.cache.soft_reset();
+        } else {
+            self.cache.reset();
+        }
+        self.cache.adaptive_tolerance = self.adaptive_tolerance.map(|(initial, _, _)| initial);
+        if self.feasible_init {
+            self.problem.constraints.project(u_current);
+        }
         (self.problem.cost)(u_current, &mut self.cache.cost_value)?; // cost value
-        self.estimate_loc_lip(u_current)?; // computes the gradient as well! (self.cache.gradient_u)
-        self.cache.gamma =
-            T::gamma_l_coeff() / T::max(self.cache.lipschitz_constant, T::min_l_estimate());
+        self.cache.num_cost_evals += 1;
+        let reused_lipschitz = if self.reuse_lipschitz && previous_lipschitz > T::zero() {
+            Some(previous_lipschitz)
+        } else {
+            None
+        };
+        match reused_lipschitz.or(self.initial_lipschitz) {
+            Some(lipschitz_constant) => {
+                (self.problem.gradf)(u_current, &mut self.cache.gradient_u)?;
+                self.cache.num_grad_evals += 1;
+                self.cache.lipschitz_constant = lipschitz_constant;
+            }
+            None => self.estimate_loc_lip(u_current)?, // computes the gradient as well! (self.cache.gradient_u)
+        }
+        self.cache.gamma = self.params.gamma_l_coeff()
+            / T::max(self.cache.lipschitz_constant, self.params.min_l_estimate());
         self.cache.sigma =
-            (T::one() - T::gamma_l_coeff()) / (T::from(4.0).unwrap() * self.cache.gamma);
+            (T::one() - self.params.gamma_l_coeff()) / (T::from(4.0).unwrap() * self.cache.gamma);
+        if self.lbfgs_reset_on_initial_gamma_change && self.cache.gamma != previous_gamma {
+            self.cache.reset_lbfgs(); // the L-BFGS buffer was built with a different gamma
+        }
         self.gradient_step(u_current); // updated self.cache.gradient_step
         self.half_step(); // updates self.cache.u_half_step
+        self.cache.norm_gamma_fpr_initial = match &self.cache.preconditioner {
+            Some(preconditioner) => preconditioner
+                .norm2_squared_diff(u_current, &self.cache.u_half_step)
+                .sqrt(),
+            None => matrix_operations::norm2_diff(u_current, &self.cache.u_half_step),
+        };
 
         Ok(())
     }
@@ -359,8 +618,8 @@ mod tests {
 
     use crate::core::panoc::panoc_engine::PANOCEngine;
     use crate::core::panoc::*;
-    use crate::core::Problem;
-    use crate::{constraints, mocks};
+    use crate::core::{AlgorithmEngine, Problem, SolverParams};
+    use crate::{constraints, mocks, FunctionCallResult};
 
     #[test]
     fn t_compute_fpr() {
@@ -374,7 +633,7 @@ mod tests {
         let u = [0.75, -1.4];
         panoc_engine.cache.gamma = 0.0234;
         panoc_engine.cache.u_half_step.copy_from_slice(&[0.5, 2.9]);
-        panoc_engine.compute_fpr(&u); // gamma_fpr ← u - u_half_step, norm_fpr ← norm(gamma_fpr)
+        panoc_engine.compute_fpr(&u).unwrap(); // gamma_fpr ← u - u_half_step, norm_fpr ← norm(gamma_fpr)
         unit_test_utils::assert_nearly_equal_array(
             &[0.25, -4.3],
             &panoc_engine.cache.gamma_fpr,
@@ -540,4 +799,35 @@ mod tests {
             "rhs_ls is wrong",
         );
     }
+
+    #[test]
+    fn t_solver_params_delta_lipschitz() {
+        let n = 2;
+        let mem = 5;
+        let bounds = constraints::NoConstraints::new();
+        let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+            Ok(())
+        };
+        let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+            Ok(())
+        };
+        let mut panoc_cache = PANOCCache::new(n, 1e-6, mem);
+
+        let mut initial_lipschitz_estimate = |params: SolverParams<f64>| {
+            let problem = Problem::new(&bounds, gradient, cost);
+            let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+            panoc_engine.params = params;
+            let mut u = [0.0, 0.0];
+            panoc_engine.init(&mut u).unwrap();
+            panoc_engine.cache.lipschitz_constant
+        };
+
+        // at u = 0, the perturbation used in the estimation is delta
+        let lip_default = initial_lipschitz_estimate(SolverParams::new());
+        let lip_tuned = initial_lipschitz_estimate(SolverParams::new().with_delta_lipschitz(0.1));
+        unit_test_utils::assert_nearly_equal(141.428_4, lip_default, 1e-4, 1e-6, "default L");
+        assert!((lip_tuned - lip_default).abs() > 1.0);
+    }
 }
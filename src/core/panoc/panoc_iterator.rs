@@ -0,0 +1,119 @@
+//! Step-by-step iteration of PANOC
+//!
+use crate::core::panoc::panoc_engine::PANOCEngine;
+use crate::core::panoc::PanocProgress;
+use crate::core::{AlgorithmEngine, OptFloat};
+use crate::{constraints, FunctionCallResult, SolverError};
+
+/// Iterator over the iterations of PANOC
+///
+/// Every call to `next` performs one iteration of PANOC and yields its
+/// [PanocProgress](struct.PanocProgress.html); the solver is initialized in
+/// the first call. The iterator ends when the solver converges or when the
+/// maximum number of iterations is reached; then, the solution is copied into
+/// `u` (as in `PANOCOptimizer::solve`). If an iteration fails, the error is
+/// yielded and the iterator ends.
+///
+/// Instances of `PanocIterator` are constructed with
+/// [PANOCOptimizer::iter](struct.PANOCOptimizer.html#method.iter).
+///
+pub struct PanocIterator<'a, 'b, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T>,
+    T: OptFloat,
+{
+    panoc_engine: &'b mut PANOCEngine<'a, GradientType, ConstraintType, CostType, T>,
+    u: &'b mut [T],
+    max_iter: usize,
+    num_iter: usize,
+    initialized: bool,
+    finished: bool,
+}
+
+impl<'a, 'b, GradientType, ConstraintType, CostType, T>
+    PanocIterator<'a, 'b, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T>,
+    T: OptFloat,
+{
+    pub(crate) fn new(
+        panoc_engine: &'b mut PANOCEngine<'a, GradientType, ConstraintType, CostType, T>,
+        u: &'b mut [T],
+        max_iter: usize,
+    ) -> Self {
+        PanocIterator {
+            panoc_engine,
+            u,
+            max_iter,
+            num_iter: 0,
+            initialized: false,
+            finished: false,
+        }
+    }
+
+    /// Whether the solver has converged (this is `false` while the iterator
+    /// has not ended)
+    pub fn has_converged(&self) -> bool {
+        self.finished && self.panoc_engine.cache.exit_condition()
+    }
+
+    /// Performs one iteration; returns `Ok(true)` if the iterations should
+    /// continue
+    fn step(&mut self) -> Result<bool, SolverError> {
+        if !self.initialized {
+            self.panoc_engine.init(self.u)?;
+            self.initialized = true;
+        }
+        let continue_iterations = self.panoc_engine.step(self.u)?;
+        if continue_iterations {
+            self.num_iter += 1;
+        }
+        Ok(continue_iterations && self.num_iter < self.max_iter)
+    }
+
+    /// Ends the iterations and copies the solution (the half step, which is
+    /// always feasible) into `u`
+    fn finish(&mut self) {
+        self.finished = true;
+        self.u.copy_from_slice(&self.panoc_engine.cache.u_half_step);
+    }
+}
+
+impl<'a, 'b, GradientType, ConstraintType, CostType, T> Iterator
+    for PanocIterator<'a, 'b, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T>,
+    T: OptFloat,
+{
+    type Item = Result<PanocProgress<T>, SolverError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let iteration_count = self.num_iter;
+        match self.step() {
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+            Ok(continue_iterations) => {
+                if !continue_iterations {
+                    self.finish();
+                }
+                if self.num_iter > iteration_count {
+                    Some(Ok(PanocProgress::from_cache(self.panoc_engine.cache)))
+                } else {
+                    // the solver converged without taking a new step
+                    None
+                }
+            }
+        }
+    }
+}
@@ -1,18 +1,30 @@
 //! PANOC optimizer
 //!
-use std::time;
+use alloc::boxed::Box;
+use core::time;
 
 use crate::core::panoc::panoc_engine::PANOCEngine;
-use crate::core::panoc::PANOCCache;
-use crate::core::{AlgorithmEngine, ExitStatus, OptFloat, Optimizer, Problem, SolverStatus};
+use crate::core::panoc::{BestIterateCriterion, PANOCCache, PanocIterator, PanocProgress};
+use crate::core::{
+    clock::Stopwatch, AlgorithmEngine, Clock, ExitStatus, OptFloat, Optimizer, Problem,
+    SolverParams, SolverStatus,
+};
 use crate::{constraints, matrix_operations, FunctionCallResult, SolverError};
 
 const MAX_ITER: usize = 100_usize;
 
+type InnerCallback<'c, T> = Box<dyn FnMut(&PanocProgress<T>) -> bool + 'c>;
+type SolveCallback<'b, T> = &'b mut dyn FnMut(usize, T, T) -> bool;
+
 /// Optimizer using the PANOC algorithm
 ///
+/// The lifetime `'c` is the lifetime of the iteration callback (see
+/// `with_inner_callback`); it is independent of `'a`, so that the cache can be
+/// accessed once the solver has returned, even if the optimizer is still in
+/// scope (a boxed callback bounded by `'a` would keep the cache borrowed until
+/// the optimizer is dropped)
 ///
-pub struct PANOCOptimizer<'a, GradientType, ConstraintType, CostType, T>
+pub struct PANOCOptimizer<'a, 'c, GradientType, ConstraintType, CostType, T>
 where
     GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
     CostType: Fn(&[T], &mut T) -> FunctionCallResult,
@@ -22,10 +34,12 @@ where
     panoc_engine: PANOCEngine<'a, GradientType, ConstraintType, CostType, T>,
     max_iter: usize,
     max_duration: Option<time::Duration>,
+    inner_callback: Option<InnerCallback<'c, T>>,
+    clock: Option<&'a dyn Clock>,
 }
 
-impl<'a, GradientType, ConstraintType, CostType, T>
-    PANOCOptimizer<'a, GradientType, ConstraintType, CostType, T>
+impl<'a, 'c, GradientType, ConstraintType, CostType, T>
+    PANOCOptimizer<'a, 'c, GradientType, ConstraintType, CostType, T>
 where
     GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
     CostType: Fn(&[T], &mut T) -> FunctionCallResult,
@@ -50,6 +64,8 @@ where
             panoc_engine: PANOCEngine::new(problem, cache),
             max_iter: MAX_ITER,
             max_duration: None,
+            inner_callback: None,
+            clock: None,
         }
     }
 
@@ -61,10 +77,70 @@ where
     /// ## Panics
     ///
     /// The method panics if the specified tolerance is not positive
-    pub fn with_tolerance(self, tolerance: T) -> Self {
-        assert!(tolerance > T::zero(), "tolerance must be larger than 0");
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.set_tolerance(tolerance);
+        self
+    }
 
-        self.panoc_engine.cache.tolerance = tolerance;
+    /// Activates an adaptive tolerance on the norm of the fixed-point residual
+    ///
+    /// The solver starts with tolerance `initial`. Every time the termination
+    /// conditions are satisfied, instead of stopping, the tolerance is
+    /// multiplied by `factor` (but not below `final_tolerance`) and the
+    /// iterations continue, so the tolerance is tightened at most once per
+    /// iteration; the solver stops once the termination conditions are
+    /// satisfied with tolerance `final_tolerance`. The tolerance is set back
+    /// to `initial` at the beginning of every solve. The adaptive tolerance
+    /// replaces the tolerance of the cache (given to `PANOCCache::new` or
+    /// `with_tolerance`) during the solve, but the latter is not modified, so
+    /// it is used again by solves without an adaptive tolerance.
+    ///
+    /// Only the (absolute) tolerance on the norm of gamma*FPR is adapted:
+    ///
+    /// - if the AKKT criterion is activated (see `with_akkt_tolerance`), its
+    ///   tolerance is not adapted, and the tolerance on gamma*FPR is only
+    ///   tightened once the AKKT criterion is satisfied as well
+    /// - if a relative tolerance is set (see
+    ///   `PANOCCache::set_relative_tolerance`), the solver stops as soon as
+    ///   the relative criterion is satisfied, so `final_tolerance` may not be
+    ///   reached
+    ///
+    /// This is meant for anytime optimization: if the solver is interrupted
+    /// (e.g., because the maximum number of iterations or the maximum
+    /// duration is reached), the adaptive tolerance at the end of the solve
+    /// (see `PANOCCache::adaptive_tolerance`) is one step tighter than the
+    /// tightest tolerance that has been attained (or equal to `initial`, if
+    /// none has been attained).
+    ///
+    /// ## Arguments
+    ///
+    /// - `initial`: initial tolerance
+    /// - `final_tolerance`: final tolerance
+    /// - `factor`: factor by which the tolerance is multiplied, in $(0, 1)$
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `final_tolerance` is not positive, if `initial`
+    /// is smaller than `final_tolerance`, or if `factor` is not in $(0, 1)$
+    ///
+    pub fn with_adaptive_tolerance(mut self, initial: T, final_tolerance: T, factor: T) -> Self {
+        assert!(
+            final_tolerance > T::zero(),
+            "final_tolerance must be positive"
+        );
+        assert!(
+            initial >= final_tolerance,
+            "initial must not be smaller than final_tolerance"
+        );
+        assert!(
+            factor > T::zero() && factor < T::one(),
+            "factor must be in (0, 1)"
+        );
+        self.panoc_engine.adaptive_tolerance = Some((initial, final_tolerance, factor));
         self
     }
 
@@ -83,7 +159,11 @@ where
     ///
     /// Returns the current mutable and updated instance of the provided object
     ///
-    ///  
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `n` floats in the cache (the
+    /// previous cost gradient, which is needed by the AKKT condition)
+    ///
     /// ## Panics
     ///
     /// The method panics if the provided value of the AKKT-specific tolerance is
@@ -104,29 +184,555 @@ where
     ///
     /// Panics if the provided number of iterations is equal to zero
     pub fn with_max_iter(mut self, max_iter: usize) -> Self {
+        self.set_max_iter(max_iter);
+        self
+    }
+
+    /// Sets the maximum solution time, useful in real-time applications
+    ///
+    /// The elapsed time is checked once per iteration (not during the line
+    /// search); if the budget is exceeded before convergence, the solver
+    /// returns with exit status `NotConvergedOutOfTime`
+    pub fn with_max_duration(mut self, max_duration: time::Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Sets the clock which is used to measure the solution time
+    ///
+    /// By default, the system clock is used, which requires the feature
+    /// `std`; without it, and unless a clock is provided, the solution time is
+    /// reported as zero and the maximum duration has no effect (see
+    /// [Clock](../clock/trait.Clock.html)).
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Keeps the LBFGS buffer of the cache from the previous solve
+    ///
+    /// By default, the cache is reset (using `PANOCCache::reset`) at the
+    /// beginning of every solve. If the LBFGS warm start is activated, only the
+    /// scalar state of the cache is reset (using `PANOCCache::soft_reset`),
+    /// so the curvature information collected while solving the previous
+    /// problem is used from the first iteration. This is useful when solving
+    /// a sequence of similar problems.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_lbfgs_warm_start(mut self) -> Self {
+        self.set_lbfgs_warm_start(true);
+        self
+    }
+
+    /// Projects the initial guess on the constraints before the first
+    /// iteration
+    ///
+    /// By default, the iterations start from the initial guess as provided,
+    /// which may be infeasible (e.g., a warm start from the solution of a
+    /// problem with different constraints). With this option, the initial
+    /// guess is first projected on the set of constraints, before the cost
+    /// and its gradient are evaluated.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_feasible_init(mut self) -> Self {
+        self.panoc_engine.feasible_init = true;
+        self
+    }
+
+    /// Sets the initial estimate of the Lipschitz constant of the gradient of
+    /// the cost
+    ///
+    /// By default, the Lipschitz constant is estimated at the initial guess
+    /// using finite differences, which requires an additional evaluation of
+    /// the gradient. When a good estimate is known (e.g., in MPC, where the
+    /// Lipschitz constant changes little from one cycle to the next), it can
+    /// be provided instead; the initial step size is then computed from it
+    /// (by default, $\gamma = 0.95/L$) and the estimate is updated during the
+    /// iterations as usual. With a preconditioner, this is the Lipschitz
+    /// constant in the scaled variable.
+    ///
+    /// ## Arguments
+    ///
+    /// - `lipschitz_constant`: initial estimate of the Lipschitz constant, $L$
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `lipschitz_constant` is not positive
+    ///
+    pub fn with_initial_lipschitz(mut self, lipschitz_constant: T) -> Self {
+        assert!(
+            lipschitz_constant > T::zero(),
+            "lipschitz_constant must be positive"
+        );
+        self.panoc_engine.initial_lipschitz = Some(lipschitz_constant);
+        self
+    }
+
+    /// Starts from the estimate of the Lipschitz constant (and the step size,
+    /// $\gamma$) of the previous solve with the same cache, instead of
+    /// estimating it anew
+    ///
+    /// In MPC, where consecutive problems are similar, this saves the
+    /// gradient evaluation of the initial estimation. A stale estimate which
+    /// is too small is corrected by the Lipschitz check of the first
+    /// iteration, as in any other iteration. If there is no previous solve,
+    /// or the cache has been reset in between (see `reset`), the Lipschitz
+    /// constant is initialized as usual (see also `with_initial_lipschitz`).
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn reuse_lipschitz(mut self) -> Self {
+        self.panoc_engine.reuse_lipschitz = true;
+        self
+    }
+
+    /// Sets the maximum number of line search iterations
+    ///
+    /// At every iteration (except for the first one), PANOC halves tau until
+    /// the line search condition is satisfied. In pathological cases this
+    /// may take many halvings, each of which costs one evaluation of the
+    /// cost and its gradient. At most `k` halvings are performed per
+    /// iteration (the default value is `10`); if the line search condition
+    /// is still violated, the last candidate is accepted, unless
+    /// `stop_on_linesearch_failure` has been called.
+    ///
+    /// The total number of line search steps of the last solve is available
+    /// from `PANOCCache::linesearch_steps`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `k`: maximum number of halvings of tau per iteration
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_max_linesearch_iters(mut self, k: usize) -> Self {
+        self.panoc_engine.max_linesearch_iters = k;
+        self
+    }
+
+    /// Terminates the solver when the line search fails
+    ///
+    /// By default, when the line search condition is still violated after
+    /// the maximum number of line search iterations (see
+    /// `with_max_linesearch_iters`), the last candidate is accepted. Once
+    /// this method is called, the solver instead terminates with exit status
+    /// `ExitStatus::LineSearchFailed`.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn stop_on_linesearch_failure(mut self) -> Self {
+        self.panoc_engine.stop_on_linesearch_failure = true;
+        self
+    }
+
+    /// Resets the L-BFGS buffer every `k` iterations
+    ///
+    /// On problems whose curvature changes abruptly, the pairs stored in the
+    /// L-BFGS buffer may become stale and lead to poor directions; resetting
+    /// the buffer periodically discards them. By default, the buffer is only
+    /// reset when the estimate of the Lipschitz constant (and so $\gamma$)
+    /// is updated.
+    ///
+    /// ## Arguments
+    ///
+    /// - `k`: period of the reset, in iterations
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `k` is zero
+    ///
+    pub fn with_lbfgs_reset_every(mut self, k: usize) -> Self {
+        assert!(k > 0, "k must be positive");
+        self.panoc_engine.lbfgs_reset_every = Some(k);
+        self
+    }
+
+    /// Resets the L-BFGS buffer kept by the warm start if the initial $\gamma$
+    /// differs from the final $\gamma$ of the previous solve
+    ///
+    /// This option only acts at the initialization of a solve, and only has
+    /// an effect together with the L-BFGS warm start (see
+    /// `with_lbfgs_warm_start`), which otherwise keeps the buffer of the
+    /// previous solve even if the initial $\gamma$ is different. Within a
+    /// solve, the L-BFGS buffer is always reset when $\gamma$ is updated
+    /// (that is, when the estimate of the Lipschitz constant increases),
+    /// since the fixed-point residual, on which the buffer is built, depends
+    /// on $\gamma$; this does not depend on this option.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_lbfgs_reset_on_initial_gamma_change(mut self) -> Self {
+        self.panoc_engine.lbfgs_reset_on_initial_gamma_change = true;
+        self
+    }
+
+    /// Resets the cache, so that the optimizer can be reconfigured in place
+    ///
+    /// This is useful when the same optimizer is used to solve problems of a
+    /// different nature (e.g., in the different modes of an MPC state
+    /// machine). The cache is reset using `PANOCCache::reset` (so the LBFGS
+    /// buffer is discarded even if the LBFGS warm start is active) and the
+    /// settings can be changed using the chainable `set_*` methods; for
+    /// example,
+    ///
+    /// ```ignore
+    /// panoc
+    ///     .reconfigure()
+    ///     .set_tolerance(1e-8)
+    ///     .set_max_iter(500)
+    ///     .set_lbfgs_warm_start(false);
+    /// ```
+    ///
+    /// ## Returns
+    ///
+    /// Returns a mutable reference to the optimizer
+    ///
+    pub fn reconfigure(&mut self) -> &mut Self {
+        self.reset();
+        self
+    }
+
+    /// Resets the cache (see `PANOCCache::reset`), so that the next call to
+    /// `solve` does not reuse the LBFGS buffer even if the LBFGS warm start is
+    /// active
+    pub fn reset(&mut self) {
+        self.panoc_engine.cache.reset();
+    }
+
+    /// Replaces the constraints of the problem, so that the optimizer and its
+    /// cache can be reused when the constraints change from one solve to the
+    /// next (e.g., the bounds of a `Rectangle` in model predictive control)
+    ///
+    /// The constraints are borrowed for the lifetime `'a` of the optimizer,
+    /// which is also the lifetime of the borrow of the cache (see
+    /// `Problem::set_constraints`). As a result:
+    ///
+    /// - the new constraints, and any data they borrow, must be declared
+    ///   before the optimizer, e.g., as a collection of sets among which the
+    ///   optimizer switches
+    /// - the constraints cannot be modified in place while the optimizer
+    ///   borrows them
+    ///
+    /// Constraints which are constructed anew in every cycle with fresh data
+    /// cannot be borrowed for that long; in that case, construct a new
+    /// optimizer in every cycle instead, which does not allocate memory
+    /// since the cache is reused.
+    ///
+    /// If the LBFGS warm start is active, the LBFGS buffer of the previous
+    /// solve is kept, although it was built with the previous constraints;
+    /// call `reset` to discard it.
+    ///
+    /// ## Arguments
+    ///
+    /// - `constraints`: the new constraints, of the same type as the
+    ///   current ones
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn set_constraints(&mut self, constraints: &'a ConstraintType) -> &mut Self {
+        self.panoc_engine.set_constraints(constraints);
+        self
+    }
+
+    /// Sets the tolerance on the norm of the fixed-point residual
+    ///
+    /// This is the chainable, in-place version of `with_tolerance`
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the specified tolerance is not positive
+    pub fn set_tolerance(&mut self, tolerance: T) -> &mut Self {
+        assert!(tolerance > T::zero(), "tolerance must be larger than 0");
+
+        self.panoc_engine.cache.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the maximum number of iterations
+    ///
+    /// This is the chainable, in-place version of `with_max_iter`
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the provided number of iterations is equal to zero
+    pub fn set_max_iter(&mut self, max_iter: usize) -> &mut Self {
         assert!(max_iter > 0, "max_iter must be larger than 0");
 
         self.max_iter = max_iter;
         self
     }
 
-    /// Sets the maximum solution time, useful in real-time applications
-    pub fn with_max_duration(mut self, max_duation: time::Duration) -> Self {
-        self.max_duration = Some(max_duation);
+    /// Activates or deactivates the LBFGS warm start
+    ///
+    /// See `with_lbfgs_warm_start`
+    pub fn set_lbfgs_warm_start(&mut self, lbfgs_warm_start: bool) -> &mut Self {
+        self.panoc_engine.lbfgs_warm_start = lbfgs_warm_start;
         self
     }
-}
 
-impl<'life, GradientType, ConstraintType, CostType, T> Optimizer<T>
-    for PANOCOptimizer<'life, GradientType, ConstraintType, CostType, T>
-where
-    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'life,
-    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
-    ConstraintType: constraints::Constraint<T> + 'life,
-    T: OptFloat,
-{
-    fn solve(&mut self, u: &mut [T]) -> Result<SolverStatus<T>, SolverError> {
-        let now = instant::Instant::now();
+    /// Whether the next call to `solve` will reuse the LBFGS buffer of the
+    /// previous one
+    ///
+    /// This is `true` if and only if the LBFGS warm start is activated (see
+    /// `with_lbfgs_warm_start`) and the cache has been initialized by a
+    /// previous solve and has not been reset since (see
+    /// `PANOCCache::cache_is_initialized`).
+    pub fn is_warm_start_active(&self) -> bool {
+        self.panoc_engine.lbfgs_warm_start && self.panoc_engine.cache.cache_is_initialized()
+    }
+
+    /// Sets the maximum number of L-BFGS-accelerated steps per solve
+    ///
+    /// After `max_lbfgs_applications` steps along an L-BFGS direction, the
+    /// solver falls back to plain forward-backward (projected gradient) steps
+    /// for the remaining iterations. This is a diagnostic (ablation) tool,
+    /// which allows users to measure the contribution of the quasi-Newton
+    /// directions to the convergence of PANOC; it is not meant to improve
+    /// performance.
+    ///
+    /// ## Arguments
+    ///
+    /// - `max_lbfgs_applications`: maximum number of L-BFGS-accelerated steps;
+    ///   if it is zero, the solver performs only forward-backward steps
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_max_lbfgs_applications(self, max_lbfgs_applications: usize) -> Self {
+        self.panoc_engine.cache.max_lbfgs_applications = Some(max_lbfgs_applications);
+        self
+    }
+
+    /// Returns the best iterate, instead of the last one, if the solver does
+    /// not converge
+    ///
+    /// On nonconvex problems, the last iterate is not necessarily the best one.
+    /// Once activated, the solver keeps a copy of the iterate with the lowest
+    /// norm of gamma*FPR or the lowest cost (according to `criterion`) and, if
+    /// it does not converge, it returns that iterate (and reports its norm of
+    /// gamma*FPR and cost in the solver status). If the solver converges, the
+    /// last iterate is returned as usual. This is not activated by default.
+    ///
+    /// ## Arguments
+    ///
+    /// - `criterion`: criterion used to compare iterates
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `n` floats, where `n` is the problem
+    /// size; at every iteration, an improved iterate is copied to this buffer
+    ///
+    pub fn keep_best(self, criterion: BestIterateCriterion) -> Self {
+        self.panoc_engine.cache.enable_best_iterate(criterion);
+        self
+    }
+
+    /// Activates the detection of stalling
+    ///
+    /// With a bad estimate of the Lipschitz constant, PANOC may stall, that
+    /// is, the cost may barely change while the norm of gamma*FPR is far
+    /// from the tolerance. PANOC decreases the forward-backward envelope,
+    /// $\varphi_\gamma$, rather than the cost, which may increase along the
+    /// iterates, so stalling is measured on the former: when stall detection
+    /// is activated, the solver terminates with exit status
+    /// `ExitStatus::Stalled` if
+    /// $\varphi_\gamma(u^{k-w}) - \varphi_\gamma(u^k) \leq \epsilon_{\rm rel} |\varphi_\gamma(u^{k-w})|$,
+    /// where $w$ is the length of the window and $\epsilon_{\rm rel}$ the
+    /// threshold.
+    ///
+    /// ## Arguments
+    ///
+    /// - `window`: number of iterations, $w$, over which the decrease of the
+    ///   forward-backward envelope is measured
+    /// - `rel_threshold`: threshold, $\epsilon_{\rm rel}$, on the relative
+    ///   decrease of the forward-backward envelope
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `window` is zero or if `rel_threshold` is not
+    /// positive
+    ///
+    /// ## Memory allocation
+    ///
+    /// This method allocates memory for `window` floats in the cache
+    ///
+    pub fn with_stall_detection(self, window: usize, rel_threshold: T) -> Self {
+        assert!(window > 0, "window must be positive");
+        assert!(rel_threshold > T::zero(), "rel_threshold must be positive");
+        self.panoc_engine
+            .cache
+            .enable_stall_detection(window, rel_threshold);
+        self
+    }
+
+    /// Deactivates the L-BFGS directions, so that PANOC performs
+    /// forward-backward (projected gradient) steps
+    ///
+    /// Every iteration takes $\tau = 0$, so the step reduces to
+    /// $u^+ = \Pi_U(u - \gamma \nabla f(u))$, while the step size $\gamma$
+    /// is still adapted using the estimate of the Lipschitz constant; the
+    /// L-BFGS buffer is neither updated nor applied. This is useful for
+    /// debugging, for tiny problems and for comparisons with the
+    /// forward-backward splitting method. This is equivalent to
+    /// `with_max_lbfgs_applications(0)`.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn without_lbfgs(self) -> Self {
+        self.with_max_lbfgs_applications(0)
+    }
+
+    /// Overrides the numerical constants of the solver
+    ///
+    /// By default, the constants used in the estimation of the Lipschitz
+    /// constant and in the choice of the step size are those of the float type
+    /// (see [OptFloat](../opt_float/trait.OptFloat.html)). The constants which
+    /// are set in `params` are used instead.
+    ///
+    /// ## Arguments
+    ///
+    /// - `params`: overrides of the numerical constants
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_solver_params(mut self, params: SolverParams<T>) -> Self {
+        self.panoc_engine.params = params;
+        self
+    }
+
+    /// Sets the perturbation used in the estimation of the initial Lipschitz
+    /// constant
+    ///
+    /// The initial Lipschitz constant is estimated by finite differences of the
+    /// gradient along a direction $h$ with $h_i = \max\\{\delta, \epsilon|u_i|\\}$
+    /// (see [LipschitzEstimator](../../lipschitz_estimator/struct.LipschitzEstimator.html)).
+    /// This overrides `delta_lipschitz` and `epsilon_lipschitz` in the solver
+    /// parameters (see `with_solver_params`).
+    ///
+    /// ## Arguments
+    ///
+    /// - `delta`: absolute perturbation, $\delta$
+    /// - `epsilon`: relative perturbation, $\epsilon$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `delta` or `epsilon` is not positive
+    ///
+    pub fn with_lipschitz_perturbation(mut self, delta: T, epsilon: T) -> Self {
+        self.panoc_engine.params = self
+            .panoc_engine
+            .params
+            .with_delta_lipschitz(delta)
+            .with_epsilon_lipschitz(epsilon);
+        self
+    }
+
+    /// Sets a callback which is invoked once per iteration
+    ///
+    /// The callback receives a [PanocProgress](struct.PanocProgress.html), which
+    /// contains the iteration count, the norm of gamma*FPR, the cost value, gamma
+    /// and tau. If the callback returns `false`, the solver stops and reports
+    /// the exit status `NotConvergedIterations`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `callback`: the callback, which is taken by value and may borrow data
+    ///   from its environment (it need not be `'static`)
+    ///
+    /// ## Returns
+    ///
+    /// Returns the current mutable and updated instance of the provided object
+    ///
+    pub fn with_inner_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&PanocProgress<T>) -> bool + 'c,
+    {
+        self.inner_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns an iterator over the iterations of PANOC
+    ///
+    /// Every call to `next` performs one iteration and yields its
+    /// [PanocProgress](struct.PanocProgress.html), so that the iterations can
+    /// be interleaved with other computations. The iterator ends when the
+    /// solver converges or when the maximum number of iterations is reached;
+    /// then, the solution is copied into `u`. The maximum duration and the
+    /// callbacks of the optimizer are not used.
+    ///
+    /// ## Arguments
+    ///
+    /// - `u`: on entry, the initial guess; once the iterator has ended, the
+    ///   solution
+    ///
+    pub fn iter<'b>(
+        &'b mut self,
+        u: &'b mut [T],
+    ) -> PanocIterator<'a, 'b, GradientType, ConstraintType, CostType, T> {
+        PanocIterator::new(&mut self.panoc_engine, u, self.max_iter)
+    }
+
+    /// Invokes the iteration callback and the given callback, if any, and
+    /// returns whether the iterations should continue
+    fn invoke_inner_callback(&mut self, callback: &mut Option<SolveCallback<'_, T>>) -> bool {
+        let cache = &self.panoc_engine.cache;
+        let continue_inner = if let Some(inner_callback) = &mut self.inner_callback {
+            inner_callback(&PanocProgress::from_cache(cache))
+        } else {
+            true
+        };
+        let continue_solve = if let Some(callback) = callback {
+            callback(cache.iteration, cache.norm_gamma_fpr, cache.cost_value)
+        } else {
+            true
+        };
+        continue_inner && continue_solve
+    }
+
+    /// Solves the problem, invoking the callback (if any) once per iteration
+    fn solve_with_optional_callback(
+        &mut self,
+        u: &mut [T],
+        mut callback: Option<SolveCallback<'_, T>>,
+    ) -> Result<SolverStatus<T>, SolverError> {
+        let now = Stopwatch::start(self.clock);
 
         /*
          * Initialise [call panoc_engine.init()]
@@ -138,6 +744,7 @@ where
         let mut num_iter: usize = 0;
         let mut continue_num_iters = true;
         let mut continue_runtime = true;
+        let mut continue_callback = true;
 
         let mut step_flag = self.panoc_engine.step(u)?;
         if let Some(dur) = self.max_duration {
@@ -145,23 +752,38 @@ where
                 num_iter += 1;
                 continue_num_iters = num_iter < self.max_iter;
                 continue_runtime = now.elapsed() <= dur;
+                continue_callback = self.invoke_inner_callback(&mut callback);
+                if !continue_callback {
+                    break;
+                }
                 step_flag = self.panoc_engine.step(u)?;
             }
         } else {
             while step_flag && continue_num_iters {
                 num_iter += 1;
                 continue_num_iters = num_iter < self.max_iter;
+                continue_callback = self.invoke_inner_callback(&mut callback);
+                if !continue_callback {
+                    break;
+                }
                 step_flag = self.panoc_engine.step(u)?;
             }
         }
 
         // check for possible NaN/inf
-        if !matrix_operations::is_finite(u) {
-            return Err(SolverError::NotFiniteComputation);
+        if let Some(index) = matrix_operations::first_non_finite(u) {
+            return Err(SolverError::NotFiniteSolution {
+                index,
+                iteration: num_iter,
+            });
         }
 
         // exit status
-        let exit_status = if !continue_num_iters {
+        let exit_status = if self.panoc_engine.cache.is_stalled() {
+            ExitStatus::Stalled
+        } else if self.panoc_engine.cache.linesearch_failed {
+            ExitStatus::LineSearchFailed
+        } else if !continue_num_iters || !continue_callback {
             ExitStatus::NotConvergedIterations
         } else if !continue_runtime {
             ExitStatus::NotConvergedOutOfTime
@@ -171,19 +793,67 @@ where
 
         // copy u_half_step into u (the algorithm should return u_bar,
         // because it's always feasible, while u may violate the constraints)
-        u.copy_from_slice(&self.panoc_engine.cache.u_half_step);
+        let cache = &self.panoc_engine.cache;
+        u.copy_from_slice(&cache.u_half_step);
+        let mut norm_gamma_fpr = cache.norm_gamma_fpr;
+        let mut cost_value = cache.cost_value;
+
+        // if the solver did not converge, return the best iterate instead
+        // (if activated)
+        if let Some(best) = &cache.best_iterate {
+            if exit_status != ExitStatus::Converged && best.recorded {
+                u.copy_from_slice(&best.u);
+                norm_gamma_fpr = best.norm_gamma_fpr;
+                cost_value = best.cost_value;
+            }
+        }
 
         // export solution status (exit status, num iterations and more)
         Ok(SolverStatus::new(
             exit_status,
             num_iter,
             now.elapsed(),
-            self.panoc_engine.cache.norm_gamma_fpr,
-            self.panoc_engine.cache.cost_value,
+            norm_gamma_fpr,
+            cost_value,
+            Some(self.panoc_engine.cache.lipschitz_constant),
+            self.panoc_engine.cache.gamma,
+        )
+        .with_evaluations(
+            self.panoc_engine.cache.num_cost_evals,
+            self.panoc_engine.cache.num_grad_evals,
         ))
     }
 }
 
+impl<'life, 'c, GradientType, ConstraintType, CostType, T> Optimizer<T>
+    for PANOCOptimizer<'life, 'c, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'life,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T> + 'life,
+    T: OptFloat,
+{
+    fn solve(&mut self, u: &mut [T]) -> Result<SolverStatus<T>, SolverError> {
+        self.solve_with_optional_callback(u, None)
+    }
+
+    fn solve_with_callback(
+        &mut self,
+        u: &mut [T],
+        callback: &mut dyn FnMut(usize, T, T) -> bool,
+    ) -> Result<SolverStatus<T>, SolverError> {
+        self.solve_with_optional_callback(u, Some(callback))
+    }
+
+    fn max_iter(&self) -> usize {
+        self.max_iter
+    }
+
+    fn set_max_iter(&mut self, max_iter: usize) {
+        PANOCOptimizer::set_max_iter(self, max_iter);
+    }
+}
+
 /* --------------------------------------------------------------------------------------------- */
 /*       TESTS                                                                                   */
 /* --------------------------------------------------------------------------------------------- */
@@ -338,4 +1008,298 @@ mod tests {
         assert!(status.iterations() < max_iters);
         assert!(status.norm_fpr() < tolerance);
     }
+
+    #[test]
+    fn t_panoc_optimizer_akkt_tolerance_changes_iterations() {
+        let (a_param, b_param) = (1.0, 200.0);
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 1.2);
+        let (tolerance, akkt_tolerance) = (1e-3, 1e-10);
+
+        let mut cache_fpr = PANOCCache::new(2, tolerance, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut u_fpr = [-1.5, 0.9];
+        let status_fpr = PANOCOptimizer::new(problem, &mut cache_fpr)
+            .with_max_iter(500)
+            .solve(&mut u_fpr)
+            .unwrap();
+
+        let mut cache_akkt = PANOCCache::new(2, tolerance, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut u_akkt = [-1.5, 0.9];
+        let status_akkt = PANOCOptimizer::new(problem, &mut cache_akkt)
+            .with_max_iter(500)
+            .with_akkt_tolerance(akkt_tolerance)
+            .solve(&mut u_akkt)
+            .unwrap();
+
+        assert!(status_fpr.has_converged() && status_akkt.has_converged());
+        assert!(status_akkt.iterations() > status_fpr.iterations());
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_panoc_optimizer_akkt_tolerance_not_positive() {
+        let bounds = constraints::Ball2::new(None, 1.2);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+        let _panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_akkt_tolerance(0.0);
+    }
+
+    #[test]
+    fn t_panoc_optimizer_callback_invocations() {
+        let (a_param, b_param) = (1.0, 200.0);
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut num_calls = 0;
+        let mut last_iteration = 0;
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(100)
+            .with_inner_callback(|progress: &PanocProgress<f64>| {
+                num_calls += 1;
+                last_iteration = progress.iteration();
+                assert!(progress.gamma() > 0.0);
+                true
+            });
+        let status = panoc.solve(&mut u_solution).unwrap();
+        drop(panoc);
+
+        assert!(status.has_converged());
+        assert_eq!(status.iterations(), num_calls);
+        assert_eq!(status.iterations(), last_iteration);
+    }
+
+    #[test]
+    fn t_panoc_optimizer_callback_early_stop() {
+        let (a_param, b_param) = (1.0, 200.0);
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(100)
+            .with_inner_callback(|progress: &PanocProgress<f64>| progress.iteration() < 5);
+        let status = panoc.solve(&mut u_solution).unwrap();
+
+        assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+        assert_eq!(5, status.iterations());
+    }
+
+    #[test]
+    fn t_panoc_optimizer_solve_with_callback() {
+        let (a_param, b_param) = (1.0, 200.0);
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+
+        // the optimizer is only accessed through the trait `Optimizer`
+        let optimizer: &mut dyn Optimizer<f64> = &mut panoc;
+        let mut iterations = vec![];
+        let mut costs = vec![];
+        let status = optimizer
+            .solve_with_callback(&mut u_solution, &mut |iteration, norm_fpr, cost| {
+                assert!(norm_fpr.is_finite());
+                iterations.push(iteration);
+                costs.push(cost);
+                iteration < 4
+            })
+            .unwrap();
+
+        assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+        assert_eq!(4, status.iterations());
+        assert_eq!(vec![1, 2, 3, 4], iterations);
+        assert!(costs.iter().all(|cost| cost.is_finite()));
+    }
+
+    #[test]
+    fn t_panoc_optimizer_out_of_time() {
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(1.0, 200.0, u, grad);
+            Ok(())
+        };
+        let slow_cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            *c = mocks::rosenbrock_cost(1.0, 200.0, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-12, 8);
+        let problem = Problem::new(&bounds, cost_gradient, slow_cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(1000)
+            .with_max_duration(std::time::Duration::from_millis(5));
+        let status = panoc.solve(&mut u_solution).unwrap();
+
+        assert_eq!(ExitStatus::NotConvergedOutOfTime, status.exit_status());
+        assert!(status.iterations() < 1000);
+    }
+
+    /// A clock which advances by one millisecond every time it is read
+    struct TickingClock {
+        ticks: std::cell::Cell<u64>,
+    }
+
+    impl Clock for TickingClock {
+        fn now(&self) -> std::time::Duration {
+            self.ticks.set(self.ticks.get() + 1);
+            std::time::Duration::from_millis(self.ticks.get())
+        }
+    }
+
+    #[test]
+    fn t_panoc_optimizer_out_of_time_clock() {
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(1.0, 200.0, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(1.0, 200.0, u);
+            Ok(())
+        };
+        let clock = TickingClock {
+            ticks: std::cell::Cell::new(0),
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-12, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(1000)
+            .with_max_duration(std::time::Duration::from_millis(5))
+            .with_clock(&clock);
+        let status = panoc.solve(&mut u_solution).unwrap();
+
+        // the clock is read once at the start and once per iteration
+        assert_eq!(ExitStatus::NotConvergedOutOfTime, status.exit_status());
+        assert_eq!(6, status.iterations());
+        assert_eq!(std::time::Duration::from_millis(7), status.solve_time());
+    }
+
+    fn rosenbrock_iterations(max_lbfgs_applications: Option<usize>) -> usize {
+        let (a_param, b_param) = (1.0, 200.0);
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100_000);
+        if let Some(max_lbfgs_applications) = max_lbfgs_applications {
+            panoc = panoc.with_max_lbfgs_applications(max_lbfgs_applications);
+        }
+        let status = panoc.solve(&mut u_solution).unwrap();
+        assert!(status.has_converged());
+        assert!(panoc_cache.num_lbfgs_applications <= max_lbfgs_applications.unwrap_or(usize::MAX));
+        status.iterations()
+    }
+
+    #[test]
+    fn t_panoc_optimizer_max_lbfgs_applications() {
+        let iterations: Vec<usize> = [Some(0), Some(10), Some(30), None]
+            .iter()
+            .map(|&cap| rosenbrock_iterations(cap))
+            .collect();
+        println!("iterations (caps: 0, 10, 30, none) = {:?}", iterations);
+        assert!(iterations.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    #[test]
+    fn t_panoc_optimizer_status_lipschitz_gamma() {
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(1.0, 200.0, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(1.0, 200.0, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .solve(&mut u_solution)
+            .unwrap();
+
+        assert_eq!(
+            Some(panoc_cache.lipschitz_constant),
+            status.lipschitz_estimate()
+        );
+        assert_eq!(panoc_cache.gamma, status.final_gamma());
+        assert!(status.final_gamma() > 0.0);
+    }
+
+    #[test]
+    fn t_panoc_optimizer_history() {
+        let (a_param, b_param) = (1.0, 200.0);
+        let mut u_solution = [-1.5, 0.9];
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 2.0);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        assert!(panoc_cache.history().is_none());
+        panoc_cache.enable_history(100);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+        let status = panoc.solve(&mut u_solution).unwrap();
+
+        assert!(status.has_converged());
+        let history = panoc_cache.history().unwrap();
+        assert_eq!(status.iterations(), history.len());
+        assert_eq!(history.len(), history.norm_gamma_fprs().len());
+        assert_eq!(history.len(), history.gammas().len());
+        assert!(history.gammas().iter().all(|&gamma| gamma > 0.0));
+        assert!(history.akkt_residuals().is_empty());
+
+        panoc_cache.reset();
+        assert!(panoc_cache.history().unwrap().is_empty());
+    }
 }
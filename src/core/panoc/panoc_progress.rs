@@ -0,0 +1,68 @@
+//! Progress of PANOC iterations
+//!
+use crate::core::panoc::PANOCCache;
+use crate::core::OptFloat;
+
+/// Snapshot of the state of PANOC after an iteration
+///
+/// Instances of `PanocProgress` are passed to the iteration callback of
+/// [PANOCOptimizer](struct.PANOCOptimizer.html) (see `with_inner_callback`)
+///
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct PanocProgress<T>
+where
+    T: OptFloat,
+{
+    /// iteration count
+    iteration: usize,
+    /// norm of gamma times the fixed-point residual
+    norm_gamma_fpr: T,
+    /// cost value
+    cost_value: T,
+    /// step size, gamma
+    gamma: T,
+    /// line search parameter, tau
+    tau: T,
+}
+
+impl<T> PanocProgress<T>
+where
+    T: OptFloat,
+{
+    /// Constructs a new instance of `PanocProgress` from the current state
+    /// of a given cache
+    pub(crate) fn from_cache(cache: &PANOCCache<T>) -> Self {
+        PanocProgress {
+            iteration: cache.iteration,
+            norm_gamma_fpr: cache.norm_gamma_fpr,
+            cost_value: cache.cost_value,
+            gamma: cache.gamma,
+            tau: cache.tau,
+        }
+    }
+
+    /// number of iterations performed so far
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// norm of gamma times the fixed-point residual
+    pub fn norm_gamma_fpr(&self) -> T {
+        self.norm_gamma_fpr
+    }
+
+    /// current value of the cost
+    pub fn cost_value(&self) -> T {
+        self.cost_value
+    }
+
+    /// current step size, gamma
+    pub fn gamma(&self) -> T {
+        self.gamma
+    }
+
+    /// current value of the line search parameter, tau
+    pub fn tau(&self) -> T {
+        self.tau
+    }
+}
@@ -0,0 +1,200 @@
+//! Solution of a sequence of problems with PANOC
+//!
+use alloc::vec::Vec;
+
+use crate::core::panoc::{PANOCCache, PANOCOptimizer};
+use crate::core::{OptFloat, Optimizer, Problem, SolverStatus};
+use crate::{constraints, FunctionCallResult, SolverError};
+
+/// Policy that determines how the state is carried over from one problem
+/// of a sequence to the next (see [solve_sequence](fn.solve_sequence.html))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetPolicy {
+    /// The cache is reset before every problem (as in every solve without the
+    /// LBFGS warm start) and every problem is solved starting from its own
+    /// initial guess
+    FullReset,
+    /// Every problem, except for the first one, is solved starting from the
+    /// solution of the previous problem; the provided initial guess of that
+    /// problem is overwritten and the LBFGS buffer of the cache is preserved
+    /// (see `PANOCCache::soft_reset`)
+    ///
+    /// Only the initial guess and the LBFGS buffer are carried over: the rest
+    /// of the state of the solver (e.g., the estimate of the Lipschitz
+    /// constant, the step size and the tolerance) is initialized anew for
+    /// every problem.
+    WarmStart,
+}
+
+/// Solves a sequence of problems of the same dimension, one after the other,
+/// using the same cache
+///
+/// This is meant for batch (offline) computations, where a large number of
+/// problems needs to be solved. The problems are taken from `problems` one at
+/// a time, as they are solved, and they all share the same `cache`, so no
+/// memory is allocated for the solution of each problem; the only allocation
+/// is that of the vector of solver statuses which is returned.
+///
+/// ## Arguments
+///
+/// - `cache`: an instance of `PANOCCache` (constructed once) whose dimension
+///   matches that of all problems
+/// - `problems`: the problems to be solved
+/// - `initial_guesses`: on entry, the initial guesses of the problems; on exit,
+///   the corresponding solutions
+/// - `reset_policy`: how the state is carried over from one problem to the next
+/// - `max_iter`: maximum number of iterations for every problem
+///
+/// ## Returns
+///
+/// Returns the solver statuses of all problems, or the first error that was
+/// encountered, in which case the subsequent problems are not solved
+///
+/// ## Panics
+///
+/// The method panics if the number of problems is not equal to the number of
+/// initial guesses, or if `max_iter` is zero. Since the problems are taken
+/// lazily, a mismatch is detected once all problems (if there are fewer
+/// problems than initial guesses) or all initial guesses (otherwise) have
+/// been used, that is, after the preceding problems have been solved.
+///
+pub fn solve_sequence<'a, GradientType, ConstraintType, CostType, T, I>(
+    cache: &mut PANOCCache<T>,
+    problems: I,
+    initial_guesses: &mut [Vec<T>],
+    reset_policy: ResetPolicy,
+    max_iter: usize,
+) -> Result<Vec<SolverStatus<T>>, SolverError>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult + 'a,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T> + 'a,
+    T: OptFloat,
+    I: IntoIterator<Item = Problem<'a, GradientType, ConstraintType, CostType, T>>,
+{
+    const WRONG_NUMBER: &str = "the number of problems and initial guesses differ";
+    let mut problems = problems.into_iter();
+    let mut statuses = Vec::with_capacity(initial_guesses.len());
+    for k in 0..initial_guesses.len() {
+        let problem = problems.next().expect(WRONG_NUMBER);
+        // the cache is reset by the optimizer, unless the warm start is active
+        let mut optimizer = PANOCOptimizer::new(problem, &mut *cache).with_max_iter(max_iter);
+        if reset_policy == ResetPolicy::WarmStart && k > 0 {
+            let (previous, next) = initial_guesses.split_at_mut(k);
+            next[0].copy_from_slice(&previous[k - 1]);
+            optimizer = optimizer.with_lbfgs_warm_start();
+        }
+        statuses.push(optimizer.solve(&mut initial_guesses[k])?);
+    }
+    assert!(problems.next().is_none(), "{}", WRONG_NUMBER);
+    Ok(statuses)
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use crate::core::constraints::*;
+    use crate::core::panoc::*;
+    use crate::core::*;
+    use crate::{mocks, FunctionCallResult};
+
+    type RosenbrockProblem<'a> = Problem<
+        'a,
+        Box<dyn Fn(&[f64], &mut [f64]) -> FunctionCallResult>,
+        Ball2<'static, f64>,
+        Box<dyn Fn(&[f64], &mut f64) -> FunctionCallResult>,
+        f64,
+    >;
+
+    fn rosenbrock_problem<'a>(
+        bounds: &'a Ball2<'static, f64>,
+        a_param: f64,
+        b_param: f64,
+    ) -> RosenbrockProblem<'a> {
+        let cost_gradient = move |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a_param, b_param, u, grad);
+            Ok(())
+        };
+        let cost_function = move |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a_param, b_param, u);
+            Ok(())
+        };
+        Problem::new(bounds, Box::new(cost_gradient), Box::new(cost_function))
+    }
+
+    fn solve_three_problems(reset_policy: ResetPolicy) {
+        let tolerance = 1e-6;
+        let bounds = Ball2::new(None, 1.5);
+        let mut panoc_cache = PANOCCache::new(2, tolerance, 8);
+        let problems = vec![
+            rosenbrock_problem(&bounds, 1.0, 100.0),
+            rosenbrock_problem(&bounds, 0.8, 150.0),
+            rosenbrock_problem(&bounds, 1.2, 50.0),
+        ];
+        let mut initial_guesses = vec![vec![-1.5, 0.9], vec![0.5, 0.5], vec![-1.0, -1.0]];
+
+        let statuses = solve_sequence(
+            &mut panoc_cache,
+            problems,
+            &mut initial_guesses,
+            reset_policy,
+            500,
+        )
+        .unwrap();
+
+        assert_eq!(3, statuses.len());
+        for (status, u) in statuses.iter().zip(initial_guesses.iter()) {
+            assert!(status.has_converged());
+            assert!(status.norm_fpr() < tolerance);
+            assert!(crate::matrix_operations::norm2(u) <= 1.5 + 1e-12);
+        }
+    }
+
+    #[test]
+    fn t_solve_sequence_full_reset() {
+        solve_three_problems(ResetPolicy::FullReset);
+    }
+
+    #[test]
+    fn t_solve_sequence_warm_start() {
+        solve_three_problems(ResetPolicy::WarmStart);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_solve_sequence_wrong_number_of_guesses() {
+        let bounds = Ball2::new(None, 1.5);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problems = vec![rosenbrock_problem(&bounds, 1.0, 100.0)];
+        let mut initial_guesses = vec![vec![0.0; 2], vec![0.0; 2]];
+        let _ = solve_sequence(
+            &mut panoc_cache,
+            problems,
+            &mut initial_guesses,
+            ResetPolicy::FullReset,
+            100,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_solve_sequence_too_many_problems() {
+        let bounds = Ball2::new(None, 1.5);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problems = vec![
+            rosenbrock_problem(&bounds, 1.0, 100.0),
+            rosenbrock_problem(&bounds, 1.0, 100.0),
+        ];
+        let mut initial_guesses = vec![vec![0.0; 2]];
+        let _ = solve_sequence(
+            &mut panoc_cache,
+            problems,
+            &mut initial_guesses,
+            ResetPolicy::FullReset,
+            100,
+        );
+    }
+}
@@ -1,7 +1,10 @@
+use crate::core::fbs::{FBSCache, FBSOptimizer};
 use crate::core::panoc::panoc_engine::PANOCEngine;
 use crate::core::panoc::*;
 use crate::core::*;
-use crate::{mocks, FunctionCallResult};
+use crate::{mocks, FunctionCallResult, SolverError};
+
+use std::num::NonZeroUsize;
 
 const N_DIM: usize = 2;
 #[test]
@@ -15,8 +18,9 @@ fn t_panoc_init() {
         let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
         let mut u = [0.75, -1.4];
         panoc_engine.init(&mut u).unwrap();
-        assert!(2.549_509_967_743_775 > panoc_engine.cache.lipschitz_constant);
-        assert!(0.372_620_625_931_781 < panoc_engine.cache.gamma, "gamma");
+        // the Lipschitz constant of the gradient is (3 + sqrt(5))/2
+        assert!(2.618_033_988_749_895 > panoc_engine.cache.lipschitz_constant);
+        assert!(0.362_868_004_775_302 < panoc_engine.cache.gamma, "gamma");
         println!("----------- {} ", panoc_engine.cache.cost_value);
         unit_test_utils::assert_nearly_equal(
             6.34125,
@@ -220,3 +224,1171 @@ fn t_zero_gamma_huber() {
     println!("iters = {}", panoc_cache.iteration);
     assert!(panoc_cache.norm_gamma_fpr <= tolerance);
 }
+
+#[test]
+fn t_panoc_cache_soft_reset() {
+    let tolerance = 1e-6;
+    let mut panoc_cache = PANOCCache::new(2, tolerance, 5);
+    let mut u = [-1.5, 0.9];
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(10);
+    let _status = panoc.solve(&mut u).unwrap();
+
+    // apply the LBFGS operator to a vector (this is the identity iff the buffer is empty)
+    let apply_lbfgs = |cache: &mut PANOCCache<f64>| {
+        let mut d = [1.0, -2.0];
+        cache.lbfgs.apply_hessian(&mut d);
+        d
+    };
+    let lbfgs_before = apply_lbfgs(&mut panoc_cache);
+    assert_ne!([1.0, -2.0], lbfgs_before);
+
+    panoc_cache.soft_reset();
+    assert_eq!(0, panoc_cache.iteration);
+    assert_eq!(1.0, panoc_cache.tau);
+    assert_eq!(0.0, panoc_cache.gamma);
+    assert_eq!(0.0, panoc_cache.cost_value);
+    unit_test_utils::assert_nearly_equal_array(
+        &lbfgs_before,
+        &apply_lbfgs(&mut panoc_cache),
+        1e-12,
+        1e-14,
+        "LBFGS buffer modified by soft_reset",
+    );
+
+    panoc_cache.reset();
+    assert_eq!([1.0, -2.0], apply_lbfgs(&mut panoc_cache));
+}
+
+#[test]
+fn t_panoc_reconfigure() {
+    let mut panoc_cache = PANOCCache::new(2, 1e-3, 5);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_lbfgs_warm_start();
+
+    let mut u = [-1.5, 0.9];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(status.has_converged());
+    assert!(status.norm_fpr() < 1e-3);
+    assert!(panoc.is_warm_start_active());
+
+    // the cache is reset, so the warm start is not active before the next solve
+    panoc
+        .reconfigure()
+        .set_tolerance(1e-10)
+        .set_max_iter(3)
+        .set_lbfgs_warm_start(false);
+    assert!(!panoc.is_warm_start_active());
+    let mut u = [-1.5, 0.9];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(!status.has_converged());
+    assert_eq!(3, status.iterations());
+
+    panoc.reconfigure().set_max_iter(1000);
+    let mut u = [-1.5, 0.9];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(status.has_converged());
+    assert!(status.norm_fpr() < 1e-10);
+    assert!(!panoc.is_warm_start_active());
+}
+
+#[test]
+fn t_panoc_warm_start_active() {
+    let tolerance = 1e-6;
+    let mut panoc_cache = PANOCCache::new(2, tolerance, 5);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    assert!(!panoc_cache.cache_is_initialized());
+
+    // without warm start, the cache is initialized, but not reused
+    let mut u = [-1.5, 0.9];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(10);
+    let _status = panoc.solve(&mut u).unwrap();
+    assert!(!panoc.is_warm_start_active());
+    assert!(panoc_cache.cache_is_initialized());
+
+    panoc_cache.reset();
+    assert!(!panoc_cache.cache_is_initialized());
+    panoc_cache.soft_reset();
+    assert!(!panoc_cache.cache_is_initialized());
+
+    // with warm start, the cache is reused after the first solve
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(10)
+        .with_lbfgs_warm_start();
+    assert!(!panoc.is_warm_start_active());
+    let _status = panoc.solve(&mut u).unwrap();
+    assert!(panoc.is_warm_start_active());
+    let _status = panoc.solve(&mut u).unwrap();
+    assert!(panoc.is_warm_start_active());
+
+    panoc_cache.reset();
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_lbfgs_warm_start();
+    assert!(!panoc.is_warm_start_active());
+}
+
+/// Solves the Rosenbrock problem with the given CBFGS parameters and a detailed
+/// log of rejected L-BFGS updates; returns the cache
+fn rosenbrock_lbfgs_rejections(alpha: f64, epsilon: f64, sy_epsilon: f64) -> PANOCCache<f64> {
+    let mut panoc_cache =
+        PANOCCache::new(2, 1e-8, 5).with_cbfgs_parameters(alpha, epsilon, sy_epsilon);
+    panoc_cache.enable_lbfgs_rejection_log(100);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 0.9];
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+    let _status = panoc.solve(&mut u).unwrap();
+    panoc_cache
+}
+
+#[test]
+fn t_panoc_lbfgs_acceptances() {
+    // default parameters: most updates are accepted
+    let cache = rosenbrock_lbfgs_rejections(1.0, 1e-8, 1e-10);
+    assert!(cache.lbfgs_acceptances() > cache.lbfgs_rejections());
+
+    // a large sy_epsilon: all updates are rejected, except for the first
+    // one, which only stores the initial state in the buffer
+    let cache = rosenbrock_lbfgs_rejections(1.0, 1e-8, 1e3);
+    assert!(cache.lbfgs_rejections() > 0);
+    assert_eq!(1, cache.lbfgs_acceptances());
+}
+
+#[test]
+fn t_panoc_lbfgs_rejection_log() {
+    // default parameters
+    let cache = rosenbrock_lbfgs_rejections(1.0, 1e-8, 1e-10);
+    assert_eq!(1.0, cache.cbfgs_alpha());
+    assert_eq!(1e-8, cache.cbfgs_epsilon());
+    assert_eq!(1e-10, cache.sy_epsilon());
+    let log = cache.lbfgs_rejection_log().unwrap();
+    assert_eq!(cache.lbfgs_rejections(), log.rejections().len());
+    let num_curvature_default = log.count(LbfgsRejectionReason::Curvature);
+
+    // a large sy_epsilon: all rejections are due to the curvature test
+    let cache = rosenbrock_lbfgs_rejections(1.0, 1e-8, 1e3);
+    let log = cache.lbfgs_rejection_log().unwrap();
+    assert!(cache.lbfgs_rejections() > 0);
+    assert_eq!(0, log.count(LbfgsRejectionReason::Cbfgs));
+    assert!(log.count(LbfgsRejectionReason::Curvature) > num_curvature_default);
+    assert!(log
+        .rejections()
+        .windows(2)
+        .all(|w| w[0].iteration() < w[1].iteration()));
+
+    // a large CBFGS epsilon and no sy_epsilon: rejections are due to the CBFGS test
+    let cache = rosenbrock_lbfgs_rejections(1.0, 1e6, 0.0);
+    let log = cache.lbfgs_rejection_log().unwrap();
+    assert_eq!(1e6, cache.cbfgs_epsilon());
+    assert!(log.count(LbfgsRejectionReason::Cbfgs) > 0);
+    assert_eq!(
+        cache.lbfgs_rejections(),
+        log.count(LbfgsRejectionReason::Cbfgs) + log.count(LbfgsRejectionReason::Curvature)
+    );
+}
+
+#[test]
+fn t_panoc_not_finite_solution() {
+    // the gradient is NaN in the second coordinate once u[0] exceeds 0.5; L-BFGS
+    // is deactivated, so that the NaN does not spread to other coordinates
+    let gradient_calls = core::cell::Cell::new(0);
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = 0.5 * crate::matrix_operations::norm2_squared_diff(u, &[1.0, 1.0, 1.0]);
+        Ok(())
+    };
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        gradient_calls.set(gradient_calls.get() + 1);
+        grad.iter_mut()
+            .zip(u.iter())
+            .for_each(|(g, &ui)| *g = ui - 1.0);
+        if u[0] > 0.5 {
+            grad[1] = f64::NAN;
+        }
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(3, 1e-8, 5);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(50)
+        .with_max_lbfgs_applications(0);
+    let mut u = [0.0; 3];
+    let result = panoc.solve(&mut u);
+
+    // the NaN is caught at the iteration where it first appears rather than
+    // after all 50 iterations have been performed
+    assert_eq!(
+        Err(SolverError::NotFiniteSolution {
+            index: 1,
+            iteration: 2
+        }),
+        result
+    );
+    assert!(gradient_calls.get() < 10);
+}
+
+#[test]
+fn t_panoc_keep_best() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    // norm of the fixed-point residual of projected gradient with a fixed step
+    let residual = |u: &[f64]| {
+        let mut grad = [0.0; 2];
+        mocks::rosenbrock_grad(a, b, u, &mut grad);
+        let mut u_fb = [u[0] - 1e-3 * grad[0], u[1] - 1e-3 * grad[1]];
+        constraints::Constraint::project(&bounds, &mut u_fb);
+        crate::matrix_operations::norm_inf_diff(u, &u_fb)
+    };
+
+    let mut u_last = [-1.5, 0.9];
+    let mut cache = PANOCCache::new(2, 1e-12, 8);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status_last = PANOCOptimizer::new(problem, &mut cache)
+        .with_max_iter(9)
+        .solve(&mut u_last)
+        .unwrap();
+
+    let mut u_best = [-1.5, 0.9];
+    let mut cache = PANOCCache::new(2, 1e-12, 8);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status_best = PANOCOptimizer::new(problem, &mut cache)
+        .with_max_iter(9)
+        .keep_best(BestIterateCriterion::NormGammaFpr)
+        .solve(&mut u_best)
+        .unwrap();
+
+    // the last iterate is worse than the best one, both in terms of the
+    // reported norm of gamma*FPR and in terms of an independent residual
+    assert_eq!(
+        ExitStatus::NotConvergedIterations,
+        status_best.exit_status()
+    );
+    assert_eq!(status_last.iterations(), status_best.iterations());
+    assert!(status_best.norm_fpr() < 0.2 * status_last.norm_fpr());
+    assert!(residual(&u_best) < residual(&u_last));
+
+    // with the cost criterion, the reported cost is the cost of the returned
+    // iterate
+    let mut u_best = [-1.5, 0.9];
+    let mut cache = PANOCCache::new(2, 1e-12, 8);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status_best = PANOCOptimizer::new(problem, &mut cache)
+        .with_max_iter(9)
+        .keep_best(BestIterateCriterion::CostValue)
+        .solve(&mut u_best)
+        .unwrap();
+    assert!(!status_best.has_converged());
+    unit_test_utils::assert_nearly_equal(
+        mocks::rosenbrock_cost(a, b, &u_best),
+        status_best.cost_value(),
+        1e-12,
+        1e-14,
+        "cost of the best iterate",
+    );
+}
+
+#[test]
+fn t_panoc_relative_tolerance() {
+    // Rosenbrock function in the variable u/s, with a large scale s; the
+    // spacing of floats near the solution, (s, s), is about 2e-9, so the
+    // absolute tolerance cannot be met (except at an exact fixed point)
+    let (a, b, s) = (1.0, 100.0, 1e7);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, &[u[0] / s, u[1] / s], grad);
+        grad.iter_mut().for_each(|g| *g /= s);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, &[u[0] / s, u[1] / s]);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let (tolerance, relative_tolerance, max_iter) = (1e-12, 1e-8, 100);
+
+    let mut cache = PANOCCache::new(2, tolerance, 8);
+    cache.set_relative_tolerance(relative_tolerance);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.2 * s, s];
+    let status = PANOCOptimizer::new(problem, &mut cache)
+        .with_max_iter(max_iter)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    assert!(status.norm_fpr() > tolerance);
+    assert!(status.norm_fpr() < relative_tolerance * (1.0 + cache.norm_gamma_fpr_initial));
+    unit_test_utils::assert_nearly_equal_array(&[s, s], &u, 1e-6, 1e-12, "u");
+
+    // with the absolute tolerance only, the solver does not converge within
+    // the same number of iterations
+    let mut cache = PANOCCache::new(2, tolerance, 8);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.2 * s, s];
+    let status_absolute = PANOCOptimizer::new(problem, &mut cache)
+        .with_max_iter(status.iterations())
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(
+        ExitStatus::NotConvergedIterations,
+        status_absolute.exit_status()
+    );
+}
+
+#[test]
+fn t_panoc_without_lbfgs_matches_fbs() {
+    let bounds = constraints::Ball2::new(None, 0.2);
+    for max_iter in 1..=6 {
+        let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+        let mut panoc_cache = PANOCCache::new(N_DIM, 1e-14, 5);
+        panoc_cache.enable_history(max_iter);
+        let mut u_panoc = [1.0, -1.0];
+        PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(max_iter)
+            .without_lbfgs()
+            .solve(&mut u_panoc)
+            .unwrap();
+        assert_eq!(0, panoc_cache.num_lbfgs_applications);
+        assert_eq!(0.0, panoc_cache.tau);
+
+        // on this quadratic problem the step size is not updated, so PANOC
+        // performs the same iterations as FBS with this step size; since
+        // PANOC returns the half step, this is one more FBS iteration
+        let gamma = panoc_cache.gamma;
+        let history = panoc_cache.history().unwrap();
+        assert!(history.gammas().iter().all(|&gamma_k| gamma_k == gamma));
+
+        let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+        let mut fbs_cache = FBSCache::new(NonZeroUsize::new(N_DIM).unwrap(), gamma, 1e-14);
+        let mut u_fbs = [1.0, -1.0];
+        FBSOptimizer::new(problem, &mut fbs_cache)
+            .with_max_iter(max_iter + 1)
+            .solve(&mut u_fbs)
+            .unwrap();
+
+        // PANOC starts from a perturbation of the initial guess (see
+        // `lipschitz_estimator`)
+        unit_test_utils::assert_nearly_equal_array(&u_fbs, &u_panoc, 1e-8, 1e-8, "u");
+    }
+}
+
+#[test]
+fn t_panoc_iterator() {
+    let radius = 0.2;
+    let bounds = constraints::Ball2::new(None, radius);
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+    let mut u = [0.0; N_DIM];
+
+    let mut panoc_iterator = panoc.iter(&mut u);
+    let mut num_iter = 0;
+    let mut last_norm_gamma_fpr = f64::INFINITY;
+    for progress in panoc_iterator.by_ref() {
+        let progress = progress.unwrap();
+        num_iter += 1;
+        assert_eq!(num_iter, progress.iteration());
+        last_norm_gamma_fpr = progress.norm_gamma_fpr();
+    }
+    assert!(panoc_iterator.has_converged());
+    assert!(panoc_iterator.next().is_none());
+    assert!(num_iter > 0 && last_norm_gamma_fpr.is_finite());
+    unit_test_utils::assert_nearly_equal_array(&mocks::SOLUTION_A, &u, 1e-4, 1e-5, "u");
+
+    // the iterator performs the same iterations as `solve`
+    let mut u_solve = [0.0; N_DIM];
+    let status = panoc.solve(&mut u_solve).unwrap();
+    assert_eq!(num_iter, status.iterations());
+    assert_eq!(u_solve, u);
+}
+
+#[test]
+fn t_panoc_iterator_max_iter() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 2.0);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(2, 1e-12, 8);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(7);
+    let mut u = [-1.5, 0.9];
+
+    let mut panoc_iterator = panoc.iter(&mut u);
+    assert_eq!(7, panoc_iterator.by_ref().map(Result::unwrap).count());
+    assert!(!panoc_iterator.has_converged());
+}
+
+#[test]
+fn t_panoc_jacobi_preconditioner() {
+    let bounds = constraints::NoConstraints::new();
+    // Jacobi preconditioner: square roots of the diagonal of the Hessian
+    let jacobi = vec![2.0, 11f64.sqrt(), 1001f64.sqrt()];
+    let solve = |preconditioner: Option<Vec<f64>>| {
+        let problem = Problem::new(
+            &bounds,
+            mocks::hard_quadratic_gradient,
+            mocks::hard_quadratic_cost,
+        );
+        let mut panoc_cache = PANOCCache::new(3, 1e-10, 10);
+        if let Some(diag) = preconditioner {
+            panoc_cache = panoc_cache.with_preconditioner(diag);
+        }
+        let mut u = [-20., 10., 0.2];
+        let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+            .with_max_iter(500)
+            .solve(&mut u)
+            .unwrap();
+        assert!(status.has_converged());
+        (u, status.iterations())
+    };
+
+    let (u, iterations) = solve(None);
+    let (u_preconditioned, iterations_preconditioned) = solve(Some(jacobi));
+    assert!(iterations_preconditioned < iterations);
+    unit_test_utils::assert_nearly_equal_array(&u, &u_preconditioned, 1e-6, 1e-8, "u");
+
+    // the solution satisfies Q u + 1 = 0
+    let mut grad = [0.0; 3];
+    mocks::hard_quadratic_gradient(&u_preconditioned, &mut grad).unwrap();
+    assert!(crate::matrix_operations::norm_inf(&grad) < 1e-6);
+}
+
+#[test]
+fn t_panoc_preconditioner_not_separable() {
+    let bounds = constraints::Ball2::new(None, 1.0);
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-8, 5).with_preconditioner(vec![1.0, 2.0]);
+    let mut u = [0.5, 0.5];
+    let result = PANOCOptimizer::new(problem, &mut panoc_cache).solve(&mut u);
+    assert_eq!(Err(SolverError::InvalidProblem), result);
+
+    // a rectangle is separable
+    let (xmin, xmax) = ([-1.0, -1.0], [1.0, 1.0]);
+    let bounds = constraints::Rectangle::new(Some(&xmin), Some(&xmax));
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_preconditioner_not_positive() {
+    let _ = PANOCCache::<f64>::new(3, 1e-10, 10).with_preconditioner(vec![1.0, 0.0, 1.0]);
+}
+
+#[test]
+fn t_panoc_feasible_init() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let radius = 1.0;
+    let bounds = constraints::Ball2::new(None, radius);
+    let u_infeasible = [-30.0, 40.0];
+    let mut panoc_cache = PANOCCache::new(2, 1e-8, 8);
+
+    let mut u = u_infeasible;
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .solve(&mut u)
+        .unwrap();
+
+    let mut u_feasible_init = u_infeasible;
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status_feasible_init = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_feasible_init()
+        .solve(&mut u_feasible_init)
+        .unwrap();
+
+    // the engine starts from the projection of the initial guess
+    let mut u_init = u_infeasible;
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+    panoc_engine.feasible_init = true;
+    panoc_engine.init(&mut u_init).unwrap();
+    // (up to the perturbation of the estimation of the Lipschitz constant)
+    assert!(crate::matrix_operations::norm2(&u_init) <= radius + 1e-5);
+
+    assert!(status_feasible_init.has_converged());
+    assert!(status_feasible_init.iterations() < status.iterations());
+}
+
+#[test]
+fn t_panoc_cache_set_lbfgs_memory() {
+    let (a, b) = (1.0, 100.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 1.0);
+    let mut panoc_cache = PANOCCache::new(2, 1e-8, 3).with_cbfgs_parameters(1.0, 1e-8, 1e-12);
+    panoc_cache.set_lbfgs_memory(12);
+    assert_eq!(1.0, panoc_cache.cbfgs_alpha);
+
+    let mut u = [-1.5, 0.9];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+}
+
+#[test]
+fn t_panoc_cache_try_new() {
+    assert!(PANOCCache::try_new(5, 1e-6, 3).is_ok());
+    let invalid_arguments = [
+        (0, 1e-6, 3),
+        (5, 0.0, 3),
+        (5, -1e-6, 3),
+        (5, f64::NAN, 3),
+        (5, 1e-6, 0),
+    ];
+    for &(problem_size, tolerance, lbfgs_memory_size) in invalid_arguments.iter() {
+        assert_eq!(
+            Some(SolverError::InvalidArgument),
+            PANOCCache::try_new(problem_size, tolerance, lbfgs_memory_size).err()
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_cache_set_lbfgs_memory_zero() {
+    let mut panoc_cache = PANOCCache::<f64>::new(2, 1e-8, 3);
+    panoc_cache.set_lbfgs_memory(0);
+}
+
+#[test]
+fn t_panoc_stall_detection() {
+    // with this initial guess, the estimate of the Lipschitz constant is very
+    // large and PANOC stalls (see t_panoc_feasible_init)
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::Ball2::new(None, 1.0);
+    let mut panoc_cache = PANOCCache::new(2, 1e-8, 8);
+
+    let mut u = [-30.0, 40.0];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_stall_detection(10, 1e-4)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::Stalled, status.exit_status());
+    assert!(status.iterations() < 1000);
+
+    // stall detection does not affect a problem which converges
+    let mut u = [-30.0, 40.0];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_feasible_init()
+        .with_stall_detection(10, 1e-12)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::Converged, status.exit_status());
+}
+
+#[test]
+fn t_panoc_stall_detection_cost_increase() {
+    let (a, b) = (1.0, 100.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let mut panoc_cache = PANOCCache::new(2, 1e-10, 5);
+    panoc_cache.enable_history(100);
+
+    // the cost increases at some iteration of this (converging) run...
+    let mut u = [-1.2, 1.0];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(100)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    let costs = panoc_cache.history().unwrap().cost_values();
+    assert!(costs.windows(2).any(|c| c[1] > c[0]));
+
+    // ...but the FBE does not, so this is not reported as stalling
+    let mut u = [-1.2, 1.0];
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let status_stall_detection = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(100)
+        .with_stall_detection(1, 1e-12)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::Converged, status_stall_detection.exit_status());
+    assert_eq!(status.iterations(), status_stall_detection.iterations());
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_stall_detection_zero_window() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+    let _ = PANOCOptimizer::new(problem, &mut panoc_cache).with_stall_detection(0, 1e-4);
+}
+
+#[test]
+fn t_panoc_initial_lipschitz() {
+    let num_gradient_calls = std::cell::Cell::new(0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        num_gradient_calls.set(num_gradient_calls.get() + 1);
+        mocks::my_gradient(u, grad)
+    };
+    let radius = 0.2;
+    let bounds = constraints::Ball2::new(None, radius);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-8, 5);
+
+    let mut u = [0.0; N_DIM];
+    let problem = Problem::new(&bounds, cost_gradient, mocks::my_cost);
+    let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+    panoc_engine.init(&mut u).unwrap();
+    let num_calls_estimate = num_gradient_calls.replace(0);
+
+    let problem = Problem::new(&bounds, cost_gradient, mocks::my_cost);
+    let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+    panoc_engine.initial_lipschitz = Some(2.5);
+    panoc_engine.init(&mut u).unwrap();
+    assert_eq!(num_calls_estimate - 1, num_gradient_calls.replace(0));
+    assert_eq!(2.5, panoc_engine.cache.lipschitz_constant);
+    unit_test_utils::assert_nearly_equal(
+        0.95 / 2.5,
+        panoc_engine.cache.gamma,
+        1e-12,
+        1e-14,
+        "gamma",
+    );
+
+    let problem = Problem::new(&bounds, cost_gradient, mocks::my_cost);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_initial_lipschitz(2.5)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    unit_test_utils::assert_nearly_equal_array(&mocks::SOLUTION_A, &u, 1e-6, 1e-8, "u");
+}
+
+#[test]
+fn t_panoc_reuse_lipschitz() {
+    let num_gradient_calls = std::cell::Cell::new(0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        num_gradient_calls.set(num_gradient_calls.get() + 1);
+        mocks::my_gradient(u, grad)
+    };
+    let radius = 0.2;
+    let bounds = constraints::Ball2::new(None, radius);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-8, 5);
+
+    let mut solve_twice = |reuse_lipschitz: bool| {
+        for _ in 0..2 {
+            let mut u = [0.0; N_DIM];
+            let problem = Problem::new(&bounds, cost_gradient, mocks::my_cost);
+            let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache);
+            if reuse_lipschitz {
+                panoc = panoc.reuse_lipschitz();
+            }
+            let status = panoc.solve(&mut u).unwrap();
+            assert!(status.has_converged());
+            unit_test_utils::assert_nearly_equal_array(&mocks::SOLUTION_A, &u, 1e-6, 1e-8, "u");
+        }
+        num_gradient_calls.replace(0)
+    };
+    let num_calls_cold = solve_twice(false);
+    let num_calls_reuse = solve_twice(true);
+    assert!(num_calls_reuse < num_calls_cold);
+}
+
+#[test]
+fn t_panoc_akkt_residual_value() {
+    let bounds = constraints::Ball2::new(None, 0.2);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-8, 5);
+    assert_eq!(None, panoc_cache.akkt_residual_value());
+    panoc_cache.set_akkt_tolerance(1e-6);
+    panoc_cache.enable_history(100);
+
+    let mut u = [0.0; N_DIM];
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(100)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+
+    let akkt_residual: f64 = panoc_cache.akkt_residual_value().unwrap();
+    assert!(akkt_residual.is_finite());
+    let akkt_residuals: &[f64] = panoc_cache.history().unwrap().akkt_residuals();
+    assert_eq!(status.iterations(), akkt_residuals.len());
+    assert!(akkt_residuals.iter().all(|r| r.is_finite()));
+    assert!(akkt_residuals[akkt_residuals.len() - 1] < akkt_residuals[0]);
+    assert!(akkt_residual < akkt_residuals[0]);
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_initial_lipschitz_not_positive() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+    let _ = PANOCOptimizer::new(problem, &mut panoc_cache).with_initial_lipschitz(0.0);
+}
+
+#[test]
+fn t_panoc_lbfgs_reset_every() {
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-10, 5);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+    panoc_engine.lbfgs_reset_every = Some(3);
+    let mut u = [-1.5, 2.0];
+    panoc_engine.init(&mut u).unwrap();
+
+    // in iterations 1 and 2 the L-BFGS buffer is used, whereas in iteration
+    // 3 it has just been reset, so the direction is the fixed-point residual
+    for iteration in 0..4 {
+        assert!(panoc_engine.step(&mut u).unwrap());
+        let cache = &panoc_engine.cache;
+        if iteration == 3 {
+            assert_eq!(cache.gamma_fpr, cache.direction_lbfgs);
+        } else if iteration > 0 {
+            assert_ne!(cache.gamma_fpr, cache.direction_lbfgs);
+        }
+    }
+
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut u = [0.0; N_DIM];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_lbfgs_reset_every(10)
+        .with_max_iter(1000)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_lbfgs_reset_every_zero() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+    let _ = PANOCOptimizer::new(problem, &mut panoc_cache).with_lbfgs_reset_every(0);
+}
+
+#[test]
+fn t_panoc_lbfgs_reset_on_initial_gamma_change() {
+    let radius = 0.2;
+    let bounds = constraints::Ball2::new(None, radius);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-8, 5);
+    let mut u = [0.0; N_DIM];
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_lbfgs_warm_start()
+        .solve(&mut u)
+        .unwrap();
+
+    // solve the problem again, starting with a different gamma
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut u = [0.0; N_DIM];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_lbfgs_warm_start()
+        .with_lbfgs_reset_on_initial_gamma_change()
+        .with_initial_lipschitz(50.0)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    unit_test_utils::assert_nearly_equal_array(&mocks::SOLUTION_A, &u, 1e-6, 1e-8, "u");
+}
+
+#[test]
+fn t_panoc_cache_memory_footprint() {
+    let float_size = core::mem::size_of::<f64>();
+    let footprint = |n: usize, m: usize| PANOCCache::<f64>::new(n, 1e-6, m).memory_footprint();
+
+    // 6 work vectors, plus 2*m*n + 2*m + 2*n floats for the L-BFGS buffer
+    assert_eq!((8 * 10 + 2 * 5 * 10 + 2 * 5) * float_size, footprint(10, 5));
+    assert_eq!(
+        (2 * 5 + 8) * 10 * float_size,
+        footprint(20, 5) - footprint(10, 5)
+    );
+    assert_eq!(
+        (2 * 10 + 2) * float_size,
+        footprint(10, 6) - footprint(10, 5)
+    );
+
+    let mut panoc_cache = PANOCCache::<f64>::new(10, 1e-6, 5);
+    let initial_footprint = panoc_cache.memory_footprint();
+    panoc_cache.enable_history(100);
+    assert_eq!(
+        initial_footprint + 4 * 100 * float_size,
+        panoc_cache.memory_footprint()
+    );
+    panoc_cache.set_lbfgs_memory(3);
+    assert_eq!(
+        initial_footprint + 4 * 100 * float_size - (2 * 10 + 2) * 2 * float_size,
+        panoc_cache.memory_footprint()
+    );
+}
+
+#[test]
+fn t_panoc_max_linesearch_iters() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    for &max_linesearch_iters in [1, 2].iter() {
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc_cache = PANOCCache::new(2, 1e-8, 10);
+        let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
+        panoc_engine.max_linesearch_iters = max_linesearch_iters;
+        let mut u = [-1.5, 2.0];
+        panoc_engine.init(&mut u).unwrap();
+        let mut max_steps_per_iteration = 0;
+        for _ in 0..200 {
+            let steps_before = panoc_engine.cache.linesearch_steps();
+            let keep_going = panoc_engine.step(&mut u).unwrap();
+            let steps = panoc_engine.cache.linesearch_steps() - steps_before;
+            assert!(steps <= max_linesearch_iters);
+            max_steps_per_iteration = max_steps_per_iteration.max(steps);
+            if !keep_going {
+                break;
+            }
+        }
+        // the cap is reached, but the solver keeps going
+        assert_eq!(max_linesearch_iters, max_steps_per_iteration);
+        assert!(panoc_engine.cache.exit_condition());
+    }
+}
+
+#[test]
+fn t_panoc_stop_on_linesearch_failure() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(2, 1e-8, 10);
+    let mut u = [-1.5, 2.0];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_max_linesearch_iters(1)
+        .stop_on_linesearch_failure()
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::LineSearchFailed, status.exit_status());
+    assert!(panoc_cache.linesearch_steps() <= status.iterations());
+
+    // with the default maximum number of line search iterations, the solver
+    // converges
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 2.0];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .stop_on_linesearch_failure()
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(ExitStatus::Converged, status.exit_status());
+    assert!(panoc_cache.linesearch_steps() > 0);
+}
+
+#[test]
+fn t_panoc_set_constraints() {
+    // minimize 0.5*||u - p||^2 over a box, whose solution is p clipped
+    // to the box
+    let p = [2.0, -3.0, 0.5];
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        grad.iter_mut()
+            .zip(u.iter().zip(p.iter()))
+            .for_each(|(g, (&ui, &pi))| *g = ui - pi);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = 0.5 * crate::matrix_operations::norm2_squared_diff(u, &p);
+        Ok(())
+    };
+    let (xmin_1, xmax_1) = ([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]);
+    let (xmin_2, xmax_2) = ([0.0, -5.0, 1.0], [1.5, 0.0, 2.0]);
+    let rectangle_1 = constraints::Rectangle::new(Some(&xmin_1), Some(&xmax_1));
+    let rectangle_2 = constraints::Rectangle::new(Some(&xmin_2), Some(&xmax_2));
+
+    let problem = Problem::new(&rectangle_1, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(3, 1e-10, 5);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+
+    let mut u = [0.0; 3];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(status.has_converged());
+    unit_test_utils::assert_nearly_equal_array(&[1.0, -1.0, 0.5], &u, 1e-8, 1e-8, "u (1)");
+
+    panoc.set_constraints(&rectangle_2);
+    let mut u = [0.0; 3];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(status.has_converged());
+    unit_test_utils::assert_nearly_equal_array(&[1.5, -3.0, 1.0], &u, 1e-8, 1e-8, "u (2)");
+}
+
+#[test]
+fn t_panoc_adaptive_tolerance() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let (initial_tolerance, final_tolerance) = (1e-2, 1e-9);
+
+    // the loose tolerance is satisfied first...
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(2, initial_tolerance, 10);
+    let mut u = [-1.5, 2.0];
+    let status_loose = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status_loose.has_converged());
+
+    // ...but, with an adaptive tolerance, this does not stop the solver
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 2.0];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_adaptive_tolerance(initial_tolerance, final_tolerance, 0.1)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    assert!(status.iterations() > status_loose.iterations());
+    assert!(status.norm_fpr() < final_tolerance);
+    assert_eq!(Some(final_tolerance), panoc_cache.adaptive_tolerance());
+    unit_test_utils::assert_nearly_equal_array(&[1.0, 1.0], &u, 1e-6, 1e-6, "u");
+
+    // the tolerance is set back to its initial value at every solve
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 2.0];
+    let status_again = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_adaptive_tolerance(initial_tolerance, final_tolerance, 0.1)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(status.iterations(), status_again.iterations());
+
+    // an interrupted solve reports the tightest tolerance attained
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 2.0];
+    let status_interrupted = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(status_loose.iterations() + 1)
+        .with_adaptive_tolerance(initial_tolerance, final_tolerance, 0.1)
+        .solve(&mut u)
+        .unwrap();
+    assert!(!status_interrupted.has_converged());
+    assert!(panoc_cache.adaptive_tolerance().unwrap() < initial_tolerance);
+    assert!(panoc_cache.adaptive_tolerance().unwrap() > final_tolerance);
+
+    // the tolerance is tightened at most once per iteration, so it does not
+    // reach its final value in a couple of iterations, even at the solution
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [1.0, 1.0];
+    let status_at_solution = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(2)
+        .with_adaptive_tolerance(initial_tolerance, final_tolerance, 0.1)
+        .solve(&mut u)
+        .unwrap();
+    assert!(!status_at_solution.has_converged());
+    assert!(panoc_cache.adaptive_tolerance().unwrap() >= 1e-5 * (1.0 - 1e-12));
+    assert!(panoc_cache.adaptive_tolerance().unwrap() > final_tolerance);
+
+    // the tolerance of the cache is not modified, so a solve without an
+    // adaptive tolerance stops at the loose tolerance again
+    assert_eq!(initial_tolerance, panoc_cache.tolerance);
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut u = [-1.5, 2.0];
+    let status_loose_again = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(status_loose.iterations(), status_loose_again.iterations());
+    assert_eq!(None, panoc_cache.adaptive_tolerance());
+}
+
+#[test]
+fn t_panoc_adaptive_and_relative_tolerance() {
+    let (a, b) = (1.0, 200.0);
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        mocks::rosenbrock_grad(a, b, u, grad);
+        Ok(())
+    };
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        *c = mocks::rosenbrock_cost(a, b, u);
+        Ok(())
+    };
+    let bounds = constraints::NoConstraints::new();
+    let (initial_tolerance, final_tolerance) = (1e-2, 1e-12);
+
+    // the (loose) relative criterion stops the solver before the final
+    // tolerance is reached
+    let problem = Problem::new(&bounds, cost_gradient, cost_function);
+    let mut panoc_cache = PANOCCache::new(2, initial_tolerance, 10);
+    panoc_cache.set_relative_tolerance(1e-3);
+    let mut u = [-1.5, 2.0];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1000)
+        .with_adaptive_tolerance(initial_tolerance, final_tolerance, 0.1)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    assert!(status.norm_fpr() > final_tolerance);
+    assert!(panoc_cache.adaptive_tolerance().unwrap() > final_tolerance);
+}
+
+#[test]
+#[should_panic]
+fn t_panoc_adaptive_tolerance_wrong_factor() {
+    let bounds = constraints::NoConstraints::new();
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+    let _ = PANOCOptimizer::new(problem, &mut panoc_cache).with_adaptive_tolerance(1e-2, 1e-6, 1.0);
+}
+
+#[test]
+fn t_panoc_function_evaluations() {
+    let cost_calls = core::cell::Cell::new(0);
+    let gradient_calls = core::cell::Cell::new(0);
+    let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+        cost_calls.set(cost_calls.get() + 1);
+        mocks::my_cost(u, c)
+    };
+    let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+        gradient_calls.set(gradient_calls.get() + 1);
+        mocks::my_gradient(u, grad)
+    };
+    let radius = 0.2;
+    let ball = constraints::Ball2::new(None, radius);
+    let mut panoc_cache = PANOCCache::new(N_DIM, 1e-6, 5);
+
+    // one iteration; the counts include the evaluations needed to estimate
+    // the Lipschitz constant and to update it
+    let problem = Problem::new(&ball, cost_gradient, cost_function);
+    let mut u = [0.75, -1.4];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .with_max_iter(1)
+        .solve(&mut u)
+        .unwrap();
+    assert_eq!(7, status.cost_evaluations());
+    assert_eq!(4, status.gradient_evaluations());
+    assert_eq!(7, cost_calls.get());
+    assert_eq!(4, gradient_calls.get());
+
+    // a full solve; the counts are reset at the beginning of every solve
+    cost_calls.set(0);
+    gradient_calls.set(0);
+    let problem = Problem::new(&ball, cost_gradient, cost_function);
+    let mut u = [0.75, -1.4];
+    let status = PANOCOptimizer::new(problem, &mut panoc_cache)
+        .solve(&mut u)
+        .unwrap();
+    assert!(status.has_converged());
+    assert_eq!(cost_calls.get(), status.cost_evaluations());
+    assert_eq!(gradient_calls.get(), status.gradient_evaluations());
+    assert_eq!(cost_calls.get(), panoc_cache.cost_evaluations());
+    assert_eq!(gradient_calls.get(), panoc_cache.gradient_evaluations());
+}
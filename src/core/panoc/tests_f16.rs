@@ -0,0 +1,27 @@
+use crate::core::panoc::*;
+use crate::core::*;
+use crate::mocks;
+use half::f16;
+
+#[test]
+fn t_panoc_f16_smoke() {
+    let radius = f16::from_f32(0.2);
+    let bounds = constraints::Ball2::new(None, radius);
+    let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+    let tolerance = f16::from_f32(1e-3);
+    let mut panoc_cache = PANOCCache::new(2, tolerance, 5);
+    let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(100);
+
+    let mut u = [f16::from_f32(0.0); 2];
+    let status = panoc.solve(&mut u).unwrap();
+    assert!(status.has_converged());
+
+    let u_f32 = [u[0].to_f32(), u[1].to_f32()];
+    unit_test_utils::assert_nearly_equal_array(
+        &mocks::SOLUTION_A_F32,
+        &u_f32,
+        1e-2,
+        1e-2,
+        "solution (f16)",
+    );
+}
@@ -15,8 +15,9 @@ fn t_panoc_init() {
         let mut panoc_engine = PANOCEngine::new(problem, &mut panoc_cache);
         let mut u = [0.75, -1.4];
         panoc_engine.init(&mut u).unwrap();
-        assert!(2.549_509_967_743_775 > panoc_engine.cache.lipschitz_constant);
-        assert!(0.372_620_625_931_781 < panoc_engine.cache.gamma, "gamma");
+        // the Lipschitz constant of the gradient is (3 + sqrt(5))/2
+        assert!(2.618_033_988_749_895 > panoc_engine.cache.lipschitz_constant);
+        assert!(0.362_868_004_775_302 < panoc_engine.cache.gamma, "gamma");
         println!("----------- {} ", panoc_engine.cache.cost_value);
         unit_test_utils::assert_nearly_equal(
             6.34125,
@@ -7,7 +7,7 @@
 //! C (and then invoked from Rust via an interface such as icasadi).
 //!
 use crate::core::OptFloat;
-use crate::{constraints, FunctionCallResult};
+use crate::{constraints, FunctionCallResult, SolverError};
 /// Definition of an optimisation problem
 ///
 /// The definition of an optimisation problem involves:
@@ -29,7 +29,7 @@ where
     /// cost function
     pub(crate) cost: CostType,
     /// phantom data for float type
-    _phantom: std::marker::PhantomData<T>,
+    _phantom: core::marker::PhantomData<T>,
 }
 
 impl<'a, GradientType, ConstraintType, CostType, T>
@@ -60,7 +60,170 @@ where
             constraints,
             gradf: cost_gradient,
             cost,
-            _phantom: std::marker::PhantomData,
+            _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Replaces the constraints of the problem
+    ///
+    /// The new constraints are borrowed for the same lifetime, `'a`, as the
+    /// constraints given to `new`, so they (and any data they borrow, such
+    /// as the bounds of a [Rectangle](../constraints/struct.Rectangle.html))
+    /// must outlive the problem and any engine or optimizer which owns it.
+    ///
+    /// ## Arguments
+    ///
+    /// - `constraints`: the new constraints, which must be of the same type
+    ///   as the current ones
+    ///
+    pub fn set_constraints(&mut self, constraints: &'a ConstraintType) {
+        self.constraints = constraints;
+    }
+
+    /// Evaluates the cost function at a given point
+    ///
+    /// ## Arguments
+    ///
+    /// - `u`: the point at which the cost is evaluated
+    ///
+    /// ## Returns
+    ///
+    /// The value of the cost function at `u`, or the error returned by the
+    /// cost function
+    pub fn cost(&self, u: &[T]) -> Result<T, SolverError> {
+        let mut cost_value = T::zero();
+        (self.cost)(u, &mut cost_value)?;
+        Ok(cost_value)
+    }
+
+    /// Evaluates the gradient of the cost function at a given point
+    ///
+    /// ## Arguments
+    ///
+    /// - `u`: the point at which the gradient is evaluated
+    /// - `grad`: on exit, the gradient of the cost function at `u`
+    ///
+    /// ## Returns
+    ///
+    /// `Ok(())`, or the error returned by the gradient function
+    pub fn gradient(&self, u: &[T], grad: &mut [T]) -> FunctionCallResult {
+        (self.gradf)(u, grad)
+    }
+}
+
+/// Proximal operator of a regularizer, which maps `(v, gamma)` to
+/// $\mathrm{prox}_{\gamma g}(v)$ in place
+pub(crate) type ProxOperator<'a, T> = &'a dyn Fn(&mut [T], T);
+
+/// Definition of an optimisation problem with a proximable regularizer
+///
+/// This is a problem of the form
+///
+/// $$
+/// \min_{u \in U} f(u) + g(u),
+/// $$
+///
+/// where $f$ is smooth (with a given cost function and gradient, as in
+/// [Problem](struct.Problem.html)), $U$ is the set of constraints and $g$ is
+/// a (possibly nonsmooth) regularizer, such as $g(u) = \lambda \Vert u \Vert_1$,
+/// whose proximal operator,
+///
+/// $$
+/// \mathrm{prox}_{\gamma g}(v) = \mathrm{argmin}_{z} g(z) + \tfrac{1}{2\gamma}\Vert z - v \Vert^2,
+/// $$
+///
+/// is provided by the user. Problems of this form are solved with
+/// `FBSOptimizer::new_proximal`; PANOC is not supported, because its line
+/// search requires the value of $g$.
+///
+/// ## Composition of the proximal operator and the projection
+///
+/// The backward step of the algorithm applies $\mathrm{prox}_{\gamma g}$
+/// and then projects on $U$. This composition is the proximal operator of
+/// $g + \delta_U$ in certain cases, e.g., when $g$ is separable (as is the
+/// $\ell_1$ norm) and $U$ is a rectangle, or when $U$ is the whole space, but
+/// not in general; in other cases, the solver may converge to a point which
+/// is not a minimizer.
+pub struct ProximalProblem<'a, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T>,
+    T: OptFloat,
+{
+    /// smooth part of the problem and constraints
+    pub(crate) problem: Problem<'a, GradientType, ConstraintType, CostType, T>,
+    /// proximal operator of the regularizer
+    pub(crate) prox: ProxOperator<'a, T>,
+}
+
+impl<'a, GradientType, ConstraintType, CostType, T>
+    ProximalProblem<'a, GradientType, ConstraintType, CostType, T>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    ConstraintType: constraints::Constraint<T>,
+    T: OptFloat,
+{
+    /// Construct a new instance of an optimisation problem with a proximable
+    /// regularizer
+    ///
+    /// ## Arguments
+    ///
+    /// - `constraints` constraints
+    /// - `prox` proximal operator of the regularizer: given `v` and `gamma`,
+    ///   it overwrites `v` with $\mathrm{prox}_{\gamma g}(v)$
+    /// - `cost_gradient` gradient of the smooth part of the cost function
+    /// - `cost` smooth part of the cost function
+    ///
+    /// ## Returns
+    ///
+    /// New instance of `ProximalProblem`
+    pub fn new(
+        constraints: &'a ConstraintType,
+        prox: &'a dyn Fn(&mut [T], T),
+        cost_gradient: GradientType,
+        cost: CostType,
+    ) -> ProximalProblem<'a, GradientType, ConstraintType, CostType, T> {
+        ProximalProblem {
+            problem: Problem::new(constraints, cost_gradient, cost),
+            prox,
+        }
+    }
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::constraints::NoConstraints;
+    use crate::mocks;
+
+    #[test]
+    fn t_problem_cost_and_gradient() {
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+        let u = [1.0, 2.0];
+        unit_test_utils::assert_nearly_equal(8.5, problem.cost(&u).unwrap(), 1e-12, 1e-14, "cost");
+        let mut grad = [0.0; 2];
+        problem.gradient(&u, &mut grad).unwrap();
+        unit_test_utils::assert_nearly_equal_array(&[4.0, 4.0], &grad, 1e-12, 1e-14, "grad");
+    }
+
+    #[test]
+    fn t_problem_cost_and_gradient_errors() {
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(
+            &bounds,
+            |_u: &[f64], _grad: &mut [f64]| -> FunctionCallResult { Err(SolverError::Cost) },
+            |_u: &[f64], _c: &mut f64| -> FunctionCallResult { Err(SolverError::Cost) },
+        );
+        let u = [1.0, 2.0];
+        let mut grad = [0.0; 2];
+        assert_eq!(Err(SolverError::Cost), problem.cost(&u));
+        assert_eq!(Err(SolverError::Cost), problem.gradient(&u, &mut grad));
+    }
 }
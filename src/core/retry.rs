@@ -0,0 +1,186 @@
+//! Solution with an increasing number of iterations
+//!
+use crate::core::{ExitStatus, OptFloat, Optimizer, SolverStatus};
+use crate::SolverError;
+
+/// Solves a problem and, while the maximum number of iterations is reached,
+/// solves it again with a larger maximum number of iterations
+///
+/// Every attempt is warm-started from the (partial) solution of the previous
+/// one. After an attempt which reaches the maximum number of iterations
+/// (exit status `NotConvergedIterations`), the maximum number of iterations
+/// of the optimizer is multiplied by `iter_growth` and the problem is solved
+/// again. The attempts stop when the exit status is any other (e.g.,
+/// `Converged` or `NotConvergedOutOfTime`), or after `max_attempts` attempts.
+/// The maximum number of iterations of the optimizer is restored at the end.
+///
+/// ## Arguments
+///
+/// - `optimizer`: the optimizer
+/// - `u`: on entry, the initial guess; on exit, the solution
+/// - `max_attempts`: maximum number of attempts
+/// - `iter_growth`: factor by which the maximum number of iterations is
+///   multiplied after every attempt which reaches it
+///
+/// ## Returns
+///
+/// Returns the solver status of the last attempt (so, the reported number of
+/// iterations is that of the last attempt), or the first error that was
+/// encountered
+///
+/// ## Panics
+///
+/// The method panics if `max_attempts` or `iter_growth` is zero
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::{constraints::Ball2, core::*, panoc::*, FunctionCallResult};
+///
+/// let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+///     *c = (1.0 - u[0]).powi(2) + 100.0 * (u[1] - u[0] * u[0]).powi(2);
+///     Ok(())
+/// };
+/// let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+///     grad[0] = 2.0 * (u[0] - 1.0) - 400.0 * u[0] * (u[1] - u[0] * u[0]);
+///     grad[1] = 200.0 * (u[1] - u[0] * u[0]);
+///     Ok(())
+/// };
+/// let bounds = Ball2::new(None, 2.0);
+/// let mut cache = PANOCCache::new(2, 1e-8, 5);
+/// let problem = Problem::new(&bounds, gradient, cost);
+/// let mut panoc = PANOCOptimizer::new(problem, &mut cache).with_max_iter(5);
+/// let mut u = [-1.5, 0.9];
+///
+/// let status = solve_with_retry(&mut panoc, &mut u, 4, 10).unwrap();
+/// assert!(status.has_converged());
+/// ```
+///
+pub fn solve_with_retry<T, O>(
+    optimizer: &mut O,
+    u: &mut [T],
+    max_attempts: usize,
+    iter_growth: usize,
+) -> Result<SolverStatus<T>, SolverError>
+where
+    T: OptFloat,
+    O: Optimizer<T> + ?Sized,
+{
+    assert!(max_attempts > 0, "max_attempts must be positive");
+    assert!(iter_growth > 0, "iter_growth must be positive");
+
+    let initial_max_iter = optimizer.max_iter();
+    let mut result = optimizer.solve(u);
+    for _ in 1..max_attempts {
+        match &result {
+            Ok(status) if status.exit_status() == ExitStatus::NotConvergedIterations => {
+                let max_iter = optimizer.max_iter().saturating_mul(iter_growth);
+                optimizer.set_max_iter(max_iter);
+                result = optimizer.solve(u);
+            }
+            _ => break,
+        }
+    }
+    optimizer.set_max_iter(initial_max_iter);
+    result
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::core::panoc::{PANOCCache, PANOCOptimizer};
+    use crate::core::Problem;
+    use crate::{constraints, mocks, FunctionCallResult};
+
+    #[test]
+    fn t_solve_with_retry() {
+        let (a, b) = (1.0, 200.0);
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a, b, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a, b, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 1.2);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(3);
+
+        let mut u = [-1.5, 0.9];
+        let mut num_attempts = 0;
+        let status = solve_with_retry(
+            &mut CountingOptimizer {
+                optimizer: &mut panoc,
+                num_attempts: &mut num_attempts,
+            },
+            &mut u,
+            5,
+            100,
+        )
+        .unwrap();
+
+        assert!(status.has_converged());
+        assert_eq!(2, num_attempts);
+        assert!(status.iterations() <= 300);
+        assert_eq!(3, panoc.max_iter());
+    }
+
+    #[test]
+    fn t_solve_with_retry_max_attempts() {
+        let (a, b) = (1.0, 200.0);
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a, b, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a, b, u);
+            Ok(())
+        };
+        let bounds = constraints::Ball2::new(None, 1.2);
+        let mut panoc_cache = PANOCCache::new(2, 1e-6, 8);
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut panoc = PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(1);
+
+        let mut u = [-1.5, 0.9];
+        let status = solve_with_retry(&mut panoc, &mut u, 2, 2).unwrap();
+        assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+        assert_eq!(2, status.iterations());
+        assert_eq!(1, panoc.max_iter());
+    }
+
+    /// Counts the calls to `solve`
+    struct CountingOptimizer<'a, O> {
+        optimizer: &'a mut O,
+        num_attempts: &'a mut usize,
+    }
+
+    impl<'a, O: Optimizer<f64>> Optimizer<f64> for CountingOptimizer<'a, O> {
+        fn solve(&mut self, u: &mut [f64]) -> Result<SolverStatus<f64>, SolverError> {
+            *self.num_attempts += 1;
+            self.optimizer.solve(u)
+        }
+
+        fn solve_with_callback(
+            &mut self,
+            u: &mut [f64],
+            callback: &mut dyn FnMut(usize, f64, f64) -> bool,
+        ) -> Result<SolverStatus<f64>, SolverError> {
+            *self.num_attempts += 1;
+            self.optimizer.solve_with_callback(u, callback)
+        }
+
+        fn max_iter(&self) -> usize {
+            self.optimizer.max_iter()
+        }
+
+        fn set_max_iter(&mut self, max_iter: usize) {
+            self.optimizer.set_max_iter(max_iter)
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Separable cost functions
+//!
+//! Many cost functions, for instance in trajectory optimization, are sums of
+//! terms, each of which depends on a single block of the decision variable,
+//!
+//! $$
+//! f(u) = \sum_{k=0}^{N-1} f_k(u_k),
+//! $$
+//!
+//! where $u = (u_0, \ldots, u_{N-1})$ and all blocks $u_k$ have the same size.
+//! The gradient of such a function is
+//! $\nabla f(u) = (\nabla f_0(u_0), \ldots, \nabla f_{N-1}(u_{N-1}))$.
+//! A [SeparableCost](struct.SeparableCost.html) assembles the cost and its
+//! gradient from the per-block terms.
+//!
+//! If the feature `rayon` is activated, the cost and its gradient can also
+//! be assembled in parallel (see `par_cost` and `par_gradient`).
+//!
+use crate::core::OptFloat;
+use crate::FunctionCallResult;
+
+/// A separable cost function, $f(u) = \sum_{k} f_k(u_k)$, with blocks of
+/// equal size
+///
+/// The per-block cost and gradient functions receive the index of the block,
+/// $k$, so that different blocks may have different cost functions.
+///
+/// # Example
+///
+/// ```
+/// use optimization_engine::core::separable::SeparableCost;
+/// use optimization_engine::FunctionCallResult;
+///
+/// // f(u) = sum_k (k + 1) * |u_k|^2, with blocks of size 2
+/// let separable = SeparableCost::new(
+///     2,
+///     |k: usize, uk: &[f64], c: &mut f64| -> FunctionCallResult {
+///         *c = (k as f64 + 1.0) * (uk[0] * uk[0] + uk[1] * uk[1]);
+///         Ok(())
+///     },
+///     |k: usize, uk: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+///         grad[0] = 2.0 * (k as f64 + 1.0) * uk[0];
+///         grad[1] = 2.0 * (k as f64 + 1.0) * uk[1];
+///         Ok(())
+///     },
+/// );
+/// let u = [1.0, 0.0, 0.0, 1.0];
+/// let mut cost = 0.0;
+/// separable.cost(&u, &mut cost).unwrap();
+/// assert_eq!(3.0, cost);
+/// ```
+///
+pub struct SeparableCost<BlockCostType, BlockGradientType, T>
+where
+    BlockCostType: Fn(usize, &[T], &mut T) -> FunctionCallResult,
+    BlockGradientType: Fn(usize, &[T], &mut [T]) -> FunctionCallResult,
+    T: OptFloat,
+{
+    block_size: usize,
+    block_cost: BlockCostType,
+    block_gradient: BlockGradientType,
+    _phantom: core::marker::PhantomData<T>,
+}
+
+impl<BlockCostType, BlockGradientType, T> SeparableCost<BlockCostType, BlockGradientType, T>
+where
+    BlockCostType: Fn(usize, &[T], &mut T) -> FunctionCallResult,
+    BlockGradientType: Fn(usize, &[T], &mut [T]) -> FunctionCallResult,
+    T: OptFloat,
+{
+    /// Constructs a new separable cost function
+    ///
+    /// ## Arguments
+    ///
+    /// - `block_size`: size of each block, $u_k$
+    /// - `block_cost`: function which computes $f_k(u_k)$ given $k$ and $u_k$
+    /// - `block_gradient`: function which computes $\nabla f_k(u_k)$ given $k$
+    ///   and $u_k$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `block_size` is zero
+    ///
+    pub fn new(
+        block_size: usize,
+        block_cost: BlockCostType,
+        block_gradient: BlockGradientType,
+    ) -> Self {
+        assert!(block_size > 0, "block_size must be positive");
+        SeparableCost {
+            block_size,
+            block_cost,
+            block_gradient,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Size of each block
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Computes the cost, $f(u) = \sum_{k} f_k(u_k)$
+    ///
+    /// The first error returned by a per-block cost function, if any, is
+    /// propagated
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `u` is not a multiple of the block size
+    ///
+    pub fn cost(&self, u: &[T], cost: &mut T) -> FunctionCallResult {
+        self.assert_dimension(u);
+        let mut total = T::zero();
+        for (k, uk) in u.chunks(self.block_size).enumerate() {
+            let mut cost_k = T::zero();
+            (self.block_cost)(k, uk, &mut cost_k)?;
+            total += cost_k;
+        }
+        *cost = total;
+        Ok(())
+    }
+
+    /// Computes the gradient of the cost, $\nabla f(u)$, block by block
+    ///
+    /// The first error returned by a per-block gradient function, if any, is
+    /// propagated
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `u` is not a multiple of the block
+    /// size, or if `u` and `grad` have different lengths
+    ///
+    pub fn gradient(&self, u: &[T], grad: &mut [T]) -> FunctionCallResult {
+        self.assert_dimension(u);
+        assert_eq!(u.len(), grad.len(), "u and grad have different lengths");
+        u.chunks(self.block_size)
+            .zip(grad.chunks_mut(self.block_size))
+            .enumerate()
+            .try_for_each(|(k, (uk, grad_k))| (self.block_gradient)(k, uk, grad_k))
+    }
+
+    fn assert_dimension(&self, u: &[T]) {
+        assert!(
+            u.len() % self.block_size == 0,
+            "the length of u is not a multiple of the block size"
+        );
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<BlockCostType, BlockGradientType, T> SeparableCost<BlockCostType, BlockGradientType, T>
+where
+    BlockCostType: Fn(usize, &[T], &mut T) -> FunctionCallResult + Sync,
+    BlockGradientType: Fn(usize, &[T], &mut [T]) -> FunctionCallResult + Sync,
+    T: OptFloat + Send + Sync,
+{
+    /// Computes the cost, $f(u) = \sum_{k} f_k(u_k)$, evaluating the blocks
+    /// in parallel
+    ///
+    /// This is the parallel version of `cost` and requires the feature
+    /// `rayon`. If several per-block cost functions fail, any one of the
+    /// errors is propagated.
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `u` is not a multiple of the block size
+    ///
+    pub fn par_cost(&self, u: &[T], cost: &mut T) -> FunctionCallResult {
+        use rayon::prelude::*;
+        self.assert_dimension(u);
+        *cost = u
+            .par_chunks(self.block_size)
+            .enumerate()
+            .map(|(k, uk)| {
+                let mut cost_k = T::zero();
+                (self.block_cost)(k, uk, &mut cost_k).map(|_| cost_k)
+            })
+            .try_reduce(T::zero, |a, b| Ok(a + b))?;
+        Ok(())
+    }
+
+    /// Computes the gradient of the cost, $\nabla f(u)$, evaluating the
+    /// blocks in parallel
+    ///
+    /// This is the parallel version of `gradient` and requires the feature
+    /// `rayon`. If several per-block gradient functions fail, any one of the
+    /// errors is propagated.
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if the length of `u` is not a multiple of the block
+    /// size, or if `u` and `grad` have different lengths
+    ///
+    pub fn par_gradient(&self, u: &[T], grad: &mut [T]) -> FunctionCallResult {
+        use rayon::prelude::*;
+        self.assert_dimension(u);
+        assert_eq!(u.len(), grad.len(), "u and grad have different lengths");
+        u.par_chunks(self.block_size)
+            .zip(grad.par_chunks_mut(self.block_size))
+            .enumerate()
+            .try_for_each(|(k, (uk, grad_k))| (self.block_gradient)(k, uk, grad_k))
+    }
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::SolverError;
+
+    const BLOCK_SIZE: usize = 3;
+
+    // f_k(u_k) = 0.5 * sum_i w_{k,i} (u_{k,i} - k)^2, with w_{k,i} = 1 + k + i
+    fn weight(k: usize, i: usize) -> f64 {
+        1.0 + k as f64 + i as f64
+    }
+
+    fn block_cost(k: usize, uk: &[f64], c: &mut f64) -> FunctionCallResult {
+        *c = uk
+            .iter()
+            .enumerate()
+            .map(|(i, &ui)| 0.5 * weight(k, i) * (ui - k as f64).powi(2))
+            .sum();
+        Ok(())
+    }
+
+    fn block_gradient(k: usize, uk: &[f64], grad: &mut [f64]) -> FunctionCallResult {
+        grad.iter_mut()
+            .zip(uk.iter())
+            .enumerate()
+            .for_each(|(i, (gi, &ui))| *gi = weight(k, i) * (ui - k as f64));
+        Ok(())
+    }
+
+    // monolithic implementation of the same cost and gradient
+    fn monolithic_cost(u: &[f64]) -> f64 {
+        u.iter()
+            .enumerate()
+            .map(|(j, &uj)| {
+                let (k, i) = (j / BLOCK_SIZE, j % BLOCK_SIZE);
+                0.5 * weight(k, i) * (uj - k as f64).powi(2)
+            })
+            .sum()
+    }
+
+    fn monolithic_gradient(u: &[f64], grad: &mut [f64]) {
+        for (j, gj) in grad.iter_mut().enumerate() {
+            let (k, i) = (j / BLOCK_SIZE, j % BLOCK_SIZE);
+            *gj = weight(k, i) * (u[j] - k as f64);
+        }
+    }
+
+    #[test]
+    fn t_separable_sum_of_squares() {
+        let separable = SeparableCost::new(BLOCK_SIZE, block_cost, block_gradient);
+        assert_eq!(BLOCK_SIZE, separable.block_size());
+        let u: Vec<f64> = (0..4 * BLOCK_SIZE).map(|j| 0.3 * j as f64 - 1.0).collect();
+
+        let mut cost = 0.0;
+        separable.cost(&u, &mut cost).unwrap();
+        unit_test_utils::assert_nearly_equal(monolithic_cost(&u), cost, 1e-12, 1e-14, "cost");
+
+        let mut grad = vec![0.0; u.len()];
+        let mut grad_monolithic = vec![0.0; u.len()];
+        separable.gradient(&u, &mut grad).unwrap();
+        monolithic_gradient(&u, &mut grad_monolithic);
+        unit_test_utils::assert_nearly_equal_array(
+            &grad_monolithic,
+            &grad,
+            1e-12,
+            1e-14,
+            "gradient",
+        );
+    }
+
+    #[test]
+    fn t_separable_error_propagation() {
+        let separable = SeparableCost::new(
+            BLOCK_SIZE,
+            |k: usize, uk: &[f64], c: &mut f64| -> FunctionCallResult {
+                if k == 1 {
+                    return Err(SolverError::Cost);
+                }
+                block_cost(k, uk, c)
+            },
+            block_gradient,
+        );
+        let u = [1.0; 2 * BLOCK_SIZE];
+        let mut cost = 0.0;
+        assert_eq!(Err(SolverError::Cost), separable.cost(&u, &mut cost));
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_separable_wrong_dimension() {
+        let separable = SeparableCost::new(BLOCK_SIZE, block_cost, block_gradient);
+        let u = [1.0; BLOCK_SIZE + 1];
+        let mut cost = 0.0;
+        let _ = separable.cost(&u, &mut cost);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn t_separable_parallel() {
+        let separable = SeparableCost::new(BLOCK_SIZE, block_cost, block_gradient);
+        let u: Vec<f64> = (0..50 * BLOCK_SIZE).map(|j| 0.1 * j as f64).collect();
+        let (mut cost, mut cost_par) = (0.0, 0.0);
+        separable.cost(&u, &mut cost).unwrap();
+        separable.par_cost(&u, &mut cost_par).unwrap();
+        unit_test_utils::assert_nearly_equal(cost, cost_par, 1e-12, 1e-14, "cost");
+        let mut grad = vec![0.0; u.len()];
+        let mut grad_par = vec![0.0; u.len()];
+        separable.gradient(&u, &mut grad).unwrap();
+        separable.par_gradient(&u, &mut grad_par).unwrap();
+        unit_test_utils::assert_nearly_equal_array(&grad, &grad_par, 1e-12, 1e-14, "grad");
+    }
+}
@@ -0,0 +1,164 @@
+//! Overrides of the numerical constants of the solvers
+//!
+//! The constants used in the estimation of the Lipschitz constant of the
+//! gradient and in the selection of the step size are provided by
+//! [OptFloat](../opt_float/trait.OptFloat.html) for every float type. On
+//! badly scaled problems these defaults may not be appropriate; they can be
+//! overridden for a particular solver using [SolverParams](struct.SolverParams.html).
+//!
+use crate::core::OptFloat;
+
+/// Optional overrides of the constants of [OptFloat](../opt_float/trait.OptFloat.html)
+///
+/// Every constant which is not overridden falls back to the default value of
+/// the float type.
+///
+/// # Example
+///
+/// ```
+/// use optimization_engine::core::{OptFloat, SolverParams};
+///
+/// let params = SolverParams::new().with_epsilon_lipschitz(1e-4);
+/// assert_eq!(1e-4, params.epsilon_lipschitz());
+/// assert_eq!(f64::delta_lipschitz(), params.delta_lipschitz());
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverParams<T>
+where
+    T: OptFloat,
+{
+    min_l_estimate: Option<T>,
+    gamma_l_coeff: Option<T>,
+    delta_lipschitz: Option<T>,
+    epsilon_lipschitz: Option<T>,
+    max_lipschitz_constant: Option<T>,
+}
+
+impl<T> Default for SolverParams<T>
+where
+    T: OptFloat,
+{
+    fn default() -> Self {
+        SolverParams::new()
+    }
+}
+
+impl<T> SolverParams<T>
+where
+    T: OptFloat,
+{
+    /// Constructs a new instance of `SolverParams` without any overrides
+    pub fn new() -> Self {
+        SolverParams {
+            min_l_estimate: None,
+            gamma_l_coeff: None,
+            delta_lipschitz: None,
+            epsilon_lipschitz: None,
+            max_lipschitz_constant: None,
+        }
+    }
+
+    /// Overrides the minimum estimate of the Lipschitz constant
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `min_l_estimate` is not positive
+    ///
+    pub fn with_min_l_estimate(mut self, min_l_estimate: T) -> Self {
+        assert!(
+            min_l_estimate > T::zero(),
+            "min_l_estimate must be positive"
+        );
+        self.min_l_estimate = Some(min_l_estimate);
+        self
+    }
+
+    /// Overrides the coefficient in $\gamma = \mathrm{coeff}/L$
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `gamma_l_coeff` is not in $(0, 1)$
+    ///
+    pub fn with_gamma_l_coeff(mut self, gamma_l_coeff: T) -> Self {
+        assert!(
+            gamma_l_coeff > T::zero() && gamma_l_coeff < T::one(),
+            "gamma_l_coeff must be in (0, 1)"
+        );
+        self.gamma_l_coeff = Some(gamma_l_coeff);
+        self
+    }
+
+    /// Overrides $\delta$ in the estimation of the initial Lipschitz constant
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `delta_lipschitz` is not positive
+    ///
+    pub fn with_delta_lipschitz(mut self, delta_lipschitz: T) -> Self {
+        assert!(
+            delta_lipschitz > T::zero(),
+            "delta_lipschitz must be positive"
+        );
+        self.delta_lipschitz = Some(delta_lipschitz);
+        self
+    }
+
+    /// Overrides $\epsilon$ in the estimation of the initial Lipschitz constant
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `epsilon_lipschitz` is not positive
+    ///
+    pub fn with_epsilon_lipschitz(mut self, epsilon_lipschitz: T) -> Self {
+        assert!(
+            epsilon_lipschitz > T::zero(),
+            "epsilon_lipschitz must be positive"
+        );
+        self.epsilon_lipschitz = Some(epsilon_lipschitz);
+        self
+    }
+
+    /// Overrides the maximum Lipschitz constant
+    ///
+    /// ## Panics
+    ///
+    /// The method panics if `max_lipschitz_constant` is not positive
+    ///
+    pub fn with_max_lipschitz_constant(mut self, max_lipschitz_constant: T) -> Self {
+        assert!(
+            max_lipschitz_constant > T::zero(),
+            "max_lipschitz_constant must be positive"
+        );
+        self.max_lipschitz_constant = Some(max_lipschitz_constant);
+        self
+    }
+
+    /// Minimum estimate of the Lipschitz constant (overridden or default)
+    pub fn min_l_estimate(&self) -> T {
+        self.min_l_estimate.unwrap_or_else(T::min_l_estimate)
+    }
+
+    /// Coefficient in $\gamma = \mathrm{coeff}/L$ (overridden or default)
+    pub fn gamma_l_coeff(&self) -> T {
+        self.gamma_l_coeff.unwrap_or_else(T::gamma_l_coeff)
+    }
+
+    /// Delta in the estimation of the initial Lipschitz constant (overridden
+    /// or default)
+    pub fn delta_lipschitz(&self) -> T {
+        self.delta_lipschitz.unwrap_or_else(T::delta_lipschitz)
+    }
+
+    /// Epsilon in the estimation of the initial Lipschitz constant (overridden
+    /// or default)
+    pub fn epsilon_lipschitz(&self) -> T {
+        self.epsilon_lipschitz.unwrap_or_else(T::epsilon_lipschitz)
+    }
+
+    /// Maximum Lipschitz constant (overridden or default)
+    pub fn max_lipschitz_constant(&self) -> T {
+        self.max_lipschitz_constant
+            .unwrap_or_else(T::max_lipschitz_constant)
+    }
+}
@@ -1,15 +1,23 @@
 //! Status of the result of a solver (number of iterations, etc)
 //!
 //!
-use std::time;
+use core::convert::TryFrom;
+use core::fmt;
+use core::time;
 
+use crate::constraints::Constraint;
 use crate::core::{ExitStatus, OptFloat};
 /// Solver status
 ///
 /// This structure contais information about the solver status. Instances of
 /// `SolverStatus` are returned by optimizers.
 ///
+/// If the feature `serde` is activated, `SolverStatus` can be serialized and
+/// deserialized; the solve time is serialized as an integer number of
+/// nanoseconds.
+///
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SolverStatus<T>
 where
     T: OptFloat,
@@ -19,11 +27,23 @@ where
     /// number of iterations for convergence
     num_iter: usize,
     /// time it took to solve
+    #[cfg_attr(feature = "serde", serde(with = "duration_nanos"))]
     solve_time: time::Duration,
     /// norm of the fixed-point residual (FPR)
     fpr_norm: T,
     /// cost value at the candidate solution
     cost_value: T,
+    /// final estimate of the Lipschitz constant of the gradient of the cost,
+    /// if the algorithm computes one
+    lipschitz_estimate: Option<T>,
+    /// final value of the step size, gamma
+    gamma: T,
+    /// number of evaluations of the cost function
+    #[cfg_attr(feature = "serde", serde(default))]
+    cost_evals: usize,
+    /// number of evaluations of the gradient of the cost
+    #[cfg_attr(feature = "serde", serde(default))]
+    grad_evals: usize,
 }
 
 impl<T> SolverStatus<T>
@@ -40,6 +60,10 @@ where
     /// - `fpr_norm` norm of the fixed-point residual; a gauge of the solution
     ///    quality
     /// - `cost_value` the value of the cost function at the solution
+    /// - `lipschitz_estimate` the final estimate of the Lipschitz constant of
+    ///   the gradient of the cost, or `None` if the algorithm does not
+    ///   estimate it
+    /// - `gamma` the final value of the step size
     ///
     pub fn new(
         exit_status: ExitStatus,
@@ -47,6 +71,8 @@ where
         solve_time: time::Duration,
         fpr_norm: T,
         cost_value: T,
+        lipschitz_estimate: Option<T>,
+        gamma: T,
     ) -> SolverStatus<T> {
         SolverStatus {
             exit_status,
@@ -54,9 +80,27 @@ where
             solve_time,
             fpr_norm,
             cost_value,
+            lipschitz_estimate,
+            gamma,
+            cost_evals: 0,
+            grad_evals: 0,
         }
     }
 
+    /// Sets the numbers of evaluations of the cost function and its gradient
+    /// (which are zero for a status constructed with `new`)
+    ///
+    /// ## Arguments
+    ///
+    /// - `cost_evals` number of evaluations of the cost function
+    /// - `grad_evals` number of evaluations of the gradient of the cost
+    ///
+    pub fn with_evaluations(mut self, cost_evals: usize, grad_evals: usize) -> SolverStatus<T> {
+        self.cost_evals = cost_evals;
+        self.grad_evals = grad_evals;
+        self
+    }
+
     /// whether the algorithm has converged
     pub fn has_converged(&self) -> bool {
         self.exit_status == ExitStatus::Converged
@@ -72,6 +116,32 @@ where
         self.solve_time
     }
 
+    /// number of iterations per second
+    ///
+    /// This is `0.0` if no iterations were performed and infinite if the
+    /// measured solve time is zero (e.g., if the timer is too coarse)
+    pub fn iterations_per_second(&self) -> f64 {
+        if self.num_iter == 0 {
+            return 0.0;
+        }
+        let seconds = self.solve_time.as_secs_f64();
+        if seconds == 0.0 {
+            return f64::INFINITY;
+        }
+        self.num_iter as f64 / seconds
+    }
+
+    /// average time per iteration
+    ///
+    /// This is zero if no iterations were performed
+    pub fn time_per_iteration(&self) -> time::Duration {
+        if self.num_iter == 0 {
+            return time::Duration::from_nanos(0);
+        }
+        let nanos = self.solve_time.as_nanos() / self.num_iter as u128;
+        time::Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
     /// norm of the fixed point residual
     pub fn norm_fpr(&self) -> T {
         self.fpr_norm
@@ -86,4 +156,439 @@ where
     pub fn exit_status(&self) -> ExitStatus {
         self.exit_status
     }
+
+    /// final estimate of the Lipschitz constant of the gradient of the cost;
+    /// this is `None` if the algorithm does not estimate it (e.g., FBS)
+    pub fn lipschitz_estimate(&self) -> Option<T> {
+        self.lipschitz_estimate
+    }
+
+    /// final value of the step size, gamma
+    pub fn final_gamma(&self) -> T {
+        self.gamma
+    }
+
+    /// number of evaluations of the cost function
+    pub fn cost_evaluations(&self) -> usize {
+        self.cost_evals
+    }
+
+    /// number of evaluations of the gradient of the cost
+    pub fn gradient_evaluations(&self) -> usize {
+        self.grad_evals
+    }
+
+    /// Aggregates the statuses of two solver runs, e.g., to collect
+    /// statistics over the problems solved along a horizon
+    ///
+    /// The numbers of iterations, the solve times and the numbers of cost and
+    /// gradient evaluations are summed, the largest
+    /// norm of the fixed-point residual, cost value and Lipschitz estimate
+    /// are kept, as well as the smallest step size. The exit status of the
+    /// merged status is the least converged one, according to the ordering
+    ///
+    /// `Converged` < `Stalled` < `NotConvergedIterations` < `Cycling`
+    /// < `LineSearchFailed` < `NotConvergedOutOfTime`
+    ///
+    /// ## Arguments
+    ///
+    /// - `other`: the status of another solver run
+    ///
+    /// ## Returns
+    ///
+    /// A new instance of `SolverStatus` with the aggregated statistics
+    ///
+    pub fn merge(&self, other: &Self) -> Self {
+        let exit_status =
+            if exit_status_rank(other.exit_status) > exit_status_rank(self.exit_status) {
+                other.exit_status
+            } else {
+                self.exit_status
+            };
+        let lipschitz_estimate = match (self.lipschitz_estimate, other.lipschitz_estimate) {
+            (Some(l_self), Some(l_other)) => Some(l_self.max(l_other)),
+            (l_self, l_other) => l_self.or(l_other),
+        };
+        SolverStatus {
+            exit_status,
+            num_iter: self.num_iter.saturating_add(other.num_iter),
+            solve_time: self.solve_time.saturating_add(other.solve_time),
+            fpr_norm: self.fpr_norm.max(other.fpr_norm),
+            cost_value: self.cost_value.max(other.cost_value),
+            lipschitz_estimate,
+            gamma: self.gamma.min(other.gamma),
+            cost_evals: self.cost_evals.saturating_add(other.cost_evals),
+            grad_evals: self.grad_evals.saturating_add(other.grad_evals),
+        }
+    }
+
+    /// Summary of the solver status for reporting
+    ///
+    /// ## Arguments
+    ///
+    /// - `solution`: the solution returned by the solver
+    /// - `constraint`: the set of constraints of the problem
+    /// - `tolerance`: tolerance of the feasibility check (see
+    ///   [Constraint::contains](../constraints/trait.Constraint.html#method.contains))
+    ///
+    /// ## Memory allocation
+    ///
+    /// The feasibility check may allocate memory (see `Constraint::contains`)
+    ///
+    pub fn summary<C>(&self, solution: &[T], constraint: &C, tolerance: T) -> StatusSummary<T>
+    where
+        C: Constraint<T>,
+    {
+        StatusSummary {
+            converged: self.has_converged(),
+            feasible: constraint.contains(solution, tolerance),
+            fpr_norm: self.fpr_norm,
+            cost: self.cost_value,
+            iterations: self.num_iter,
+            time_us: u64::try_from(self.solve_time.as_micros()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Compact human-readable summary of the solver status, e.g.,
+/// `converged in 23 iters (1.2ms), |fpr|=3.1e-7, cost=6.3400`
+impl<T> fmt::Display for SolverStatus<T>
+where
+    T: OptFloat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match self.exit_status {
+            ExitStatus::Converged => "converged",
+            ExitStatus::NotConvergedIterations => "not converged (max iterations)",
+            ExitStatus::NotConvergedOutOfTime => "not converged (out of time)",
+            ExitStatus::Cycling => "not converged (cycling)",
+            ExitStatus::Stalled => "not converged (stalled)",
+            ExitStatus::LineSearchFailed => "not converged (line search failed)",
+        };
+        write!(f, "{} in {} iters (", outcome, self.num_iter)?;
+        write_duration(f, self.solve_time)?;
+        let fpr_norm = self.fpr_norm.to_f64().unwrap_or(f64::NAN);
+        write!(f, "), |fpr|={:.1e}, cost=", fpr_norm)?;
+        let cost = self.cost_value.to_f64().unwrap_or(f64::NAN);
+        if cost == 0.0 || (1e-3..1e5).contains(&cost.abs()) {
+            write!(f, "{:.4}", cost)
+        } else {
+            write!(f, "{:.3e}", cost)
+        }
+    }
+}
+
+/// Rank of an exit status, from the most to the least converged one (used
+/// to merge solver statuses)
+fn exit_status_rank(exit_status: ExitStatus) -> u8 {
+    match exit_status {
+        ExitStatus::Converged => 0,
+        ExitStatus::Stalled => 1,
+        ExitStatus::NotConvergedIterations => 2,
+        ExitStatus::Cycling => 3,
+        ExitStatus::LineSearchFailed => 4,
+        ExitStatus::NotConvergedOutOfTime => 5,
+    }
+}
+
+/// Writes a duration in the most appropriate unit (µs, ms or s)
+fn write_duration(f: &mut fmt::Formatter<'_>, duration: time::Duration) -> fmt::Result {
+    let seconds = duration.as_secs_f64();
+    if seconds < 1e-3 {
+        write!(f, "{:.1}µs", seconds * 1e6)
+    } else if seconds < 1.0 {
+        write!(f, "{:.1}ms", seconds * 1e3)
+    } else {
+        write!(f, "{:.2}s", seconds)
+    }
+}
+
+/// Summary of a solver status, for reporting
+///
+/// Instances of `StatusSummary` are constructed with
+/// [SolverStatus::summary](struct.SolverStatus.html#method.summary)
+///
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusSummary<T>
+where
+    T: OptFloat,
+{
+    converged: bool,
+    feasible: bool,
+    fpr_norm: T,
+    cost: T,
+    iterations: usize,
+    time_us: u64,
+}
+
+impl<T> StatusSummary<T>
+where
+    T: OptFloat,
+{
+    /// whether the algorithm has converged
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// whether the solution satisfies the constraints (up to the tolerance)
+    pub fn feasible(&self) -> bool {
+        self.feasible
+    }
+
+    /// norm of the fixed point residual
+    pub fn fpr_norm(&self) -> T {
+        self.fpr_norm
+    }
+
+    /// value of the cost at the solution
+    pub fn cost(&self) -> T {
+        self.cost
+    }
+
+    /// number of iterations
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// solve time in microseconds
+    pub fn time_us(&self) -> u64 {
+        self.time_us
+    }
+}
+
+/// (De)serialization of durations as whole nanoseconds
+#[cfg(feature = "serde")]
+mod duration_nanos {
+    use core::convert::TryFrom;
+    use core::time;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(duration: &time::Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        u64::try_from(duration.as_nanos())
+            .unwrap_or(u64::MAX)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<time::Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u64::deserialize(deserializer).map(time::Duration::from_nanos)
+    }
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use crate::core::{ExitStatus, SolverStatus};
+    use std::time;
+
+    #[test]
+    fn t_solver_status_display_converged() {
+        let status = SolverStatus::new(
+            ExitStatus::Converged,
+            23,
+            time::Duration::from_micros(1_200),
+            3.1e-7,
+            6.34,
+            Some(12.5),
+            0.0625,
+        );
+        let text = format!("{}", status);
+        assert!(text.contains("converged in 23 iters"), "{}", text);
+        assert!(!text.contains("not converged"), "{}", text);
+        assert!(text.contains("(1.2ms)"), "{}", text);
+        assert!(text.contains("|fpr|=3.1e-7"), "{}", text);
+        assert!(text.contains("cost=6.34"), "{}", text);
+    }
+
+    #[test]
+    fn t_solver_status_display_not_converged() {
+        let status = SolverStatus::new(
+            ExitStatus::NotConvergedIterations,
+            500,
+            time::Duration::from_nanos(95_400),
+            2.5e-3_f32,
+            -1.5e6,
+            None,
+            0.5,
+        );
+        let text = format!("{}", status);
+        assert!(text.contains("not converged"), "{}", text);
+        assert!(text.contains("500 iters"), "{}", text);
+        assert!(text.contains("(95.4µs)"), "{}", text);
+        assert!(text.contains("|fpr|=2.5e-3"), "{}", text);
+        assert!(text.contains("cost=-1.500e6"), "{}", text);
+
+        let status = SolverStatus::new(
+            ExitStatus::NotConvergedOutOfTime,
+            7,
+            time::Duration::from_millis(2_500),
+            1.0,
+            0.0,
+            None,
+            0.5,
+        );
+        let text = format!("{}", status);
+        assert!(text.contains("out of time"), "{}", text);
+        assert!(text.contains("(2.50s)"), "{}", text);
+    }
+
+    #[test]
+    fn t_solver_status_throughput() {
+        let status = SolverStatus::new(
+            ExitStatus::Converged,
+            250,
+            time::Duration::from_millis(5),
+            1e-6,
+            1.0,
+            None,
+            0.5,
+        );
+        unit_test_utils::assert_nearly_equal(
+            50_000.0,
+            status.iterations_per_second(),
+            1e-10,
+            1e-12,
+            "iterations per second",
+        );
+        assert_eq!(time::Duration::from_micros(20), status.time_per_iteration());
+
+        let status = SolverStatus::new(
+            ExitStatus::NotConvergedIterations,
+            3,
+            time::Duration::from_nanos(100),
+            1e-6_f32,
+            1.0,
+            None,
+            0.5,
+        );
+        assert_eq!(time::Duration::from_nanos(33), status.time_per_iteration());
+    }
+
+    #[test]
+    fn t_solver_status_throughput_edge_cases() {
+        let no_iterations = SolverStatus::new(
+            ExitStatus::Converged,
+            0,
+            time::Duration::from_micros(3),
+            0.0,
+            1.0,
+            None,
+            0.5,
+        );
+        assert_eq!(0.0, no_iterations.iterations_per_second());
+        assert_eq!(
+            time::Duration::from_nanos(0),
+            no_iterations.time_per_iteration()
+        );
+
+        let no_time = SolverStatus::new(
+            ExitStatus::Converged,
+            10,
+            time::Duration::from_nanos(0),
+            0.0,
+            1.0,
+            None,
+            0.5,
+        );
+        assert!(no_time.iterations_per_second().is_infinite());
+        assert_eq!(time::Duration::from_nanos(0), no_time.time_per_iteration());
+    }
+
+    #[test]
+    fn t_solver_status_merge() {
+        let status_a = SolverStatus::new(
+            ExitStatus::Converged,
+            10,
+            time::Duration::from_micros(300),
+            1e-6,
+            2.0,
+            Some(4.0),
+            0.25,
+        )
+        .with_evaluations(25, 20);
+        let status_b = SolverStatus::new(
+            ExitStatus::NotConvergedIterations,
+            100,
+            time::Duration::from_micros(700),
+            1e-3,
+            -1.0,
+            None,
+            0.125,
+        )
+        .with_evaluations(200, 150);
+        let merged = status_a.merge(&status_b);
+        assert_eq!(ExitStatus::NotConvergedIterations, merged.exit_status());
+        assert_eq!(110, merged.iterations());
+        assert_eq!(time::Duration::from_micros(1_000), merged.solve_time());
+        assert_eq!(1e-3, merged.norm_fpr());
+        assert_eq!(2.0, merged.cost_value());
+        assert_eq!(Some(4.0), merged.lipschitz_estimate());
+        assert_eq!(0.125, merged.final_gamma());
+        assert_eq!(225, merged.cost_evaluations());
+        assert_eq!(170, merged.gradient_evaluations());
+        assert_eq!(merged, status_b.merge(&status_a));
+    }
+
+    #[test]
+    fn t_solver_status_merge_exit_status_order() {
+        let status = |exit_status| {
+            SolverStatus::new(
+                exit_status,
+                1,
+                time::Duration::from_nanos(1),
+                0.0,
+                0.0,
+                None,
+                1.0,
+            )
+        };
+        let ordered = [
+            ExitStatus::Converged,
+            ExitStatus::Stalled,
+            ExitStatus::NotConvergedIterations,
+            ExitStatus::Cycling,
+            ExitStatus::LineSearchFailed,
+            ExitStatus::NotConvergedOutOfTime,
+        ];
+        for (i, &exit_status_i) in ordered.iter().enumerate() {
+            for (j, &exit_status_j) in ordered.iter().enumerate() {
+                let merged = status(exit_status_i).merge(&status(exit_status_j));
+                assert_eq!(ordered[usize::max(i, j)], merged.exit_status());
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn t_solver_status_json_round_trip() {
+        let status = SolverStatus::new(
+            ExitStatus::NotConvergedOutOfTime,
+            42,
+            time::Duration::from_nanos(1_234_567_891),
+            1.5e-7,
+            -3.25,
+            Some(12.5),
+            0.0625,
+        );
+        let json = serde_json::to_string(&status).unwrap();
+        assert!(json.contains("1234567891"));
+        let status_copy: SolverStatus<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(status, status_copy);
+        assert_eq!(ExitStatus::NotConvergedOutOfTime, status_copy.exit_status());
+        assert_eq!(42, status_copy.iterations());
+        assert_eq!(1_234_567_891, status_copy.solve_time().as_nanos());
+        assert_eq!(1.5e-7, status_copy.norm_fpr());
+        assert_eq!(-3.25, status_copy.cost_value());
+        assert_eq!(Some(12.5), status_copy.lipschitz_estimate());
+        assert_eq!(0.0625, status_copy.final_gamma());
+    }
 }
@@ -0,0 +1,342 @@
+//! Trust-region-style solution of unconstrained problems
+//!
+//! The problem $\min_{u} f(u)$ is solved by a sequence of subproblems,
+//!
+//! $$
+//! \min_{u} f(u) \text{ subject to } \Vert u - u_k \Vert \leq \Delta_k,
+//! $$
+//!
+//! whose trust-region radius, $\Delta_k$, is adapted based on how well a
+//! quadratic model of $f$ predicts the actual reduction of the cost.
+//!
+use alloc::vec;
+
+use crate::constraints::{Ball2, NoConstraints};
+use crate::core::panoc::{PANOCCache, PANOCOptimizer};
+use crate::core::{ExitStatus, OptFloat, Optimizer, Problem, SolverStatus};
+use crate::{matrix_operations, FunctionCallResult, SolverError};
+
+/// A step whose length is at least this fraction of the radius is considered
+/// to be on the boundary of the trust region
+const BOUNDARY_FRACTION: f64 = 0.99;
+
+/// Solves an unconstrained problem with PANOC by a sequence of trust-region
+/// subproblems, adapting the radius of the trust region between solves
+///
+/// Every subproblem minimizes the cost over the ball of radius $\Delta_k$
+/// centered at the current point, $u_k$, and is warm-started from $u_k$
+/// (the LBFGS buffer is preserved across subproblems). Let $d$ be the step
+/// to the solution of the subproblem and $g_k$ the gradient at $u_k$. The
+/// reduction of the cost predicted by the quadratic model of $f$ at $u_k$,
+/// $f(u_k) + \langle g_k, d \rangle + \tfrac{b_k}{2}\Vert d \Vert^2$, is
+///
+/// $$
+/// \mathrm{pred} = -\langle g_k, d \rangle - \tfrac{b_k}{2}\Vert d \Vert^2,
+/// $$
+///
+/// where the curvature $b_k$ is the secant estimate
+/// $\langle g_{j+1} - g_j, s_j \rangle / \Vert s_j \Vert^2$ of the last accepted
+/// step, $s_j = u_{j+1} - u_j$, or, before any step has been accepted, the
+/// estimate of the Lipschitz constant of $\nabla f$ of the subproblem. The
+/// model only uses information which is available at $u_k$, and the ratio $\rho = (f(u_k) - f(u_k + d))/\mathrm{pred}$ determines the
+/// next radius: if $\rho < 1/4$, the radius is divided by 4; if $\rho > 3/4$
+/// and the step is on the boundary of the trust region, the radius is
+/// doubled (see `Ball2::scaled`). The step is accepted if it reduces the
+/// cost. The iterations stop when a subproblem converges to a point in the
+/// interior of the trust region, which is then a solution of the
+/// unconstrained problem.
+///
+/// ## Arguments
+///
+/// - `problem`: the (unconstrained) problem
+/// - `ball`: a ball centered at the origin, whose radius is the initial
+///   radius of the trust region; on exit, its radius is the final radius
+/// - `cache`: an instance of `PANOCCache` (constructed once)
+/// - `u`: on entry, the initial guess; on exit, the solution
+/// - `max_iter`: maximum number of iterations for every subproblem
+/// - `max_solves`: maximum number of subproblems
+///
+/// ## Returns
+///
+/// Returns the solver status, or the first error that was encountered. The
+/// numbers of iterations and evaluations and the solve time are summed over
+/// all subproblems (see `SolverStatus::merge`); the other fields are those of
+/// the last subproblem. If no subproblem converges in the interior of the
+/// trust region within `max_solves` solves, the exit status is
+/// `NotConvergedIterations`.
+///
+/// ## Panics
+///
+/// The method panics if `ball` is not centered at the origin, or if
+/// `max_iter` or `max_solves` is zero
+///
+/// ## Memory allocation
+///
+/// The method allocates memory for the current point and two gradients
+///
+/// ## Example
+///
+/// ```
+/// use optimization_engine::{constraints::*, core::*, panoc::*, FunctionCallResult};
+///
+/// let cost = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+///     *c = (1.0 - u[0]).powi(2) + 100.0 * (u[1] - u[0] * u[0]).powi(2);
+///     Ok(())
+/// };
+/// let gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+///     grad[0] = 2.0 * (u[0] - 1.0) - 400.0 * u[0] * (u[1] - u[0] * u[0]);
+///     grad[1] = 200.0 * (u[1] - u[0] * u[0]);
+///     Ok(())
+/// };
+/// let bounds = NoConstraints::new();
+/// let problem = Problem::new(&bounds, gradient, cost);
+/// let mut ball = Ball2::new(None, 0.5);
+/// let mut cache = PANOCCache::new(2, 1e-8, 5);
+/// let mut u = [-1.5, 0.9];
+///
+/// let status = trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 100, 50).unwrap();
+/// assert!(status.has_converged());
+/// assert!(status.cost_value() < 1e-5);
+/// ```
+///
+pub fn trust_region_solve<GradientType, CostType, T>(
+    problem: &Problem<'_, GradientType, NoConstraints, CostType, T>,
+    ball: &mut Ball2<'_, T>,
+    cache: &mut PANOCCache<T>,
+    u: &mut [T],
+    max_iter: usize,
+    max_solves: usize,
+) -> Result<SolverStatus<T>, SolverError>
+where
+    GradientType: Fn(&[T], &mut [T]) -> FunctionCallResult,
+    CostType: Fn(&[T], &mut T) -> FunctionCallResult,
+    T: OptFloat,
+{
+    assert!(
+        ball.center().is_none(),
+        "the ball must be centered at the origin"
+    );
+    assert!(max_iter > 0, "max_iter must be positive");
+    assert!(max_solves > 0, "max_solves must be positive");
+
+    let boundary_fraction = T::from(BOUNDARY_FRACTION).unwrap();
+    let quarter = T::from(0.25).unwrap();
+    let three_quarters = T::from(0.75).unwrap();
+    let two = T::from(2.0).unwrap();
+
+    let mut center = u.to_vec();
+    let mut cost_center = problem.cost(&center)?;
+    let mut grad_center = vec![T::zero(); u.len()];
+    let mut grad_trial = vec![T::zero(); u.len()];
+    problem.gradient(&center, &mut grad_center)?;
+
+    // secant estimate of the curvature along the last accepted step
+    let mut curvature = None;
+    let mut total_status: Option<SolverStatus<T>> = None;
+    let mut last_status = None;
+    for k in 0..max_solves {
+        let radius = ball.radius();
+        let region = Ball2::new(Some(&center), radius);
+        let subproblem = Problem::new(&region, &problem.gradf, &problem.cost);
+        let mut optimizer = PANOCOptimizer::new(subproblem, &mut *cache).with_max_iter(max_iter);
+        if k > 0 {
+            optimizer = optimizer.with_lbfgs_warm_start();
+        }
+        u.copy_from_slice(&center);
+        let subproblem_status = optimizer.solve(u)?;
+        total_status = Some(match total_status {
+            Some(total) => total.merge(&subproblem_status),
+            None => subproblem_status,
+        });
+        last_status = Some(subproblem_status);
+
+        let step_norm = matrix_operations::norm2_diff(u, &center);
+        let on_boundary = step_norm >= boundary_fraction * radius;
+        if subproblem_status.has_converged() && !on_boundary {
+            return Ok(accumulated_status(
+                ExitStatus::Converged,
+                &total_status.unwrap(),
+                &subproblem_status,
+            ));
+        }
+
+        // ratio of the actual to the predicted reduction
+        let cost_trial = problem.cost(u)?;
+        let (gradient_step, step_norm_squared) = u
+            .iter()
+            .zip(center.iter())
+            .zip(grad_center.iter())
+            .fold((T::zero(), T::zero()), |(gs, ss), ((&ui, &ci), &gc)| {
+                (gs + gc * (ui - ci), ss + (ui - ci) * (ui - ci))
+            });
+        let b = curvature
+            .or_else(|| subproblem_status.lipschitz_estimate())
+            .unwrap_or_else(T::zero);
+        let predicted_reduction = -gradient_step - b * step_norm_squared / two;
+        let actual_reduction = cost_center - cost_trial;
+        let ratio = if predicted_reduction > T::zero() {
+            actual_reduction / predicted_reduction
+        } else {
+            T::zero()
+        };
+
+        if ratio < quarter {
+            *ball = ball.scaled(quarter);
+        } else if ratio > three_quarters && on_boundary {
+            *ball = ball.scaled(two);
+        }
+        if actual_reduction > T::zero() {
+            problem.gradient(u, &mut grad_trial)?;
+            let secant_step = u
+                .iter()
+                .zip(center.iter())
+                .zip(grad_center.iter().zip(grad_trial.iter()))
+                .fold(T::zero(), |sum, ((&ui, &ci), (&gc, &gt))| {
+                    sum + (gt - gc) * (ui - ci)
+                });
+            if step_norm_squared > T::zero() {
+                curvature = Some(secant_step / step_norm_squared);
+            }
+            center.copy_from_slice(u);
+            cost_center = cost_trial;
+            grad_center.copy_from_slice(&grad_trial);
+        } else {
+            u.copy_from_slice(&center);
+        }
+    }
+
+    Ok(accumulated_status(
+        ExitStatus::NotConvergedIterations,
+        &total_status.unwrap(),
+        &last_status.unwrap(),
+    ))
+}
+
+/// Status with the given exit status, the iterations, solve time and
+/// evaluations of `total` and the remaining fields of `last`
+fn accumulated_status<T>(
+    exit_status: ExitStatus,
+    total: &SolverStatus<T>,
+    last: &SolverStatus<T>,
+) -> SolverStatus<T>
+where
+    T: OptFloat,
+{
+    SolverStatus::new(
+        exit_status,
+        total.iterations(),
+        total.solve_time(),
+        last.norm_fpr(),
+        last.cost_value(),
+        last.lipschitz_estimate(),
+        last.final_gamma(),
+    )
+    .with_evaluations(total.cost_evaluations(), total.gradient_evaluations())
+}
+
+/* --------------------------------------------------------------------------------------------- */
+/*       TESTS                                                                                   */
+/* --------------------------------------------------------------------------------------------- */
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mocks;
+
+    #[test]
+    fn t_trust_region_rosenbrock() {
+        let (a, b) = (1.0, 100.0);
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(a, b, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(a, b, u);
+            Ok(())
+        };
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut cache = PANOCCache::new(2, 1e-8, 5);
+
+        // the initial radius is small, so the first steps are cut short by
+        // the trust region and the radius grows
+        let initial_radius = 0.05;
+        let mut ball = Ball2::new(None, initial_radius);
+        let mut u = [-1.5, 0.9];
+        let status = trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 100, 200).unwrap();
+        assert!(status.has_converged());
+        assert!(ball.radius() > initial_radius);
+        unit_test_utils::assert_nearly_equal_array(&[a, a * a], &u, 1e-5, 1e-6, "u");
+    }
+
+    #[test]
+    fn t_trust_region_interior_solution() {
+        // f(u) = (u_0^2 - 1)^2; the minimizer u_0 = 1 is in the interior of
+        // the initial trust region, so the first subproblem solves the problem
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            grad[0] = 4.0 * u[0] * (u[0] * u[0] - 1.0);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = (u[0] * u[0] - 1.0).powi(2);
+            Ok(())
+        };
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut cache = PANOCCache::new(1, 1e-10, 3);
+
+        let mut ball = Ball2::new(None, 100.0);
+        let mut u = [3.0];
+        let status = trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 100, 1).unwrap();
+        assert!(status.has_converged());
+        unit_test_utils::assert_nearly_equal(100.0, ball.radius(), 1e-12, 1e-12, "radius");
+        unit_test_utils::assert_nearly_equal(1.0, u[0], 1e-6, 1e-8, "u");
+    }
+
+    #[test]
+    fn t_trust_region_max_solves() {
+        let cost_gradient = |u: &[f64], grad: &mut [f64]| -> FunctionCallResult {
+            mocks::rosenbrock_grad(1.0, 100.0, u, grad);
+            Ok(())
+        };
+        let cost_function = |u: &[f64], c: &mut f64| -> FunctionCallResult {
+            *c = mocks::rosenbrock_cost(1.0, 100.0, u);
+            Ok(())
+        };
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(&bounds, cost_gradient, cost_function);
+        let mut cache = PANOCCache::new(2, 1e-8, 5);
+
+        let mut ball = Ball2::new(None, 0.01);
+        let mut u = [-1.5, 0.9];
+        let status = trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 1000, 1).unwrap();
+        assert_eq!(ExitStatus::NotConvergedIterations, status.exit_status());
+        assert!(matrix_operations::norm2_diff(&u, &[-1.5, 0.9]) <= 0.01 + 1e-12);
+
+        // the first subproblem is the same as above; the iterations and
+        // evaluations of all subproblems are summed
+        let mut cache = PANOCCache::new(2, 1e-8, 5);
+        let mut ball = Ball2::new(None, 0.01);
+        let mut u = [-1.5, 0.9];
+        let status_three =
+            trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 1000, 3).unwrap();
+        assert_eq!(
+            ExitStatus::NotConvergedIterations,
+            status_three.exit_status()
+        );
+        assert!(status_three.iterations() >= status.iterations() + 2);
+        assert!(status_three.gradient_evaluations() > status.gradient_evaluations());
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_trust_region_centered_ball() {
+        let bounds = NoConstraints::new();
+        let problem = Problem::new(&bounds, mocks::my_gradient, mocks::my_cost);
+        let center = [1.0, 1.0];
+        let mut ball = Ball2::new(Some(&center), 1.0);
+        let mut cache = PANOCCache::new(2, 1e-8, 5);
+        let mut u = [0.0; 2];
+        let _ = trust_region_solve(&problem, &mut ball, &mut cache, &mut u, 100, 10);
+    }
+}
@@ -1,4 +1,5 @@
 #![deny(missing_docs)]
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 //! **Optimization Engine** is a framework for **fast** and **accurate** embedded nonconvex optimization.
 //!
 //! # About Optimization Engine
@@ -37,9 +38,21 @@
 //!   are mappings with smooth partial derivatives, and
 //! - $C\subseteq\mathbb{R}^{n_1}$ is a convex closed set on which we can easily compute projections.
 //!
+//! # Targets without `std`
+//!
+//! The feature `std` is activated by default. Without it (that is, when
+//! compiled with `--no-default-features`), the crate is `no_std` and only
+//! requires an allocator (`alloc`), so it can be used on bare-metal targets.
+//! In that case, [AffineSpace](constraints/struct.AffineSpace.html) and
+//! [EpigraphSquaredNorm](constraints/struct.EpigraphSquaredNorm.html) are not
+//! available, and the solvers measure time using a user-provided
+//! [Clock](core/clock/trait.Clock.html).
+//!
 
 extern crate num;
 
+extern crate alloc;
+
 /// Exceptions/Errors that may arise while solving a problem
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SolverError {
@@ -47,6 +60,22 @@ pub enum SolverError {
     Cost,
     /// Computation failed and NaN/Infinite value was obtained
     NotFiniteComputation,
+    /// Computation failed and the solution has a NaN/Infinite value
+    NotFiniteSolution {
+        /// index of the first NaN/Infinite element of the solution
+        index: usize,
+        /// number of iterations performed when the NaN/Infinite value was
+        /// detected
+        iteration: usize,
+    },
+    /// An invalid argument was provided (e.g., a nonpositive radius or
+    /// incompatible dimensions) to a non-panicking constructor, such as
+    /// `Ball2::try_new`
+    InvalidArgument,
+    /// The problem is not supported by the algorithm (e.g., the constraints
+    /// are not convex, whereas the algorithm has been configured to require
+    /// convex constraints)
+    InvalidProblem,
 }
 
 /// Result of a function call (status)
@@ -9,8 +9,14 @@
 //!
 //! This method computes a numerical approximation of the norm of the directional
 //! derivative of a function $F:\mathbb{R}^n \to \mathbb{R}^n$ at a point $u\in\mathbb{R}^n$
-//! along a direction $h \in \mathbb{R}^n$ with $h_i = \max \\{\delta, \epsilon u_i\\}$,
-//! where $\delta$ and $\epsilon$ are given small numbers.
+//! along a direction $h \in \mathbb{R}^n$ with $h_i = \max \\{\delta, \epsilon |u_i|\\}$,
+//! where $\delta$ (absolute perturbation) and $\epsilon$ (relative perturbation)
+//! are given small numbers. By default, these are `delta_lipschitz` and
+//! `epsilon_lipschitz` of the float type (see
+//! [OptFloat](../core/opt_float/trait.OptFloat.html)), that is, the values
+//! used by PANOC. For problems whose variables are very small or very large,
+//! these should be adapted to the scale of the variables using `with_delta`
+//! and `with_epsilon`.
 //!
 //! The estimated (local) Lipschitz constant is
 //!
@@ -18,6 +24,24 @@
 //! L_F(u) = \frac{\Vert{}F(u + h) - F(u){}\Vert}{\Vert{}h{}\Vert}
 //! $$
 //!
+//! When $F$ is the gradient of a cost function, the estimate can be used, for
+//! example, to choose the step size of the forward-backward splitting method.
+//!
+//! Since $L_F(u)$ is the norm of the directional derivative along a single
+//! direction, it may underestimate the Lipschitz constant, or be inaccurate
+//! near kinks of $F$. Alternatively, the largest eigenvalue of the Jacobian
+//! of $F$ at $u$ can be approximated with a few (matrix-free) power
+//! iterations,
+//!
+//! $$
+//! w = \frac{F(u + \eta v) - F(u)}{\eta},
+//! \quad
+//! v \leftarrow \frac{w}{\Vert{}w{}\Vert},
+//! $$
+//!
+//! where $\eta = \max\\{\delta, \epsilon\Vert{}u{}\Vert\\}$; this uses
+//! only evaluations of $F$ (see `LipschitzEstimator::estimate_power`).
+//!
 //! # Example
 //!
 //! ```
@@ -30,44 +54,46 @@
 //!     Ok(())
 //! }
 //!
-//! let mut u: [f64; 3] = [1.0, 2.0, 3.0];
-//! let mut function_value = [0.0; 3];
-//! let mut lip_estimator = LipschitzEstimator::new(&mut u, &F, &mut function_value);
-//! let lip = lip_estimator.estimate_local_lipschitz();
+//! let u: [f64; 3] = [1.0, 2.0, 3.0];
+//! let mut lip_estimator = LipschitzEstimator::new(F, 3);
+//! let lip = lip_estimator.estimate(&u).unwrap();
 //! ```
 //!
 
+use alloc::{vec, vec::Vec};
+
 use crate::core::OptFloat;
 use crate::{matrix_operations, SolverError};
 
-const DEFAULT_DELTA: f64 = 1e-6;
-const DEFAULT_EPSILON: f64 = 1e-6;
-
 /// Structure for the computation of estimates of the Lipschitz constant of mappings
-pub struct LipschitzEstimator<'a, F, T>
+///
+/// The estimator allocates its workspace once, upon construction, so it can
+/// be used to compute estimates at several points without allocating memory.
+pub struct LipschitzEstimator<F, T>
 where
     F: Fn(&[T], &mut [T]) -> Result<(), SolverError>,
     T: OptFloat,
 {
-    /// `u_decision_var` is the point where the Lipschitz constant is estimated
-    u_decision_var: &'a mut [T],
-    ///  internally allocated workspace memory
-    workspace: Vec<T>,
-    /// `function_value_at_u` a vector which is updated with the
-    /// value of the given function, `F`, at `u`; the provided value
-    /// of `function_value_at_u_p` is not used
-    function_value_at_u: &'a mut [T],
     ///
     /// Function whose Lipschitz constant is to be approximated
     ///
     /// For example, in optimization, this is the gradient (Jacobian matrix)
     /// of the cost function (this is a closure)
-    function: &'a F,
+    function: F,
+    /// Perturbed point, $u + h$
+    u_perturbed: Vec<T>,
+    /// Value of the given function, `F`, at the last point where the
+    /// Lipschitz constant was estimated
+    function_value_at_u: Vec<T>,
+    /// Internally allocated workspace memory
+    workspace: Vec<T>,
+    /// Direction of the power iteration
+    direction: Vec<T>,
     epsilon_lip: T,
     delta_lip: T,
 }
 
-impl<'a, F, T> LipschitzEstimator<'a, F, T>
+impl<F, T> LipschitzEstimator<F, T>
 where
     F: Fn(&[T], &mut [T]) -> Result<(), SolverError>,
     T: OptFloat,
@@ -76,33 +102,27 @@ where
     ///
     /// # Arguments
     ///
-    /// - `u_` On entry: point where the Lipschitz constant is estimated,
-    ///    On exit: the provided slice is modified (this is why it is a mutable
-    ///    reference). The value of `u_` at exit is slightly perturbed. If you need
-    ///    to keep the original value of `u_`, you need to make a copy of the variable
-    ///    before you provide it to this method.
-    /// - `f_` given closure
-    /// - `function_value_` externally allocated memory which on exit stores the
-    ///    value of the given function at `u_`, that is `f_(u_)`
+    /// - `function` given closure (a reference to a closure can also be
+    ///   provided)
+    /// - `dimension` dimension, $n$, of the domain and range of the function
     ///
     /// # Returns
     ///
     /// New instance of `LipschitzEstimator`
     ///
+    /// # Memory allocation
+    ///
+    /// Allocates memory for four vectors of length `dimension`
     ///
-    pub fn new(
-        u_: &'a mut [T],
-        f_: &'a F,
-        function_value_: &'a mut [T],
-    ) -> LipschitzEstimator<'a, F, T> {
-        let n: usize = u_.len();
+    pub fn new(function: F, dimension: usize) -> LipschitzEstimator<F, T> {
         LipschitzEstimator {
-            u_decision_var: u_,
-            workspace: vec![T::zero(); n],
-            function_value_at_u: function_value_,
-            function: f_,
-            epsilon_lip: T::from(DEFAULT_EPSILON).unwrap(),
-            delta_lip: T::from(DEFAULT_DELTA).unwrap(),
+            function,
+            u_perturbed: vec![T::zero(); dimension],
+            function_value_at_u: vec![T::zero(); dimension],
+            workspace: vec![T::zero(); dimension],
+            direction: vec![T::zero(); dimension],
+            epsilon_lip: T::epsilon_lipschitz(),
+            delta_lip: T::delta_lipschitz(),
         }
     }
 
@@ -111,7 +131,8 @@ where
     ///
     /// # Arguments
     ///
-    /// - `delta`: parameter delta (the default value is `1e-6`)
+    /// - `delta`: absolute perturbation, $\delta$ (the default value is
+    ///   `T::delta_lipschitz()`)
     ///
     /// # Panics
     /// The method will panic if `delta` is non positive
@@ -127,7 +148,8 @@ where
     ///
     /// # Arguments
     ///
-    /// - `epsilon`: parameter epsilon  (the default value is `1e-6`)
+    /// - `epsilon`: relative perturbation, $\epsilon$ (the default value is
+    ///   `T::epsilon_lipschitz()`)
     ///
     /// # Panics
     /// The method will panic if `epsilon` is non positive
@@ -139,91 +161,210 @@ where
     }
 
     ///
-    /// Getter method for the Jacobian
+    /// Getter method for the value of the function
     ///
     /// During the computation of the local lipschitz constant at `u`,
     /// the value of the given function at `u` is computed and stored
     /// internally. This method returns a pointer to that vector.
     ///
-    /// If `estimate_local_lipschitz` has not been computed, the result
-    /// will point to a zero vector.
+    /// If `estimate` has not been called, the result will point to a zero vector.
     pub fn get_function_value(&self) -> &[T] {
-        self.function_value_at_u
+        &self.function_value_at_u
     }
 
     ///
-    /// Evaluates a local Lipschitz constant of a given function
+    /// Evaluates a local Lipschitz constant of the given function at `u`
     ///
-    /// Functions are closures of type `F` as shown here.
+    /// # Arguments
     ///
-    /// # Returns
+    /// - `u`: point where the Lipschitz constant is estimated (it is not modified)
     ///
-    /// - estimate of local Lipschitz constant (at point `u`)
+    /// # Returns
     ///
+    /// - estimate of local Lipschitz constant (at point `u`), or the error
+    ///   returned by the given function
     ///
     /// # Example
     ///
     /// ```
     /// use optimization_engine::SolverError;
+    /// use optimization_engine::lipschitz_estimator::LipschitzEstimator;
     ///
-    /// let mut u = [1.0, 2.0, 3.0];
-    /// let mut function_value = [0.0; 3];
     /// let f = |u: &[f64], g: &mut [f64]| -> Result<(), SolverError> {
     ///     g[0] = 3.0 * u[0];
     ///     g[1] = 2.0 * u[1];
     ///     g[2] = 4.5;
     ///     Ok(())
     /// };
-    /// let mut lip_estimator =
-    ///     optimization_engine::lipschitz_estimator::LipschitzEstimator::new(&mut u, &f, &mut function_value);
-    /// let lip = lip_estimator.estimate_local_lipschitz();
+    /// let mut lip_estimator = LipschitzEstimator::new(f, 3);
+    /// let lip = lip_estimator.estimate(&[1.0, 2.0, 3.0]).unwrap();
+    /// let lip_other = lip_estimator.estimate(&[-1.0, 0.5, 8.0]).unwrap();
     /// ```
     ///
-    ///
     /// # Panics
-    /// No rust-side panics, unless the C function which is called via this interface
-    /// fails.
     ///
-    pub fn estimate_local_lipschitz(&mut self) -> Result<T, SolverError> {
-        // function_value = gradient(u, p)
-        (self.function)(self.u_decision_var, self.function_value_at_u)?;
-        let epsilon_lip = self.epsilon_lip;
-        let delta_lip = self.delta_lip;
-
-        // workspace = h = max{epsilon * u, delta}
-        self.workspace
-            .iter_mut()
-            .zip(self.u_decision_var.iter())
-            .for_each(|(out, &s)| {
-                *out = if epsilon_lip * s > delta_lip {
-                    epsilon_lip * s
-                } else {
-                    delta_lip
-                }
-            });
-        let norm_h = matrix_operations::norm2(&self.workspace);
+    /// The method panics if the length of `u` is not equal to the dimension
+    /// which was specified in the constructor
+    ///
+    pub fn estimate(&mut self, u: &[T]) -> Result<T, SolverError> {
+        assert_eq!(
+            self.u_perturbed.len(),
+            u.len(),
+            "u has incompatible dimensions"
+        );
+        self.u_perturbed.copy_from_slice(u);
+        estimate_local_lipschitz(
+            &self.function,
+            &mut self.u_perturbed,
+            &mut self.function_value_at_u,
+            &mut self.workspace,
+            self.delta_lip,
+            self.epsilon_lip,
+        )
+    }
 
-        // u += workspace
-        // u = u + h
-        self.u_decision_var
-            .iter_mut()
-            .zip(self.workspace.iter())
-            .for_each(|(out, a)| *out += *a);
+    ///
+    /// Estimates the Lipschitz constant of the given function at `u` using
+    /// power iterations
+    ///
+    /// The largest (in absolute value) eigenvalue of the Jacobian of $F$ at
+    /// $u$ is approximated with `num_iters` power iterations, where the
+    /// products of the Jacobian with a vector $v$ are approximated by finite
+    /// differences, $(F(u + \eta v) - F(u))/\eta$, with
+    /// $\eta = \max\\{\delta, \epsilon\Vert{}u{}\Vert\\}$. If $F$ is the gradient of
+    /// a function, its Jacobian is symmetric, so this is its spectral norm.
+    ///
+    /// Each iteration costs one evaluation of $F$ and the buffers which were
+    /// allocated upon construction are reused.
+    ///
+    /// # Arguments
+    ///
+    /// - `u`: point where the Lipschitz constant is estimated (it is not modified)
+    /// - `num_iters`: number of power iterations
+    ///
+    /// # Returns
+    ///
+    /// - the absolute value of the Rayleigh quotient, $|v^\intercal J_F(u) v|$,
+    ///   where $v$ is the last (normalized) iterate, or the error returned by
+    ///   the given function
+    ///
+    /// # Panics
+    ///
+    /// The method panics if the length of `u` is not equal to the dimension
+    /// which was specified in the constructor, or if `num_iters` is zero
+    ///
+    pub fn estimate_power(&mut self, u: &[T], num_iters: usize) -> Result<T, SolverError> {
+        assert_eq!(
+            self.u_perturbed.len(),
+            u.len(),
+            "u has incompatible dimensions"
+        );
+        assert!(num_iters > 0, "num_iters must be positive");
 
-        // workspace = F(u + h)
-        (self.function)(self.u_decision_var, &mut self.workspace)?;
+        // function_value = F(u)
+        (self.function)(u, &mut self.function_value_at_u)?;
+        let eta = T::max(
+            self.delta_lip,
+            self.epsilon_lip * matrix_operations::norm2(u),
+        );
 
-        // workspace = F(u + h) - F(u)
-        self.workspace
+        // initial direction: v = (1, ..., 1)/sqrt(n)
+        let n = T::from(u.len()).unwrap();
+        self.direction
             .iter_mut()
-            .zip(self.function_value_at_u.iter())
-            .for_each(|(out, a)| *out -= *a);
-
-        let norm_workspace = matrix_operations::norm2(&self.workspace);
-        Ok(norm_workspace / norm_h)
+            .for_each(|vi| *vi = T::one() / n.sqrt());
+
+        let mut rayleigh_quotient = T::zero();
+        for _ in 0..num_iters {
+            // u_perturbed = u + eta * v
+            self.u_perturbed
+                .iter_mut()
+                .zip(u.iter().zip(self.direction.iter()))
+                .for_each(|(out, (&ui, &vi))| *out = ui + eta * vi);
+
+            // workspace = w = (F(u + eta * v) - F(u)) / eta
+            (self.function)(&self.u_perturbed, &mut self.workspace)?;
+            self.workspace
+                .iter_mut()
+                .zip(self.function_value_at_u.iter())
+                .for_each(|(out, &a)| *out = (*out - a) / eta);
+
+            rayleigh_quotient = matrix_operations::inner_product(&self.direction, &self.workspace);
+            let norm_w = matrix_operations::norm2(&self.workspace);
+            if norm_w <= T::zero() {
+                break;
+            }
+
+            // v = w / ||w||
+            self.direction
+                .iter_mut()
+                .zip(self.workspace.iter())
+                .for_each(|(vi, &wi)| *vi = wi / norm_w);
+        }
+        Ok(rayleigh_quotient.abs())
     }
 }
 
+/// Evaluates a local Lipschitz constant of `function` at `u` using
+/// externally allocated memory
+///
+/// This is used by PANOC, so that its solves do not allocate memory.
+///
+/// # Arguments
+///
+/// - `function`: given closure
+/// - `u`: on entry, the point where the Lipschitz constant is estimated; on
+///   exit, the perturbed point $u + h$
+/// - `function_value`: on exit, the value of `function` at (the given) `u`
+/// - `workspace`: work vector of the same length as `u`
+/// - `delta`: absolute perturbation, $\delta$
+/// - `epsilon`: relative perturbation, $\epsilon$
+///
+/// # Returns
+///
+/// The estimate of the local Lipschitz constant, or the error returned by
+/// the given function
+///
+pub(crate) fn estimate_local_lipschitz<F, T>(
+    function: &F,
+    u: &mut [T],
+    function_value: &mut [T],
+    workspace: &mut [T],
+    delta: T,
+    epsilon: T,
+) -> Result<T, SolverError>
+where
+    F: Fn(&[T], &mut [T]) -> Result<(), SolverError>,
+    T: OptFloat,
+{
+    // function_value = F(u)
+    function(u, function_value)?;
+
+    // workspace = h = max{epsilon * |u|, delta}
+    workspace
+        .iter_mut()
+        .zip(u.iter())
+        .for_each(|(out, &s)| *out = T::max(epsilon * s.abs(), delta));
+    let norm_h = matrix_operations::norm2(workspace);
+
+    // u = u + h
+    u.iter_mut()
+        .zip(workspace.iter())
+        .for_each(|(out, &hi)| *out += hi);
+
+    // workspace = F(u + h)
+    function(u, workspace)?;
+
+    // workspace = F(u + h) - F(u)
+    workspace
+        .iter_mut()
+        .zip(function_value.iter())
+        .for_each(|(out, a)| *out -= *a);
+
+    let norm_workspace = matrix_operations::norm2(workspace);
+    Ok(norm_workspace / norm_h)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -232,16 +373,15 @@ mod tests {
 
     #[test]
     fn t_test_lip_delta_epsilon_0() {
-        let mut u: [f64; 3] = [1.0, 2.0, 3.0];
-        let mut function_value = [0.0; 3];
+        let u: [f64; 3] = [1.0, 2.0, 3.0];
 
         let f =
             |u: &[f64], g: &mut [f64]| -> Result<(), SolverError> { mocks::lipschitz_mock(u, g) };
 
-        let mut lip_estimator = LipschitzEstimator::new(&mut u, &f, &mut function_value)
+        let mut lip_estimator = LipschitzEstimator::new(f, 3)
             .with_delta(1e-4)
             .with_epsilon(1e-4);
-        let lip = lip_estimator.estimate_local_lipschitz().unwrap();
+        let lip = lip_estimator.estimate(&u).unwrap();
 
         unit_test_utils::assert_nearly_equal(
             1.336_306_209_562_331,
@@ -257,36 +397,33 @@ mod tests {
     #[test]
     #[should_panic]
     fn t_test_lip_delta_epsilon_panic1() {
-        let mut u: [f64; 3] = [1.0, 2.0, 3.0];
-        let mut function_value = [0.0; 3];
-
         let _lip_estimator =
-            LipschitzEstimator::new(&mut u, &mocks::lipschitz_mock, &mut function_value)
-                .with_epsilon(0.0);
+            LipschitzEstimator::new(mocks::lipschitz_mock::<f64>, 3).with_epsilon(0.0);
     }
 
     #[test]
     #[should_panic]
     fn t_test_lip_delta_epsilon_panic2() {
-        let mut u: [f64; 3] = [1.0, 2.0, 3.0];
-        let mut function_value = [0.0; 3];
-
         let _lip_estimator =
-            LipschitzEstimator::new(&mut u, &mocks::lipschitz_mock, &mut function_value)
-                .with_epsilon(0.0);
+            LipschitzEstimator::new(mocks::lipschitz_mock::<f64>, 3).with_delta(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_test_lip_wrong_dimension() {
+        let mut lip_estimator = LipschitzEstimator::new(mocks::lipschitz_mock::<f64>, 3);
+        let _lip = lip_estimator.estimate(&[1.0, 2.0]);
     }
 
     #[test]
     fn t_test_lip_estimator_mock() {
-        let mut u: [f64; 3] = [1.0, 2.0, 3.0];
+        let u: [f64; 3] = [1.0, 2.0, 3.0];
 
         let f =
             |u: &[f64], g: &mut [f64]| -> Result<(), SolverError> { mocks::lipschitz_mock(u, g) };
 
-        let mut function_value = [0.0; 3];
-
-        let mut lip_estimator = LipschitzEstimator::new(&mut u, &f, &mut function_value);
-        let lip = lip_estimator.estimate_local_lipschitz().unwrap();
+        let mut lip_estimator = LipschitzEstimator::new(&f, 3);
+        let lip = lip_estimator.estimate(&u).unwrap();
 
         unit_test_utils::assert_nearly_equal(
             1.336_306_209_416_582_3,
@@ -298,16 +435,90 @@ mod tests {
         println!("L_mock = {}", lip);
     }
 
+    #[test]
+    fn t_test_lip_estimator_known_jacobian() {
+        // the Jacobian of `lipschitz_mock` is J = diag(3, 2, 0), so the
+        // estimate is ||Jh||/||h||, which is exact because the mapping is affine
+        let mut lip_estimator = LipschitzEstimator::new(mocks::lipschitz_mock::<f64>, 3);
+
+        // h is proportional to (1, 1, 1)
+        let u = [1.0, 1.0, 1.0];
+        let lip = lip_estimator.estimate(&u).unwrap();
+        unit_test_utils::assert_nearly_equal(
+            (13.0_f64 / 3.0).sqrt(),
+            lip,
+            1e-6,
+            1e-10,
+            "lipschitz constant",
+        );
+        assert_eq!([1.0, 1.0, 1.0], u);
+
+        // h is proportional to (1, 0, 0), so the estimate is the norm of J
+        let lip = lip_estimator
+            .with_delta(1e-12)
+            .with_epsilon(1e-6)
+            .estimate(&[1e3, 0.0, 0.0])
+            .unwrap();
+        unit_test_utils::assert_nearly_equal(3.0, lip, 1e-6, 1e-10, "norm of J");
+    }
+
+    #[test]
+    fn t_test_lip_estimator_small_scale() {
+        // F(u) = s sin(u/s) varies on the scale s, and its Jacobian at 0 is I
+        let scale = 1e-13;
+        let f = |u: &[f64], g: &mut [f64]| -> Result<(), SolverError> {
+            g.iter_mut()
+                .zip(u.iter())
+                .for_each(|(gi, &ui)| *gi = scale * (ui / scale).sin());
+            Ok(())
+        };
+        let u = [0.0, 0.0];
+
+        // the default perturbation is much larger than the scale of u
+        let lip_default = LipschitzEstimator::new(f, 2).estimate(&u).unwrap();
+        assert!((lip_default - 1.0).abs() > 0.5);
+
+        let lip = LipschitzEstimator::new(f, 2)
+            .with_delta(1e-4 * scale)
+            .with_epsilon(1e-4)
+            .estimate(&u)
+            .unwrap();
+        unit_test_utils::assert_nearly_equal(1.0, lip, 1e-6, 1e-10, "lipschitz constant");
+    }
+
+    #[test]
+    fn t_test_lip_estimator_power() {
+        // the spectral norm of the Hessian of `hard_quadratic_cost`
+        let norm_hessian = 1_001.652_987_493_693;
+        let u = [1.5, -0.4, 2.0];
+        let mut lip_estimator = LipschitzEstimator::new(mocks::hard_quadratic_gradient::<f64>, 3);
+        let lip_fd = lip_estimator.estimate(&u).unwrap();
+
+        let mut errors = [0.0; 4];
+        for (error, &num_iters) in errors.iter_mut().zip([1, 2, 5, 20].iter()) {
+            let lip = lip_estimator.estimate_power(&u, num_iters).unwrap();
+            *error = (lip - norm_hessian).abs();
+        }
+        assert!(errors.windows(2).all(|e| e[1] <= e[0]));
+        assert!(errors[3] < 1e-4);
+        assert!((lip_fd - norm_hessian).abs() > errors[3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_test_lip_estimator_power_zero_iters() {
+        let mut lip_estimator = LipschitzEstimator::new(mocks::hard_quadratic_gradient::<f64>, 3);
+        let _lip = lip_estimator.estimate_power(&[1.0, 2.0, 3.0], 0);
+    }
+
     #[test]
     fn t_test_get_function_value() {
         let u: [f64; 10] = [1.0, 2.0, 3.0, -5.0, 1.0, 10.0, 14.0, 17.0, 3.0, 5.0];
-        let mut function_value = [0.0; 10];
-        let mut u_copy = u;
 
         let f =
             |u: &[f64], g: &mut [f64]| -> Result<(), SolverError> { mocks::lipschitz_mock(u, g) };
 
-        let mut lip_estimator = LipschitzEstimator::new(&mut u_copy, &f, &mut function_value);
+        let mut lip_estimator = LipschitzEstimator::new(&f, 10);
         {
             let computed_gradient = lip_estimator.get_function_value();
 
@@ -323,7 +534,7 @@ mod tests {
             });
         }
 
-        lip_estimator.estimate_local_lipschitz().unwrap();
+        lip_estimator.estimate(&u).unwrap();
 
         let computed_gradient = lip_estimator.get_function_value();
         let mut actual_gradient = [0.0; 10];
@@ -23,6 +23,15 @@
 //! let norm_sq_a_minus_b = norm2_squared_diff(&a, &b);
 //! assert!(norm_sq_a_minus_b == 27.);
 //!
+//! // Euclidean distance between vectors
+//! let dist_a_b = norm2_diff(&a, &b);
+//! assert!((dist_a_b - 27f64.sqrt()).abs() < 1e-12);
+//!
+//! // y <-- y + 2 * a
+//! let mut y = [1.0, 1.0, 1.0];
+//! axpy(&mut y, 2.0, &a);
+//! assert!(y == [3.0, 5.0, 7.0]);
+//!
 //! // Sum of elements of vector
 //! let sum_a = sum(&a);
 //! assert!(sum_a == 6.);
@@ -33,69 +42,607 @@
 //! // Infinity norm
 //! let norm_inf_b = norm_inf(&b);
 //! assert!(norm_inf_b == 6.);
+//!
+//! // p-norm
+//! let norm_p_a = norm_p(&a, 3.0);
+//! assert!((norm_p_a - 36f64.cbrt()).abs() < 1e-12);
 //! ```
 //!
+//! # Fused multiply-add
+//!
+//! On targets with a hardware FMA instruction (that is, if the target
+//! feature `fma` is enabled, e.g., with `-C target-cpu=native`, or on
+//! `aarch64`), the serial implementations of `inner_product` and
+//! `norm2_squared` accumulate using fused multiply-add operations
+//! (`Float::mul_add`), which round once per product and sum. This is more
+//! accurate than a separate multiplication and addition. On other targets,
+//! `mul_add` would be computed in software, which is several times slower,
+//! so a separate multiplication and addition is used instead.
+//!
+//! # Parallel computations
+//!
+//! If the feature `parallel` is activated, `inner_product`, `sum`,
+//! `norm2_squared` (so, also `norm2`) and `axpy` use several threads (using
+//! `rayon`) for vectors of `f32` and `f64` whose length is at least
+//! `parallel_threshold()` (see `set_parallel_threshold`); shorter vectors
+//! are processed serially. The dispatch is done by the `parallel_*` methods
+//! of `OptFloat`, so other implementors of `OptFloat` are processed serially
+//! and need not be `Send + Sync`.
+//! Reductions split the vector recursively in halves down to blocks of fixed
+//! size, which are summed serially, so the result depends only on the length
+//! of the vector (not on the number of threads or the scheduling); it may,
+//! however, differ slightly from the result of the serial computation.
+//!
+//! # BLAS
+//!
+//! If the feature `blas` is activated, `inner_product`, `norm2` and `norm1`
+//! are computed by the BLAS routines `xDOT`, `xNRM2` and `xASUM` respectively
+//! (via the crate `cblas`) for vectors of `f32` and `f64`; other types use the
+//! pure-Rust implementations. The dispatch is done by the `blas_*` methods of
+//! `OptFloat`. A BLAS implementation needs to be linked, e.g.,
+//! by adding a dependency on `openblas-src` or `blas-src`. The feature `blas`
+//! requires the feature `std`.
+//!
 
-use std::iter::Sum;
-use std::ops::Mul;
+use core::iter::Sum;
+use core::ops::Mul;
 
 use num::{Float, Zero};
 
+use crate::core::OptFloat;
+use crate::SolverError;
+
+/// Default minimum length of vectors which are processed in parallel
+#[cfg(feature = "parallel")]
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 65_536;
+
+/// Sets the minimum length of vectors which are processed in parallel
+///
+/// This setting is global; the default value is `DEFAULT_PARALLEL_THRESHOLD`
+#[cfg(feature = "parallel")]
+pub fn set_parallel_threshold(threshold: usize) {
+    parallel::THRESHOLD.store(threshold, core::sync::atomic::Ordering::Relaxed);
+}
+
+/// Minimum length of vectors which are processed in parallel
+#[cfg(feature = "parallel")]
+pub fn parallel_threshold() -> usize {
+    parallel::THRESHOLD.load(core::sync::atomic::Ordering::Relaxed)
+}
+
+#[cfg(feature = "parallel")]
+pub(crate) mod parallel {
+    use core::iter::Sum;
+    use core::ops::Range;
+    use core::sync::atomic::AtomicUsize;
+    use num::Float;
+
+    /// Length of the blocks which are processed serially
+    const BLOCK_SIZE: usize = 4_096;
+
+    pub(super) static THRESHOLD: AtomicUsize = AtomicUsize::new(super::DEFAULT_PARALLEL_THRESHOLD);
+
+    /// Sums `leaf` over the blocks of `range`; the range is split in halves
+    /// recursively, so the order of the additions depends only on its length
+    pub(super) fn reduce<T, F>(range: Range<usize>, leaf: &F) -> T
+    where
+        T: Float + Send,
+        F: Fn(Range<usize>) -> T + Sync,
+    {
+        if range.len() <= BLOCK_SIZE {
+            return leaf(range);
+        }
+        let mid = range.start + range.len() / 2;
+        let (left, right) = rayon::join(
+            || reduce(range.start..mid, leaf),
+            || reduce(mid..range.end, leaf),
+        );
+        left + right
+    }
+
+    /// Applies `f` to the blocks of `y`, together with their offsets
+    pub(super) fn for_each_block<T, F>(y: &mut [T], offset: usize, f: &F)
+    where
+        T: Send,
+        F: Fn(&mut [T], usize) + Sync,
+    {
+        if y.len() <= BLOCK_SIZE {
+            f(y, offset);
+            return;
+        }
+        let mid = y.len() / 2;
+        let (left, right) = y.split_at_mut(mid);
+        rayon::join(
+            || for_each_block(left, offset, f),
+            || for_each_block(right, offset + mid, f),
+        );
+    }
+
+    /// Inner product of `a` and `b` using several threads, or `None` if the
+    /// vectors are shorter than the parallel threshold
+    pub(crate) fn inner_product<T>(a: &[T], b: &[T]) -> Option<T>
+    where
+        T: Float + Sum<T> + Send + Sync,
+    {
+        if a.len() < super::parallel_threshold() {
+            return None;
+        }
+        Some(reduce(0..a.len(), &|range: Range<usize>| {
+            super::inner_product_serial(&a[range.clone()], &b[range])
+        }))
+    }
+
+    /// Squared 2-norm of `a` using several threads, or `None` if the vector
+    /// is shorter than the parallel threshold
+    pub(crate) fn norm2_squared<T>(a: &[T]) -> Option<T>
+    where
+        T: Float + Sum<T> + Send + Sync,
+    {
+        if a.len() < super::parallel_threshold() {
+            return None;
+        }
+        Some(reduce(0..a.len(), &|range: Range<usize>| {
+            super::norm2_squared_serial(&a[range])
+        }))
+    }
+
+    /// Sum of the elements of `a` using several threads, or `None` if the
+    /// vector is shorter than the parallel threshold
+    pub(crate) fn sum<T>(a: &[T]) -> Option<T>
+    where
+        T: Float + Sum<T> + Send + Sync,
+    {
+        if a.len() < super::parallel_threshold() {
+            return None;
+        }
+        Some(reduce(0..a.len(), &|range: Range<usize>| {
+            super::sum_serial(&a[range])
+        }))
+    }
+
+    /// Computes `y <- y + a x` using several threads and returns `true`, or
+    /// returns `false` (and leaves `y` unchanged) if the vectors are shorter
+    /// than the parallel threshold
+    pub(crate) fn axpy<T>(y: &mut [T], a: T, x: &[T]) -> bool
+    where
+        T: Float + Send + Sync,
+    {
+        if y.len() < super::parallel_threshold() {
+            return false;
+        }
+        for_each_block(y, 0, &|y_block: &mut [T], offset: usize| {
+            super::axpy_serial(y_block, a, &x[offset..offset + y_block.len()])
+        });
+        true
+    }
+}
+
+#[cfg(feature = "blas")]
+pub(crate) mod blas {
+    use core::convert::TryFrom;
+
+    /// Inner product using `DDOT`, or `None` if the length of the vectors
+    /// does not fit in an `i32`
+    pub(crate) fn ddot(a: &[f64], b: &[f64]) -> Option<f64> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::ddot(n, a, 1, b, 1) })
+    }
+
+    /// Inner product using `SDOT`, or `None` if the length of the vectors
+    /// does not fit in an `i32`
+    pub(crate) fn sdot(a: &[f32], b: &[f32]) -> Option<f32> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::sdot(n, a, 1, b, 1) })
+    }
+
+    /// Euclidean norm using `DNRM2`, or `None` if the length of the vector
+    /// does not fit in an `i32`
+    pub(crate) fn dnrm2(a: &[f64]) -> Option<f64> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::dnrm2(n, a, 1) })
+    }
+
+    /// Euclidean norm using `SNRM2`, or `None` if the length of the vector
+    /// does not fit in an `i32`
+    pub(crate) fn snrm2(a: &[f32]) -> Option<f32> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::snrm2(n, a, 1) })
+    }
+
+    /// 1-norm using `DASUM`, or `None` if the length of the vector does not
+    /// fit in an `i32`
+    pub(crate) fn dasum(a: &[f64]) -> Option<f64> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::dasum(n, a, 1) })
+    }
+
+    /// 1-norm using `SASUM`, or `None` if the length of the vector does not
+    /// fit in an `i32`
+    pub(crate) fn sasum(a: &[f32]) -> Option<f32> {
+        let n = i32::try_from(a.len()).ok()?;
+        Some(unsafe { cblas::sasum(n, a, 1) })
+    }
+}
+
 /// Calculate the inner product of two vectors
 #[inline(always)]
 pub fn inner_product<T>(a: &[T], b: &[T]) -> T
+where
+    T: OptFloat,
+{
+    assert!(a.len() == b.len());
+    if let Some(result) = T::blas_dot(a, b) {
+        return result;
+    }
+    if let Some(result) = T::parallel_inner_product(a, b) {
+        return result;
+    }
+    inner_product_serial(a, b)
+}
+
+/// Computes `a * b + sum` with a fused multiply-add operation
+#[cfg(any(target_feature = "fma", target_arch = "aarch64"))]
+#[inline(always)]
+fn multiply_add<T: Float>(a: T, b: T, sum: T) -> T {
+    a.mul_add(b, sum)
+}
+
+/// Computes `a * b + sum` (without a hardware FMA instruction, `mul_add`
+/// would be computed in software)
+#[cfg(not(any(target_feature = "fma", target_arch = "aarch64")))]
+#[inline(always)]
+fn multiply_add<T: Float>(a: T, b: T, sum: T) -> T {
+    sum + a * b
+}
+
+#[inline(always)]
+fn inner_product_serial<T>(a: &[T], b: &[T]) -> T
 where
     T: Float + Sum<T> + Mul<T, Output = T>,
+{
+    a.iter()
+        .zip(b.iter())
+        .fold(T::zero(), |sum, (&x, &y)| multiply_add(x, y, sum))
+}
+
+/// Calculate the inner product of two vectors using compensated summation
+///
+/// The products $a_i b_i$ are accumulated using `sum_kahan`'s compensated
+/// summation, which is more accurate than `inner_product` for long vectors
+/// (especially in `f32`), at the cost of about four times as many floating
+/// point operations. Note that the rounding errors of the products themselves
+/// are not compensated.
+///
+/// ## Panics
+///
+/// The method panics if `a` and `b` do not have the same length
+pub fn inner_product_kahan<T>(a: &[T], b: &[T]) -> T
+where
+    T: Float,
 {
     assert!(a.len() == b.len());
+    compensated_sum(a.iter().zip(b.iter()).map(|(&x, &y)| x * y))
+}
+
+/// Computes $y \leftarrow y + a x$, where $a$ is a scalar and $x$, $y$ are
+/// vectors (as in BLAS's `xAXPY`)
+///
+/// ## Panics
+///
+/// The method panics if `x` and `y` do not have the same length
+#[inline(always)]
+pub fn axpy<T>(y: &mut [T], a: T, x: &[T])
+where
+    T: OptFloat,
+{
+    assert_eq!(y.len(), x.len(), "x and y must have the same length");
+    if T::parallel_axpy(y, a, x) {
+        return;
+    }
+    axpy_serial(y, a, x);
+}
 
-    a.iter().zip(b.iter()).map(|(x, y)| (*x) * (*y)).sum()
+#[inline(always)]
+fn axpy_serial<T>(y: &mut [T], a: T, x: &[T])
+where
+    T: Float,
+{
+    y.iter_mut()
+        .zip(x.iter())
+        .for_each(|(yi, &xi)| *yi = *yi + a * xi);
+}
+
+/// Computes $u \leftarrow u + \gamma w$, where $\gamma$ is a scalar and $u$,
+/// $w$ are vectors (same as `axpy`, with the arguments in the order of
+/// `sub_scaled_in_place`)
+///
+/// ## Panics
+///
+/// The method panics if `u` and `w` do not have the same length
+#[inline(always)]
+pub fn add_scaled_in_place<T>(u: &mut [T], gamma: T, w: &[T])
+where
+    T: OptFloat,
+{
+    axpy(u, gamma, w);
+}
+
+/// Computes $u \leftarrow u - \gamma w$, where $\gamma$ is a scalar and $u$,
+/// $w$ are vectors; this is, for example, a gradient step with step size
+/// $\gamma$ and gradient $w$
+///
+/// ## Panics
+///
+/// The method panics if `u` and `w` do not have the same length
+#[inline(always)]
+pub fn sub_scaled_in_place<T>(u: &mut [T], gamma: T, w: &[T])
+where
+    T: OptFloat,
+{
+    axpy(u, -gamma, w);
+}
+
+/// Computes the elementwise (Hadamard) product of two vectors,
+/// $z_i = x_i y_i$, and stores it in `out`
+///
+/// ## Panics
+///
+/// The method panics if `out`, `x` and `y` do not have the same length
+#[inline(always)]
+pub fn hadamard_into<T>(out: &mut [T], x: &[T], y: &[T])
+where
+    T: Float,
+{
+    assert_eq!(out.len(), x.len(), "out and x must have the same length");
+    assert_eq!(x.len(), y.len(), "x and y must have the same length");
+    out.iter_mut()
+        .zip(x.iter().zip(y.iter()))
+        .for_each(|(out_i, (&xi, &yi))| *out_i = xi * yi);
+}
+
+/// Computes $x \leftarrow a x$, where $a$ is a scalar (as in BLAS's `xSCAL`)
+#[inline(always)]
+pub fn scale_in_place<T>(x: &mut [T], a: T)
+where
+    T: Float,
+{
+    x.iter_mut().for_each(|xi| *xi = *xi * a);
+}
+
+/// Clamps a vector in place between a lower and an upper bound, that is,
+/// $x_i \leftarrow \min\\{\max\\{x_i, l_i\\}, u_i\\}$
+///
+/// This is the projection on the rectangle $\\{x {}:{} l \leq x \leq u\\}$;
+/// either bound may be omitted (or have infinite elements). The lower bound
+/// is applied first, so $u_i$ prevails if $l_i > u_i$.
+///
+/// ## Arguments
+///
+/// - `x`: the vector to be clamped
+/// - `lo`: lower bound, $l$, or `None` if there is no lower bound
+/// - `hi`: upper bound, $u$, or `None` if there is no upper bound
+///
+/// If a bound is shorter than `x`, only the first elements of `x` are
+/// clamped by it
+#[inline(always)]
+pub fn clamp_into<T>(x: &mut [T], lo: Option<&[T]>, hi: Option<&[T]>)
+where
+    T: Float,
+{
+    if let Some(lo) = lo {
+        x.iter_mut().zip(lo.iter()).for_each(|(xi, &li)| {
+            if *xi < li {
+                *xi = li
+            };
+        });
+    }
+    if let Some(hi) = hi {
+        x.iter_mut().zip(hi.iter()).for_each(|(xi, &ui)| {
+            if *xi > ui {
+                *xi = ui
+            };
+        });
+    }
 }
 
 /// Calculate the 1-norm of a vector
 #[inline(always)]
 pub fn norm1<T>(a: &[T]) -> T
 where
-    T: Float + Sum<T>,
+    T: OptFloat,
 {
+    if let Some(result) = T::blas_asum(a) {
+        return result;
+    }
     a.iter().map(|x| x.abs()).sum()
 }
 
 /// Calculate the 2-norm of a vector
+///
+/// The squared norm is first computed with a plain sum of squares; only if
+/// that sum overflows (while all elements of `a` are finite), the norm is
+/// recomputed using a scaled sum of squares, which does not overflow unless
+/// the norm itself does. This is mostly relevant for `f32`.
 #[inline(always)]
 pub fn norm2<T>(a: &[T]) -> T
 where
-    T: Float + Sum<T> + Mul<T, Output = T>,
+    T: OptFloat,
 {
+    // BLAS implementations differ in how infinite elements are handled, so
+    // non-finite results are recomputed below
+    if let Some(result) = T::blas_nrm2(a).filter(|result| result.is_finite()) {
+        return result;
+    }
     let norm: T = norm2_squared(a);
+    if norm.is_infinite() && is_finite(a) {
+        return scaled_norm2(a);
+    }
     norm.sqrt()
 }
 
+/// Normalizes a vector in place, that is, $x \leftarrow x / \Vert{}x{}\Vert$,
+/// unless its 2-norm does not exceed `eps`
+///
+/// ## Arguments
+///
+/// - `x`: the vector to be normalized
+/// - `eps`: threshold on the norm of `x` below which `x` is left unchanged,
+///   since its direction cannot be determined reliably
+///
+/// ## Returns
+///
+/// The 2-norm of `x` before normalization if it exceeds `eps` (in which
+/// case `x` now has unit norm), otherwise zero (and `x` is unchanged)
+#[inline(always)]
+pub fn normalize_in_place<T>(x: &mut [T], eps: T) -> T
+where
+    T: OptFloat,
+{
+    let norm_x = norm2(x);
+    if norm_x > eps {
+        scale_in_place(x, T::one() / norm_x);
+        norm_x
+    } else {
+        T::zero()
+    }
+}
+
+/// Calculate the 2-norm of a vector using a scaled sum of squares (as in
+/// LAPACK's `xNRM2`), which avoids overflow in intermediate computations
+fn scaled_norm2<T>(a: &[T]) -> T
+where
+    T: Float,
+{
+    let mut scale = T::zero();
+    let mut sum_of_squares = T::one();
+    a.iter().filter(|xi| !xi.is_zero()).for_each(|xi| {
+        let abs_xi = xi.abs();
+        if scale < abs_xi {
+            sum_of_squares = T::one() + sum_of_squares * (scale / abs_xi).powi(2);
+            scale = abs_xi;
+        } else {
+            sum_of_squares = sum_of_squares + (abs_xi / scale).powi(2);
+        }
+    });
+    scale * sum_of_squares.sqrt()
+}
+
+/// Calculate the weighted 2-norm of a vector,
+/// $\Vert{}x{}\Vert_w = \sqrt{\sum_i w_i x_i^2}$, where the weights $w_i$ are
+/// nonnegative
+///
+/// ## Panics
+///
+/// The method panics if `x` and `w` do not have the same length; in debug
+/// builds, it also panics if any of the weights is negative
+#[inline(always)]
+pub fn weighted_norm2<T>(x: &[T], w: &[T]) -> T
+where
+    T: Float,
+{
+    assert_eq!(x.len(), w.len(), "x and w must have the same length");
+    debug_assert!(
+        w.iter().all(|&wi| wi >= T::zero()),
+        "the weights must be nonnegative"
+    );
+    x.iter()
+        .zip(w.iter())
+        .fold(T::zero(), |sum, (&xi, &wi)| sum + wi * xi * xi)
+        .sqrt()
+}
+
+/// Minimum length of vectors for which `norm2_squared_diff` uses an unrolled
+/// loop with four accumulators
+const UNROLL_THRESHOLD: usize = 16;
+
 /// Calculate the squared 2-norm of the difference of two vectors
+///
+/// For vectors with at least 16 elements, the loop is unrolled (four
+/// elements at a time, with separate accumulators) and, on targets with a
+/// hardware FMA instruction, uses fused multiply-add operations, so the
+/// result may differ from that of the plain sum in the last bits
 #[inline(always)]
 pub fn norm2_squared_diff<T>(a: &[T], b: &[T]) -> T
 where
     T: Float + Sum<T> + Mul<T, Output = T>,
 {
-    a.iter().zip(b.iter()).fold(T::zero(), |mut sum, (&x, &y)| {
-        sum = sum + (x - y).powi(2);
-        sum
-    })
+    let n = a.len().min(b.len());
+    if n < UNROLL_THRESHOLD {
+        return a.iter().zip(b.iter()).fold(T::zero(), |mut sum, (&x, &y)| {
+            sum = sum + (x - y).powi(2);
+            sum
+        });
+    }
+    let (a, b) = (&a[..n], &b[..n]);
+    let mut sums = [T::zero(); 4];
+    let a_chunks = a.chunks_exact(4);
+    let b_chunks = b.chunks_exact(4);
+    let tail = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder().iter())
+        .fold(T::zero(), |sum, (&x, &y)| multiply_add(x - y, x - y, sum));
+    a_chunks.zip(b_chunks).for_each(|(x, y)| {
+        sums.iter_mut().enumerate().for_each(|(i, sum)| {
+            let d = x[i] - y[i];
+            *sum = multiply_add(d, d, *sum);
+        });
+    });
+    (sums[0] + sums[1]) + (sums[2] + sums[3]) + tail
 }
 
-/// Calculate the 2-norm of a vector
+/// Calculate the 2-norm of the difference of two vectors, that is, the
+/// Euclidean distance between them
+#[inline(always)]
+pub fn norm2_diff<T>(a: &[T], b: &[T]) -> T
+where
+    T: Float + Sum<T> + Mul<T, Output = T>,
+{
+    norm2_squared_diff(a, b).sqrt()
+}
+
+/// Calculate the squared 2-norm of a vector
+///
+/// Unlike `norm2`, this method has no scaled (overflow-safe) path, since
+/// none is needed: the squares are nonnegative, so every partial sum (also
+/// in the parallel reduction) is bounded by the result, and no intermediate
+/// computation overflows unless the squared norm itself does. The result is
+/// infinite if the norm of `a` exceeds the square root of the largest finite
+/// value of `T`, since the squared norm is then not representable (use
+/// `norm2` in such cases).
 #[inline(always)]
 pub fn norm2_squared<T>(a: &[T]) -> T
+where
+    T: OptFloat,
+{
+    if let Some(result) = T::parallel_norm2_squared(a) {
+        return result;
+    }
+    norm2_squared_serial(a)
+}
+
+#[inline(always)]
+fn norm2_squared_serial<T>(a: &[T]) -> T
 where
     T: Float + Sum<T> + Mul<T, Output = T>,
 {
-    let norm: T = a.iter().map(|x| (*x) * (*x)).sum();
-    norm
+    a.iter().fold(T::zero(), |sum, &x| multiply_add(x, x, sum))
 }
 
 /// Calculate the sum of all elements of a vector
 #[inline(always)]
 pub fn sum<T>(a: &[T]) -> T
+where
+    T: OptFloat,
+{
+    if let Some(result) = T::parallel_sum(a) {
+        return result;
+    }
+    sum_serial(a)
+}
+
+#[inline(always)]
+fn sum_serial<T>(a: &[T]) -> T
 where
     T: Float + Sum<T> + Mul<T, Output = T>,
 {
@@ -103,7 +650,44 @@ where
     norm
 }
 
-/// Calculates the infinity-norm of a vector
+/// Calculate the sum of all elements of a vector using compensated summation
+///
+/// This uses the Kahan-Babuška (Neumaier) variant of Kahan's compensated
+/// summation: the rounding error of every addition is accumulated separately
+/// and added to the result at the end. Unlike `sum`, whose error grows with the
+/// length of the vector, the error is essentially independent of the length;
+/// this matters for long vectors in `f32`. The method is about four times more
+/// expensive than `sum`.
+pub fn sum_kahan<T>(a: &[T]) -> T
+where
+    T: Float,
+{
+    compensated_sum(a.iter().copied())
+}
+
+/// Kahan-Babuška (Neumaier) compensated summation of the elements of an iterator
+fn compensated_sum<T, I>(values: I) -> T
+where
+    T: Float,
+    I: Iterator<Item = T>,
+{
+    let mut sum = T::zero();
+    let mut compensation = T::zero();
+    values.for_each(|x| {
+        let t = sum + x;
+        compensation = if sum.abs() >= x.abs() {
+            compensation + ((sum - t) + x)
+        } else {
+            compensation + ((x - t) + sum)
+        };
+        sum = t;
+    });
+    sum + compensation
+}
+
+/// Calculates the infinity-norm of a vector, $\Vert{}a{}\Vert_\infty = \max_i |a_i|$
+///
+/// The infinity norm of an empty vector is zero.
 #[inline(always)]
 pub fn norm_inf<T>(a: &[T]) -> T
 where
@@ -113,6 +697,45 @@ where
         .fold(T::zero(), |current_max, x| x.abs().max(current_max))
 }
 
+/// Calculates the $p$-norm of a vector, $\Vert{}a{}\Vert_p = \left(\sum_i |a_i|^p\right)^{1/p}$
+///
+/// The cases $p = 1$, $p = 2$ and $p = \infty$ are delegated to `norm1`,
+/// `norm2` and `norm_inf` respectively. In all other cases, the largest
+/// absolute value of the elements of `a` is factored out before summing, so
+/// the computation does not overflow unless the norm itself does.
+///
+/// ## Arguments
+///
+/// - `a`: vector
+/// - `p`: order of the norm; it can be infinite
+///
+/// ## Panics
+///
+/// The method panics if `p` is smaller than 1 or NaN
+///
+#[inline(always)]
+pub fn norm_p<T>(a: &[T], p: T) -> T
+where
+    T: OptFloat,
+{
+    assert!(p >= T::one(), "p must be at least 1");
+    if p == T::one() {
+        return norm1(a);
+    }
+    if p == T::one() + T::one() {
+        return norm2(a);
+    }
+    if p.is_infinite() {
+        return norm_inf(a);
+    }
+    let max_abs = norm_inf(a);
+    if max_abs.is_zero() || !max_abs.is_finite() {
+        return max_abs;
+    }
+    let sum: T = a.iter().map(|x| (x.abs() / max_abs).powf(p)).sum();
+    max_abs * sum.powf(p.recip())
+}
+
 /// Computes the infinity norm of the difference of two vectors
 #[inline(always)]
 pub fn norm_inf_diff<T>(a: &[T], b: &[T]) -> T
@@ -138,7 +761,137 @@ pub fn is_finite<T>(a: &[T]) -> bool
 where
     T: Float,
 {
-    !a.iter().any(|&xi| !xi.is_finite())
+    first_non_finite(a).is_none()
+}
+
+/// Finds the first element of a vector which is not finite
+///
+/// ## Returns
+///
+/// Returns the index of the first element which is either NaN or Infinity,
+/// or `None` if all elements are finite
+#[inline(always)]
+pub fn first_non_finite<T>(a: &[T]) -> Option<usize>
+where
+    T: Float,
+{
+    a.iter().position(|xi| !xi.is_finite())
+}
+
+/// Calculate the sum of all elements of a vector, failing on the first
+/// element which is not finite
+///
+/// ## Returns
+///
+/// The sum of the elements of `a`, or `SolverError::NotFiniteComputation` if
+/// any of its elements (or the sum) is NaN or infinite
+#[inline(always)]
+pub fn sum_checked<T>(a: &[T]) -> Result<T, SolverError>
+where
+    T: OptFloat,
+{
+    if first_non_finite(a).is_some() {
+        return Err(SolverError::NotFiniteComputation);
+    }
+    let result = sum(a);
+    if result.is_finite() {
+        Ok(result)
+    } else {
+        Err(SolverError::NotFiniteComputation)
+    }
+}
+
+/// Calculate the 2-norm of a vector, failing on the first element which is
+/// not finite
+///
+/// ## Returns
+///
+/// The 2-norm of `a`, or `SolverError::NotFiniteComputation` if any of its
+/// elements (or the norm) is NaN or infinite
+#[inline(always)]
+pub fn norm2_checked<T>(a: &[T]) -> Result<T, SolverError>
+where
+    T: OptFloat,
+{
+    if first_non_finite(a).is_some() {
+        return Err(SolverError::NotFiniteComputation);
+    }
+    let result = norm2(a);
+    if result.is_finite() {
+        Ok(result)
+    } else {
+        Err(SolverError::NotFiniteComputation)
+    }
+}
+
+/// Index of the element with the largest absolute value
+///
+/// NaN elements are skipped and, in case of ties, the first index is returned
+///
+/// ## Returns
+///
+/// Returns the index of the element with the largest absolute value, or
+/// `None` if `x` is empty or all its elements are NaN
+#[inline(always)]
+pub fn argmax_abs<T>(x: &[T]) -> Option<usize>
+where
+    T: Float,
+{
+    arg_extremum(x, |xi| xi.abs(), |a, b| a > b)
+}
+
+/// Index of the largest element
+///
+/// NaN elements are skipped and, in case of ties, the first index is returned
+///
+/// ## Returns
+///
+/// Returns the index of the largest element, or `None` if `x` is empty or
+/// all its elements are NaN
+#[inline(always)]
+pub fn argmax<T>(x: &[T]) -> Option<usize>
+where
+    T: Float,
+{
+    arg_extremum(x, |xi| xi, |a, b| a > b)
+}
+
+/// Index of the smallest element
+///
+/// NaN elements are skipped and, in case of ties, the first index is returned
+///
+/// ## Returns
+///
+/// Returns the index of the smallest element, or `None` if `x` is empty or
+/// all its elements are NaN
+#[inline(always)]
+pub fn argmin<T>(x: &[T]) -> Option<usize>
+where
+    T: Float,
+{
+    arg_extremum(x, |xi| xi, |a, b| a < b)
+}
+
+/// Index of the first non-NaN element of `x` whose value, `value(x_i)`, is
+/// better than the values of all other elements, where `a` is better than
+/// `b` if `is_better(a, b)`
+fn arg_extremum<T, V, B>(x: &[T], value: V, is_better: B) -> Option<usize>
+where
+    T: Float,
+    V: Fn(T) -> T,
+    B: Fn(T, T) -> bool,
+{
+    x.iter()
+        .enumerate()
+        .filter(|(_, xi)| !xi.is_nan())
+        .fold(None, |best: Option<(usize, T)>, (i, &xi)| {
+            let vi = value(xi);
+            match best {
+                Some((_, v_best)) if !is_better(vi, v_best) => best,
+                _ => Some((i, vi)),
+            }
+        })
+        .map(|(i, _)| i)
 }
 
 /* ---------------------------------------------------------------------------- */
@@ -159,12 +912,140 @@ mod tests {
         );
     }
 
+    #[cfg(all(
+        not(feature = "blas"),
+        any(target_feature = "fma", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn t_inner_product_fma_accuracy() {
+        // a * b = 1 - 2^(-54) is rounded to 1, so the naive inner product
+        // of [-1, a] and [1, b] is zero, whereas with fused multiply-add it
+        // is exact
+        let eps = 2.0_f64.powi(-27);
+        let (a, b) = (1.0 + eps, 1.0 - eps);
+        let x = [-1.0, a];
+        let y = [1.0, b];
+        let naive = x[0] * y[0] + x[1] * y[1];
+        assert_eq!(0.0, naive);
+        assert_eq!(-eps * eps, matrix_operations::inner_product(&x, &y));
+    }
+
     #[test]
     #[should_panic]
     fn t_inner_product_test_panic() {
         matrix_operations::inner_product(&[2.0, 3.0], &[1.0, 2.0, 3.0]);
     }
 
+    #[test]
+    fn t_clamp_into_both_bounds() {
+        let lo = [-1.0, 0.0, 2.0, f64::NEG_INFINITY];
+        let hi = [1.0, 0.5, 3.0, 4.0];
+        let mut x = [-2.0, 0.25, 5.0, -1e10];
+        matrix_operations::clamp_into(&mut x, Some(&lo), Some(&hi));
+        assert_eq!([-1.0, 0.25, 3.0, -1e10], x);
+    }
+
+    #[test]
+    fn t_clamp_into_lower_only() {
+        let lo = [-1.0, 0.0, 2.0];
+        let mut x = [-2.0, 0.25, 5.0];
+        matrix_operations::clamp_into(&mut x, Some(&lo), None);
+        assert_eq!([-1.0, 0.25, 5.0], x);
+    }
+
+    #[test]
+    fn t_clamp_into_upper_only() {
+        let hi = [1.0, 0.0, f64::INFINITY];
+        let mut x = [-2.0, 0.25, 5.0];
+        matrix_operations::clamp_into(&mut x, None, Some(&hi));
+        assert_eq!([-2.0, 0.0, 5.0], x);
+    }
+
+    #[test]
+    fn t_clamp_into_matches_rectangle() {
+        use crate::constraints::{Constraint, Rectangle};
+        let lo = [-1.0, 0.0, 2.0, -0.5];
+        let hi = [1.0, 0.5, 3.0, 0.5];
+        let x0 = [-2.0, 0.1 + 0.2, 2.9999999999999996, 0.5000000000000001];
+        let check = |lo: Option<&[f64]>, hi: Option<&[f64]>| {
+            let mut x = x0;
+            matrix_operations::clamp_into(&mut x, lo, hi);
+            let mut x_rectangle = x0;
+            Rectangle::new(lo, hi).project(&mut x_rectangle);
+            assert_eq!(x_rectangle, x);
+        };
+        check(Some(&lo), Some(&hi));
+        check(Some(&lo), None);
+        check(None, Some(&hi));
+    }
+
+    #[test]
+    fn t_argmax_abs() {
+        assert_eq!(
+            Some(2),
+            matrix_operations::argmax_abs(&[1.0, -2.0, -5.0, 4.0])
+        );
+        assert_eq!(
+            Some(1),
+            matrix_operations::argmax_abs(&[1.0, -3.0, 3.0, 2.0])
+        );
+        assert_eq!(Some(0), matrix_operations::argmax_abs(&[-0.5]));
+        assert_eq!(None, matrix_operations::argmax_abs::<f64>(&[]));
+    }
+
+    #[test]
+    fn t_argmin_argmax() {
+        let x = [1.0, -2.0, 7.0, -2.0, 7.0];
+        assert_eq!(Some(1), matrix_operations::argmin(&x));
+        assert_eq!(Some(2), matrix_operations::argmax(&x));
+        assert_eq!(Some(0), matrix_operations::argmin(&[3.0_f32]));
+        assert_eq!(Some(0), matrix_operations::argmax(&[3.0_f32]));
+        assert_eq!(None, matrix_operations::argmin::<f64>(&[]));
+        assert_eq!(None, matrix_operations::argmax::<f64>(&[]));
+    }
+
+    #[test]
+    fn t_argmin_argmax_nan() {
+        let x = [f64::NAN, -1.0, f64::NAN, 2.0, -4.0];
+        assert_eq!(Some(4), matrix_operations::argmin(&x));
+        assert_eq!(Some(3), matrix_operations::argmax(&x));
+        assert_eq!(Some(4), matrix_operations::argmax_abs(&x));
+        assert_eq!(None, matrix_operations::argmax(&[f64::NAN, f64::NAN]));
+    }
+
+    #[test]
+    fn t_norm2_squared_diff_unrolled() {
+        for &n in [0, 1, 3, 15, 16, 17, 18, 19, 64, 1001].iter() {
+            let a: Vec<f64> = (0..n).map(|i| (0.37 * i as f64).sin()).collect();
+            let b: Vec<f64> = (0..n).map(|i| (1.1 * i as f64).cos() / 3.0).collect();
+            let expected = a
+                .iter()
+                .zip(b.iter())
+                .fold(0.0, |sum, (x, y)| sum + (x - y) * (x - y));
+            unit_test_utils::assert_nearly_equal(
+                expected,
+                matrix_operations::norm2_squared_diff(&a, &b),
+                1e-13,
+                1e-15,
+                "norm2_squared_diff",
+            );
+        }
+    }
+
+    #[test]
+    fn t_norm2_squared_diff_f32() {
+        let a: Vec<f32> = (0..37).map(|i| i as f32 / 7.0).collect();
+        let b = vec![1.0_f32; 37];
+        let expected: f32 = a.iter().map(|x| (x - 1.0) * (x - 1.0)).sum();
+        unit_test_utils::assert_nearly_equal(
+            expected,
+            matrix_operations::norm2_squared_diff(&a, &b),
+            1e-5,
+            1e-6,
+            "norm2_squared_diff",
+        );
+    }
+
     #[test]
     fn t_norm1_test() {
         unit_test_utils::assert_nearly_equal(
@@ -187,6 +1068,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn t_norm2_f32_overflow() {
+        let n = 16;
+        let x = vec![0.5 * f32::MAX.sqrt(); n];
+        assert!(matrix_operations::norm2_squared(&x).is_infinite());
+        let norm_x = matrix_operations::norm2(&x);
+        assert!(norm_x.is_finite());
+        unit_test_utils::assert_nearly_equal(
+            2.0 * f32::MAX.sqrt(),
+            norm_x,
+            1e-5,
+            0.0,
+            "norm2 (scaled sum)",
+        );
+    }
+
+    #[test]
+    fn t_norm2_squared_f32_large_elements() {
+        // the squared norm, f32::MAX/n, is close to the largest finite value,
+        // but no partial sum overflows
+        for &n in [1, 4, 15, 16, 17, 100].iter() {
+            let x = vec![f32::MAX.sqrt() / n as f32; n];
+            let norm_squared = matrix_operations::norm2_squared(&x);
+            assert!(norm_squared.is_finite());
+            unit_test_utils::assert_nearly_equal(
+                f32::MAX / n as f32,
+                norm_squared,
+                1e-5,
+                0.0,
+                "norm2_squared",
+            );
+        }
+
+        // 99% of the largest finite value
+        let n = 16;
+        let x = vec![(0.99 * f32::MAX / n as f32).sqrt(); n];
+        let norm_squared = matrix_operations::norm2_squared(&x);
+        unit_test_utils::assert_nearly_equal(
+            0.99 * f32::MAX,
+            norm_squared,
+            1e-5,
+            0.0,
+            "norm2_squared",
+        );
+    }
+
+    #[test]
+    fn t_norm2_infinite_element() {
+        assert!(matrix_operations::norm2(&[1.0, f64::INFINITY]).is_infinite());
+    }
+
     #[test]
     fn t_norm_inf_test() {
         unit_test_utils::assert_nearly_equal(
@@ -205,6 +1137,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn t_norm_inf_negative_entries() {
+        assert_eq!(5.5, matrix_operations::norm_inf(&[-1.0, -5.5, -3.0]));
+        assert_eq!(2.0, matrix_operations::norm_inf(&[-2.0]));
+        assert_eq!(0.0, matrix_operations::norm_inf::<f64>(&[]));
+        assert_eq!(0.5_f32, matrix_operations::norm_inf(&[0.25_f32, -0.5]));
+    }
+
     #[test]
     fn t_norm_inf_diff() {
         let x = [1.0, 2.0, 1.0];
@@ -242,4 +1182,428 @@ mod tests {
         let norm2sq = matrix_operations::norm2_squared_diff(&x, &y);
         unit_test_utils::assert_nearly_equal(190., norm2sq, 1e-10, 1e-12, "norm sq diff");
     }
+
+    #[test]
+    fn t_weighted_norm2() {
+        let x = [2.0, -5.0, 7.0, -1.0];
+        assert_eq!(
+            matrix_operations::norm2(&x),
+            matrix_operations::weighted_norm2(&x, &[1.0; 4])
+        );
+        // sqrt(0.5 * 4 + 2 * 25 + 0 * 49 + 3 * 1) = sqrt(55)
+        unit_test_utils::assert_nearly_equal(
+            55f64.sqrt(),
+            matrix_operations::weighted_norm2(&x, &[0.5, 2.0, 0.0, 3.0]),
+            1e-12,
+            1e-14,
+            "weighted norm",
+        );
+        assert_eq!(0.0, matrix_operations::weighted_norm2::<f64>(&[], &[]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_weighted_norm2_wrong_length() {
+        let _ = matrix_operations::weighted_norm2(&[1.0, 2.0], &[1.0]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn t_weighted_norm2_negative_weight() {
+        let _ = matrix_operations::weighted_norm2(&[1.0, 2.0], &[1.0, -1.0]);
+    }
+
+    #[test]
+    fn t_norm2_diff() {
+        let x = [2.0, 5.0, 7.0, -1.0];
+        let y = [4.0, 1.0, 0.0, 10.0];
+        let manual = x
+            .iter()
+            .zip(y.iter())
+            .map(|(xi, yi)| (xi - yi) * (xi - yi))
+            .sum::<f64>()
+            .sqrt();
+        assert_eq!(manual, matrix_operations::norm2_diff(&x, &y));
+        unit_test_utils::assert_nearly_equal(
+            190f64.sqrt(),
+            matrix_operations::norm2_diff(&x, &y),
+            1e-10,
+            1e-12,
+            "norm diff",
+        );
+        assert_eq!(
+            matrix_operations::norm2(&x),
+            matrix_operations::norm2_diff(&x, &[0.0; 4])
+        );
+    }
+
+    #[test]
+    fn t_norm_p() {
+        let x = [1.0, -2.0, 3.0];
+        unit_test_utils::assert_nearly_equal(
+            36f64.cbrt(),
+            matrix_operations::norm_p(&x, 3.0),
+            1e-12,
+            1e-14,
+            "3-norm",
+        );
+        unit_test_utils::assert_nearly_equal(
+            (1.0f64 + 2.0f64.powf(1.5) + 3.0f64.powf(1.5)).powf(1.0 / 1.5),
+            matrix_operations::norm_p(&x, 1.5),
+            1e-12,
+            1e-14,
+            "1.5-norm",
+        );
+        unit_test_utils::assert_nearly_equal(
+            0.0,
+            matrix_operations::norm_p(&[0.0, 0.0], 3.0),
+            1e-12,
+            1e-14,
+            "3-norm of zero",
+        );
+    }
+
+    #[test]
+    fn t_norm_p_special_values() {
+        let x = [1.0, -8.0, 3.0, 0.5];
+        assert_eq!(
+            matrix_operations::norm1(&x),
+            matrix_operations::norm_p(&x, 1.0)
+        );
+        assert_eq!(
+            matrix_operations::norm2(&x),
+            matrix_operations::norm_p(&x, 2.0)
+        );
+        assert_eq!(
+            matrix_operations::norm_inf(&x),
+            matrix_operations::norm_p(&x, f64::INFINITY)
+        );
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::norm_inf(&x),
+            matrix_operations::norm_p(&x, 200.0),
+            1e-8,
+            1e-10,
+            "large p",
+        );
+    }
+
+    #[test]
+    fn t_norm_p_no_overflow() {
+        let x = [3e30f32, 4e30f32];
+        let norm = matrix_operations::norm_p(&x, 3.0);
+        assert!(norm.is_finite());
+        unit_test_utils::assert_nearly_equal(
+            91f32.cbrt() * 1e30,
+            norm,
+            1e-5,
+            1e20,
+            "3-norm of large vector",
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_norm_p_wrong_p() {
+        let _ = matrix_operations::norm_p(&[1.0, 2.0], 0.5);
+    }
+
+    #[test]
+    fn t_axpy() {
+        let mut y = [1.0, -2.0, 0.5];
+        let x = [2.0, 4.0, -1.0];
+        matrix_operations::axpy(&mut y, -0.5, &x);
+        unit_test_utils::assert_nearly_equal_array(&[0.0, -4.0, 1.0], &y, 1e-12, 1e-14, "y");
+        matrix_operations::axpy(&mut y, 0.0, &x);
+        unit_test_utils::assert_nearly_equal_array(&[0.0, -4.0, 1.0], &y, 1e-12, 1e-14, "y");
+        matrix_operations::axpy::<f64>(&mut [], 3.0, &[]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_axpy_wrong_dimensions() {
+        let mut y = [1.0, 2.0, 3.0];
+        matrix_operations::axpy(&mut y, 1.0, &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn t_add_sub_scaled_in_place() {
+        let u = [1.0, -2.0, 0.5, 3.25];
+        let w = [2.0, 4.0, -1.0, 0.1];
+        let gamma = 0.3;
+
+        let mut u_minus = u;
+        matrix_operations::sub_scaled_in_place(&mut u_minus, gamma, &w);
+        let mut u_plus = u;
+        matrix_operations::add_scaled_in_place(&mut u_plus, gamma, &w);
+        for i in 0..u.len() {
+            let mut u_minus_i = u[i];
+            u_minus_i -= gamma * w[i];
+            assert_eq!(u_minus_i, u_minus[i]);
+            assert_eq!(u[i] + gamma * w[i], u_plus[i]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_sub_scaled_in_place_wrong_dimensions() {
+        let mut u = [1.0, 2.0, 3.0];
+        matrix_operations::sub_scaled_in_place(&mut u, 1.0, &[1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_add_scaled_in_place_wrong_dimensions() {
+        let mut u = [1.0, 2.0];
+        matrix_operations::add_scaled_in_place(&mut u, 1.0, &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn t_hadamard_into() {
+        let mut z = [0.0; 3];
+        matrix_operations::hadamard_into(&mut z, &[1.0, -2.0, 0.5], &[2.0, 4.0, -1.0]);
+        assert_eq!([2.0, -8.0, -0.5], z);
+
+        let mut z_f32 = [0.0_f32; 3];
+        matrix_operations::hadamard_into(&mut z_f32, &[1.5, -2.0, 3.0], &[2.0, 0.25, -1.0]);
+        assert_eq!([3.0, -0.5, -3.0], z_f32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_hadamard_into_wrong_dimensions() {
+        let mut z = [0.0; 3];
+        matrix_operations::hadamard_into(&mut z, &[1.0, 2.0, 3.0], &[1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_hadamard_into_wrong_dimensions_f32() {
+        let mut z = [0.0_f32; 2];
+        matrix_operations::hadamard_into(&mut z, &[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn t_scale_in_place() {
+        let mut x = [1.0, -2.0, 0.5];
+        matrix_operations::scale_in_place(&mut x, -2.0);
+        assert_eq!([-2.0, 4.0, -1.0], x);
+
+        let mut x_f32 = [1.0_f32, -2.0, 0.5];
+        matrix_operations::scale_in_place(&mut x_f32, 0.5);
+        assert_eq!([0.5, -1.0, 0.25], x_f32);
+        matrix_operations::scale_in_place::<f32>(&mut [], 3.0);
+    }
+
+    #[test]
+    fn t_sum_kahan() {
+        let x: Vec<f32> = (0..1000)
+            .map(|i| match i % 4 {
+                0 => 1e8,
+                2 => -1e8,
+                _ => 1.0,
+            })
+            .collect();
+        assert_eq!(500.0, matrix_operations::sum_kahan(&x));
+        assert_ne!(500.0, matrix_operations::sum(&x));
+        assert_eq!(0.0, matrix_operations::sum_kahan::<f64>(&[]));
+        assert_eq!(6.0, matrix_operations::sum_kahan(&[1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn t_inner_product_kahan() {
+        // the products are 1e8, 1, -1e8, 1, 1e8, 1, -1e8, 1, ...
+        let a: Vec<f32> = (0..1000)
+            .map(|i| match i % 4 {
+                0 => 1e4,
+                2 => -1e4,
+                _ => 1.0,
+            })
+            .collect();
+        let b: Vec<f32> = (0..1000)
+            .map(|i| if i % 2 == 0 { 1e4 } else { 1.0 })
+            .collect();
+        assert_eq!(500.0, matrix_operations::inner_product_kahan(&a, &b));
+        assert_ne!(500.0, matrix_operations::inner_product(&a, &b));
+        assert_eq!(
+            32.0,
+            matrix_operations::inner_product_kahan(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0])
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn t_inner_product_kahan_wrong_dimensions() {
+        let _ = matrix_operations::inner_product_kahan(&[1.0, 2.0], &[1.0]);
+    }
+
+    #[test]
+    fn t_normalize_in_place() {
+        let mut x = [3.0, 0.0, -4.0];
+        let norm_x = matrix_operations::normalize_in_place(&mut x, 1e-12);
+        unit_test_utils::assert_nearly_equal(5.0, norm_x, 1e-14, 1e-14, "norm");
+        unit_test_utils::assert_nearly_equal_array(&[0.6, 0.0, -0.8], &x, 1e-14, 1e-14, "x");
+
+        // a near-zero vector is left unchanged
+        let mut x = [1e-14, -1e-14];
+        assert_eq!(0.0, matrix_operations::normalize_in_place(&mut x, 1e-12));
+        assert_eq!([1e-14, -1e-14], x);
+
+        let mut x = [0.0_f64; 3];
+        assert_eq!(0.0, matrix_operations::normalize_in_place(&mut x, 0.0));
+        assert_eq!([0.0; 3], x);
+    }
+
+    #[test]
+    fn t_sum_checked() {
+        assert_eq!(Ok(6.0), matrix_operations::sum_checked(&[1.0, 2.0, 3.0]));
+        assert_eq!(
+            Err(SolverError::NotFiniteComputation),
+            matrix_operations::sum_checked(&[1.0, f64::NAN, 3.0])
+        );
+        assert_eq!(
+            Err(SolverError::NotFiniteComputation),
+            matrix_operations::sum_checked(&[f64::INFINITY, f64::NEG_INFINITY])
+        );
+        // the entries are finite, but their sum overflows
+        assert_eq!(
+            Err(SolverError::NotFiniteComputation),
+            matrix_operations::sum_checked(&[f64::MAX, f64::MAX])
+        );
+    }
+
+    #[test]
+    fn t_norm2_checked() {
+        assert_eq!(Ok(5.0), matrix_operations::norm2_checked(&[3.0, -4.0]));
+        assert_eq!(Ok(0.0), matrix_operations::norm2_checked::<f64>(&[]));
+        assert_eq!(
+            Err(SolverError::NotFiniteComputation),
+            matrix_operations::norm2_checked(&[3.0, f64::NAN])
+        );
+        assert_eq!(
+            Err(SolverError::NotFiniteComputation),
+            matrix_operations::norm2_checked(&[f64::NEG_INFINITY, 1.0])
+        );
+    }
+
+    #[test]
+    fn t_first_non_finite() {
+        assert_eq!(None, matrix_operations::first_non_finite(&[1.0, -2.0, 3.0]));
+        assert_eq!(None, matrix_operations::first_non_finite::<f64>(&[]));
+        assert_eq!(
+            Some(2),
+            matrix_operations::first_non_finite(&[1.0, -2.0, f64::NAN, 4.0, f64::NAN])
+        );
+        assert_eq!(
+            Some(3),
+            matrix_operations::first_non_finite(&[1.0, -2.0, 3.0, f64::INFINITY])
+        );
+        assert_eq!(
+            Some(0),
+            matrix_operations::first_non_finite(&[f64::NEG_INFINITY, 1.0])
+        );
+        assert!(!matrix_operations::is_finite(&[
+            1.0,
+            -2.0,
+            3.0,
+            f64::INFINITY
+        ]));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn t_parallel_vs_serial() {
+        let n = 100_000;
+        assert!(n >= matrix_operations::parallel_threshold());
+        let a: Vec<f64> = (0..n).map(|i| (0.001 * i as f64).sin()).collect();
+        let b: Vec<f64> = (0..n).map(|i| 1.0 / (1.0 + i as f64)).collect();
+
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::inner_product_serial(&a, &b),
+            matrix_operations::inner_product(&a, &b),
+            1e-12,
+            1e-14,
+            "inner product",
+        );
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::sum_serial(&a),
+            matrix_operations::sum(&a),
+            1e-12,
+            1e-14,
+            "sum",
+        );
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::norm2_squared_serial(&a),
+            matrix_operations::norm2_squared(&a),
+            1e-12,
+            1e-14,
+            "norm2 squared",
+        );
+
+        // the reduction is deterministic
+        assert_eq!(
+            matrix_operations::sum(&a).to_bits(),
+            matrix_operations::sum(&a).to_bits()
+        );
+
+        let mut y = b.clone();
+        let mut y_serial = b.clone();
+        matrix_operations::axpy(&mut y, -0.5, &a);
+        matrix_operations::axpy_serial(&mut y_serial, -0.5, &a);
+        assert_eq!(y_serial, y);
+    }
+    #[cfg(feature = "blas")]
+    #[test]
+    fn t_blas_vs_pure() {
+        let n = 1000;
+        let a: Vec<f64> = (0..n).map(|i| (0.01 * i as f64).sin()).collect();
+        let b: Vec<f64> = (0..n).map(|i| 1.0 / (1.0 + i as f64)).collect();
+        let pure_norm1: f64 = a.iter().map(|x| x.abs()).sum();
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::inner_product_serial(&a, &b),
+            matrix_operations::inner_product(&a, &b),
+            1e-12,
+            1e-14,
+            "inner product (f64)",
+        );
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::norm2_squared_serial(&a).sqrt(),
+            matrix_operations::norm2(&a),
+            1e-12,
+            1e-14,
+            "norm2 (f64)",
+        );
+        unit_test_utils::assert_nearly_equal(
+            pure_norm1,
+            matrix_operations::norm1(&a),
+            1e-12,
+            1e-14,
+            "norm1 (f64)",
+        );
+
+        let a32: Vec<f32> = a.iter().map(|&x| x as f32).collect();
+        let b32: Vec<f32> = b.iter().map(|&x| x as f32).collect();
+        let pure_norm1_32: f32 = a32.iter().map(|x| x.abs()).sum();
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::inner_product_serial(&a32, &b32),
+            matrix_operations::inner_product(&a32, &b32),
+            1e-5,
+            1e-6,
+            "inner product (f32)",
+        );
+        unit_test_utils::assert_nearly_equal(
+            matrix_operations::norm2_squared_serial(&a32).sqrt(),
+            matrix_operations::norm2(&a32),
+            1e-5,
+            1e-6,
+            "norm2 (f32)",
+        );
+        unit_test_utils::assert_nearly_equal(
+            pure_norm1_32,
+            matrix_operations::norm1(&a32),
+            1e-5,
+            1e-6,
+            "norm1 (f32)",
+        );
+    }
 }
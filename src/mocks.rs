@@ -87,7 +87,7 @@ pub fn hard_quadratic_cost<T: OptFloat>(u: &[T], cost: &mut T) -> Result<(), Sol
 }
 
 pub fn hard_quadratic_gradient<T: OptFloat>(u: &[T], grad: &mut [T]) -> Result<(), SolverError> {
-    // norm(Hessian) = 1000.653 (Lipschitz gradient)
+    // norm(Hessian) = 1001.653 (Lipschitz gradient)
     grad[0] = T::from(4.0).unwrap() * u[0]
         + T::from(5.0).unwrap() * u[1]
         + T::from(25.0).unwrap() * u[2]